@@ -0,0 +1,16 @@
+-- src-tauri/migrations/0005_audit_exports.sql
+PRAGMA foreign_keys = ON;
+
+CREATE TABLE IF NOT EXISTS audit_exports (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  export_id TEXT NOT NULL UNIQUE,
+  export_dir TEXT NOT NULL,
+  from_ts INTEGER,
+  to_ts INTEGER,
+  audit_rows INTEGER NOT NULL,
+  login_rows INTEGER NOT NULL,
+  anchor TEXT NOT NULL, -- export anchor; seeds the next export's hash chain
+  signature TEXT NOT NULL,
+  created_by TEXT,
+  created_at INTEGER NOT NULL
+);
@@ -0,0 +1,29 @@
+-- src-tauri/migrations/0006_audit_retention.sql
+PRAGMA foreign_keys = ON;
+
+CREATE TABLE IF NOT EXISTS retention_policies (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  table_name TEXT NOT NULL, -- audit_logs | login_history | failed_logins | recent_access
+  action_class TEXT NOT NULL DEFAULT '*', -- audit_logs only: auth | admin | file | index | *
+  retain_days INTEGER NOT NULL,
+  updated_by TEXT,
+  updated_at INTEGER NOT NULL,
+  UNIQUE(table_name, action_class)
+);
+
+CREATE TABLE IF NOT EXISTS audit_archives (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  archive_id TEXT NOT NULL UNIQUE,
+  table_name TEXT NOT NULL,
+  action_class TEXT NOT NULL,
+  file_path TEXT NOT NULL, -- <data dir>/archives/*.jsonl.gz (+ .sig.json sidecar)
+  row_count INTEGER NOT NULL,
+  first_id INTEGER,
+  last_id INTEGER,
+  cutoff_ts INTEGER NOT NULL,
+  sha256 TEXT NOT NULL,
+  signature TEXT NOT NULL,
+  created_by TEXT,
+  created_at INTEGER NOT NULL,
+  restored_path TEXT -- read-only sqlite view built by admin_restore_audit_archive
+);
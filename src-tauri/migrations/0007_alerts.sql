@@ -0,0 +1,44 @@
+-- src-tauri/migrations/0007_alerts.sql
+PRAGMA foreign_keys = ON;
+
+CREATE TABLE IF NOT EXISTS app_settings (
+  key TEXT PRIMARY KEY,
+  value TEXT NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS alert_rules (
+  rule_key TEXT PRIMARY KEY, -- mass_delete | open_burst | off_hours | repeated_denials | new_drive
+  enabled INTEGER NOT NULL DEFAULT 1,
+  severity TEXT NOT NULL, -- low | medium | high | critical
+  threshold INTEGER NOT NULL DEFAULT 0,
+  window_minutes INTEGER NOT NULL DEFAULT 0,
+  params TEXT, -- JSON, e.g. duty hours for off_hours
+  updated_by TEXT,
+  updated_at INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS alerts (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  rule_key TEXT NOT NULL,
+  severity TEXT NOT NULL,
+  actor_user_id INTEGER,
+  actor_username TEXT,
+  summary TEXT NOT NULL,
+  details TEXT, -- JSON
+  first_event_id INTEGER, -- audit_logs.id range that triggered the alert
+  last_event_id INTEGER,
+  created_at INTEGER NOT NULL,
+  acknowledged INTEGER NOT NULL DEFAULT 0,
+  acknowledged_by TEXT,
+  acknowledged_at INTEGER,
+  ack_note TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_alerts_rule_created ON alerts(rule_key, created_at);
+CREATE INDEX IF NOT EXISTS idx_alerts_ack_created ON alerts(acknowledged, created_at);
+
+CREATE TABLE IF NOT EXISTS alert_seen_drives (
+  drive TEXT PRIMARY KEY,
+  first_seen_at INTEGER NOT NULL,
+  first_event_id INTEGER
+);
@@ -0,0 +1,14 @@
+-- src-tauri/migrations/0008_audit_forward_queue.sql
+PRAGMA foreign_keys = ON;
+
+-- disk-backed retry queue for syslog/CEF forwarding (config lives in app_settings)
+CREATE TABLE IF NOT EXISTS audit_forward_queue (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  audit_id INTEGER NOT NULL,
+  payload TEXT NOT NULL, -- JSON snapshot of the audit_logs row
+  enqueued_at INTEGER NOT NULL,
+  attempts INTEGER NOT NULL DEFAULT 0,
+  next_attempt_at INTEGER NOT NULL,
+  last_error TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_audit_forward_queue_next ON audit_forward_queue(next_attempt_at);
@@ -0,0 +1,41 @@
+-- src-tauri/migrations/0009_file_history.sql
+PRAGMA foreign_keys = ON;
+
+-- structured audit targets for file actions
+ALTER TABLE audit_logs ADD COLUMN target_type TEXT;       -- 'file' | 'dir'
+ALTER TABLE audit_logs ADD COLUMN target_path TEXT;
+ALTER TABLE audit_logs ADD COLUMN target_dest_path TEXT;  -- move/rename/copy destination
+ALTER TABLE audit_logs ADD COLUMN target_file_id INTEGER; -- file_identities.id
+ALTER TABLE audit_logs ADD COLUMN target_dest_file_id INTEGER; -- identity created by a copy
+CREATE INDEX IF NOT EXISTS idx_audit_logs_target_file ON audit_logs(target_file_id);
+CREATE INDEX IF NOT EXISTS idx_audit_logs_target_dest_file ON audit_logs(target_dest_file_id);
+
+-- stable identity of a file across renames and moves
+CREATE TABLE IF NOT EXISTS file_identities (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  current_path TEXT NOT NULL,
+  current_key TEXT NOT NULL, -- normalized for lookups (separators, case on Windows)
+  kind TEXT NOT NULL DEFAULT 'file',
+  volume_dev INTEGER,        -- unix st_dev
+  inode INTEGER,             -- unix st_ino
+  origin_file_id INTEGER,    -- source identity when created by a copy
+  first_seen_at INTEGER NOT NULL,
+  last_seen_at INTEGER NOT NULL,
+  deleted_at INTEGER
+);
+CREATE INDEX IF NOT EXISTS idx_file_identities_key ON file_identities(current_key, deleted_at);
+CREATE INDEX IF NOT EXISTS idx_file_identities_inode ON file_identities(volume_dev, inode);
+CREATE INDEX IF NOT EXISTS idx_file_identities_origin ON file_identities(origin_file_id);
+
+CREATE TABLE IF NOT EXISTS file_path_history (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  file_id INTEGER NOT NULL,
+  path TEXT NOT NULL,
+  path_key TEXT NOT NULL,
+  valid_from INTEGER NOT NULL,
+  valid_to INTEGER,
+  audit_id INTEGER, -- audit row that moved the file here (NULL for external moves)
+  FOREIGN KEY(file_id) REFERENCES file_identities(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_file_path_history_file ON file_path_history(file_id, valid_from);
+CREATE INDEX IF NOT EXISTS idx_file_path_history_key ON file_path_history(path_key);
@@ -0,0 +1,35 @@
+-- src-tauri/migrations/0010_file_content.sql
+PRAGMA foreign_keys = ON;
+
+-- files_index_fts becomes a regular FTS5 table so it can hold document text;
+-- rows are kept in sync with files_index by the files_index_a* triggers
+DROP TRIGGER IF EXISTS files_index_ai;
+DROP TRIGGER IF EXISTS files_index_ad;
+DROP TRIGGER IF EXISTS files_index_au;
+DROP TABLE IF EXISTS files_index_fts;
+CREATE VIRTUAL TABLE files_index_fts USING fts5(name, path, body);
+INSERT INTO files_index_fts(rowid, name, path, body) SELECT id, name, path, '' FROM files_index;
+
+-- per-file outcome of text extraction
+CREATE TABLE IF NOT EXISTS file_content (
+  file_id INTEGER PRIMARY KEY,   -- files_index.id
+  extractor TEXT NOT NULL,       -- pdf | docx | xlsx | pptx | legacy_office | text
+  status TEXT NOT NULL,          -- ok | empty | too_large | timeout | error
+  chars INTEGER NOT NULL DEFAULT 0,
+  error TEXT,
+  source_size INTEGER,           -- size when extracted; a change triggers re-extraction
+  source_mtime INTEGER,
+  extracted_at INTEGER NOT NULL
+);
+
+CREATE TRIGGER IF NOT EXISTS files_index_ai AFTER INSERT ON files_index BEGIN
+  INSERT INTO files_index_fts(rowid, name, path, body) VALUES (new.id, new.name, new.path, '');
+END;
+CREATE TRIGGER IF NOT EXISTS files_index_ad AFTER DELETE ON files_index BEGIN
+  DELETE FROM files_index_fts WHERE rowid = old.id;
+  DELETE FROM file_content WHERE file_id = old.id;
+END;
+CREATE TRIGGER IF NOT EXISTS files_index_au AFTER UPDATE OF name, path ON files_index
+WHEN old.name IS NOT new.name OR old.path IS NOT new.path BEGIN
+  UPDATE files_index_fts SET name = new.name, path = new.path WHERE rowid = old.id;
+END;
@@ -0,0 +1,18 @@
+-- src-tauri/migrations/0011_ocr.sql
+PRAGMA foreign_keys = ON;
+
+-- OCR outcome per indexed file (NULL = not attempted yet)
+ALTER TABLE file_content ADD COLUMN ocr_status TEXT;  -- ok | empty | not_needed | error | timeout
+ALTER TABLE file_content ADD COLUMN ocr_sha256 TEXT;  -- ocr_cache key used
+
+-- OCR results keyed by content hash, so identical files are OCR'd once
+CREATE TABLE IF NOT EXISTS ocr_cache (
+  sha256 TEXT PRIMARY KEY,
+  status TEXT NOT NULL,
+  text TEXT NOT NULL DEFAULT '',
+  languages TEXT NOT NULL,   -- tesseract -l value used
+  pages INTEGER NOT NULL DEFAULT 0,
+  error TEXT,
+  duration_ms INTEGER NOT NULL DEFAULT 0,
+  created_at INTEGER NOT NULL
+);
@@ -0,0 +1,10 @@
+-- src-tauri/migrations/0012_incremental_index.sql
+PRAGMA foreign_keys = ON;
+
+-- change detection for incremental re-indexing
+ALTER TABLE files_index ADD COLUMN parent TEXT;   -- containing directory (per-directory reconcile)
+ALTER TABLE files_index ADD COLUMN mtime INTEGER; -- modification time, epoch seconds
+ALTER TABLE files_index ADD COLUMN ctime INTEGER; -- unix: inode change time; Windows: creation time
+ALTER TABLE files_index ADD COLUMN inode INTEGER; -- unix inode (NULL on Windows)
+CREATE INDEX IF NOT EXISTS idx_files_index_parent ON files_index(parent);
+-- existing rows get `parent` filled in by the app on first start
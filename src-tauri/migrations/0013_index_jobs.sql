@@ -0,0 +1,27 @@
+-- src-tauri/migrations/0013_index_jobs.sql
+PRAGMA foreign_keys = ON;
+
+-- background index runs, checkpointed so they survive restarts
+CREATE TABLE IF NOT EXISTS index_jobs (
+  id TEXT PRIMARY KEY,               -- job UUID returned to the frontend
+  kind TEXT NOT NULL,                -- 'path' | 'all_drives'
+  roots TEXT NOT NULL,               -- JSON array of root directories
+  status TEXT NOT NULL,              -- running | finished | failed
+  processed INTEGER NOT NULL DEFAULT 0,
+  added INTEGER NOT NULL DEFAULT 0,
+  updated INTEGER NOT NULL DEFAULT 0,
+  unchanged INTEGER NOT NULL DEFAULT 0,
+  removed INTEGER NOT NULL DEFAULT 0,
+  last_path TEXT,
+  pending TEXT,                      -- JSON array: directories still to walk (last checkpoint)
+  error TEXT,
+  started_by INTEGER,
+  started_by_username TEXT,
+  started_at INTEGER NOT NULL,
+  checkpoint_at INTEGER,
+  finished_at INTEGER,
+  active_ms INTEGER NOT NULL DEFAULT 0, -- walking time summed over resumes
+  resumes INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_index_jobs_status ON index_jobs(status);
+CREATE INDEX IF NOT EXISTS idx_index_jobs_started ON index_jobs(started_at);
@@ -0,0 +1,24 @@
+-- src-tauri/migrations/0014_index_rules.sql
+PRAGMA foreign_keys = ON;
+
+-- admin include/exclude rules for the indexer (on top of the built-in exclusions)
+CREATE TABLE IF NOT EXISTS index_rules (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  action TEXT NOT NULL,              -- include | exclude
+  syntax TEXT NOT NULL,              -- glob | regex (matched against the full path, '/' separators)
+  pattern TEXT NOT NULL,
+  root TEXT,                         -- only paths under this root; NULL = everywhere
+  enabled INTEGER NOT NULL DEFAULT 1,
+  note TEXT,
+  created_by_username TEXT,
+  created_at INTEGER NOT NULL
+);
+
+-- depth / file size limits; root '' is the default for all roots
+CREATE TABLE IF NOT EXISTS index_limits (
+  root TEXT PRIMARY KEY,
+  max_depth INTEGER,
+  max_file_size INTEGER,             -- bytes
+  updated_by_username TEXT,
+  updated_at INTEGER NOT NULL
+);
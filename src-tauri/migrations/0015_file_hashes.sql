@@ -0,0 +1,19 @@
+-- src-tauri/migrations/0015_file_hashes.sql
+PRAGMA foreign_keys = ON;
+
+-- content hash per indexed file, recomputed when size or mtime changes
+CREATE TABLE IF NOT EXISTS file_hashes (
+  file_id INTEGER PRIMARY KEY,       -- files_index.id
+  algorithm TEXT NOT NULL,           -- blake3 | sha256
+  hash TEXT,                         -- lowercase hex; NULL when status = 'error'
+  status TEXT NOT NULL,              -- ok | error
+  error TEXT,
+  source_size INTEGER,               -- size/mtime the hash was computed from
+  source_mtime INTEGER,
+  hashed_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_file_hashes_hash ON file_hashes(hash);
+
+CREATE TRIGGER IF NOT EXISTS files_index_hash_ad AFTER DELETE ON files_index BEGIN
+  DELETE FROM file_hashes WHERE file_id = old.id;
+END;
@@ -0,0 +1,18 @@
+-- src-tauri/migrations/0016_duplicate_removals.sql
+PRAGMA foreign_keys = ON;
+
+-- copies deleted by "keep one" duplicate resolution
+CREATE TABLE IF NOT EXISTS duplicate_removals (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  removed_path TEXT NOT NULL,
+  kept_path TEXT NOT NULL,           -- the copy that was kept
+  algorithm TEXT NOT NULL,
+  hash TEXT NOT NULL,
+  size INTEGER NOT NULL,
+  removed_by INTEGER,
+  removed_by_username TEXT,
+  removed_at INTEGER NOT NULL,
+  audit_id INTEGER                   -- the fs_delete audit row
+);
+CREATE INDEX IF NOT EXISTS idx_duplicate_removals_at ON duplicate_removals(removed_at);
+CREATE INDEX IF NOT EXISTS idx_duplicate_removals_hash ON duplicate_removals(hash);
@@ -0,0 +1,34 @@
+-- src-tauri/migrations/0017_scheduled_jobs.sql
+PRAGMA foreign_keys = ON;
+
+-- recurring background jobs (cron expression, evaluated in local time)
+CREATE TABLE IF NOT EXISTS scheduled_jobs (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,
+  kind TEXT NOT NULL,                -- rescan | audit_retention | session_cleanup | hash_verify | db_optimize
+  cron TEXT NOT NULL,
+  params TEXT NOT NULL DEFAULT '{}', -- JSON, e.g. {"root": "..."} for rescan
+  enabled INTEGER NOT NULL DEFAULT 1,
+  catch_up INTEGER NOT NULL DEFAULT 1, -- run once at startup after a missed slot
+  next_run_at INTEGER,
+  last_run_at INTEGER,
+  last_status TEXT,
+  created_by INTEGER,
+  created_by_username TEXT,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+
+-- run history; kept when the job is deleted
+CREATE TABLE IF NOT EXISTS scheduled_job_runs (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  job_id INTEGER NOT NULL,
+  kind TEXT NOT NULL,
+  trigger TEXT NOT NULL,             -- schedule | catch_up | manual
+  status TEXT NOT NULL,              -- running | succeeded | failed | skipped | interrupted
+  message TEXT,
+  scheduled_for INTEGER,
+  started_at INTEGER NOT NULL,
+  finished_at INTEGER
+);
+CREATE INDEX IF NOT EXISTS idx_scheduled_job_runs_job ON scheduled_job_runs(job_id, started_at);
@@ -0,0 +1,22 @@
+-- src-tauri/migrations/0018_volumes.sql
+PRAGMA foreign_keys = ON;
+
+-- volumes known by filesystem identity rather than mount point
+CREATE TABLE IF NOT EXISTS volumes (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  key TEXT NOT NULL UNIQUE,          -- uuid:<uuid> | label:<label> | partuuid:<id> | net:<share> | dev:<device>
+  uuid TEXT,                         -- filesystem UUID (volume serial on Windows)
+  label TEXT,
+  fs_type TEXT,
+  device TEXT,
+  mount_point TEXT,                  -- current or last mount; '[<key>]' once displaced
+  online INTEGER NOT NULL DEFAULT 0,
+  first_seen_at INTEGER NOT NULL,
+  last_seen_at INTEGER NOT NULL,
+  last_mounted_at INTEGER
+);
+
+-- index rows are stored relative to their volume so a remount can re-base them
+ALTER TABLE files_index ADD COLUMN volume_id INTEGER;
+ALTER TABLE files_index ADD COLUMN rel_path TEXT;
+CREATE INDEX IF NOT EXISTS idx_files_index_volume ON files_index(volume_id);
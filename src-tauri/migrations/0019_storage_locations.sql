@@ -0,0 +1,18 @@
+-- src-tauri/migrations/0019_storage_locations.sql
+PRAGMA foreign_keys = ON;
+
+-- named storage locations ("Ops NAS", "Training USB #3", ...), one per volume
+CREATE TABLE IF NOT EXISTS storage_locations (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+  description TEXT,
+  owner_unit TEXT,
+  access_policy TEXT NOT NULL DEFAULT 'open',  -- open | restricted (admins only)
+  volume_key TEXT NOT NULL UNIQUE,             -- volumes.key
+  available INTEGER NOT NULL DEFAULT 0,        -- mounted and readable at the last probe
+  last_seen_at INTEGER,
+  created_by INTEGER,
+  created_by_username TEXT,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
@@ -0,0 +1,6 @@
+-- src-tauri/migrations/0020_storage_history.sql
+PRAGMA foreign_keys = ON;
+
+-- storage_scans samples are keyed by volume key (or mount point when unknown)
+ALTER TABLE storage_scans ADD COLUMN mount_point TEXT;
+ALTER TABLE storage_scans ADD COLUMN label TEXT;
@@ -0,0 +1,5 @@
+-- src-tauri/migrations/0021_space_analytics.sql
+PRAGMA foreign_keys = ON;
+
+-- largest-file reports read files_index ordered by size
+CREATE INDEX IF NOT EXISTS idx_files_index_size ON files_index(size);
@@ -0,0 +1,31 @@
+-- src-tauri/migrations/0022_file_metadata.sql
+PRAGMA foreign_keys = ON;
+
+-- embedded document properties, one row per files_index row
+CREATE TABLE IF NOT EXISTS file_metadata (
+    file_id INTEGER PRIMARY KEY,
+    title TEXT,
+    author TEXT,
+    subject TEXT,
+    keywords TEXT,
+    creator_tool TEXT,
+    producer TEXT,
+    last_modified_by TEXT,
+    revision TEXT,
+    created_at INTEGER,
+    modified_at INTEGER,
+    page_count INTEGER,
+    slide_count INTEGER,
+    word_count INTEGER,
+    camera_make TEXT,
+    camera_model TEXT,
+    taken_at INTEGER,
+    gps_lat REAL,
+    gps_lon REAL,
+    extracted_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_file_metadata_created ON file_metadata(created_at);
+CREATE INDEX IF NOT EXISTS idx_file_metadata_taken ON file_metadata(taken_at);
+
+-- files_index_fts gains a `meta` column; fs_ops rebuilds it on startup and
+-- the content pass re-extracts text and metadata
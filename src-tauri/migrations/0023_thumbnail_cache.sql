@@ -0,0 +1,16 @@
+-- src-tauri/migrations/0023_thumbnail_cache.sql
+PRAGMA foreign_keys = ON;
+
+-- thumbnails cached under <data dir>/thumbnails, keyed by content hash and
+-- edge length; evicted least recently used first
+CREATE TABLE IF NOT EXISTS thumbnail_cache (
+    cache_key TEXT PRIMARY KEY,
+    file_name TEXT NOT NULL,
+    source_kind TEXT NOT NULL,
+    bytes INTEGER NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    last_access INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_thumbnail_cache_access ON thumbnail_cache(last_access);
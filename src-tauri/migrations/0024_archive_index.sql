@@ -0,0 +1,22 @@
+-- src-tauri/migrations/0024_archive_index.sql
+PRAGMA foreign_keys = ON;
+
+-- last member listing of each indexed archive (ZIP, TAR, 7z); the members
+-- themselves are files_index rows with file_type 'member' and paths like
+-- `/share/bundle.zip!/Appendix A.pdf`
+CREATE TABLE IF NOT EXISTS archive_scans (
+    file_id INTEGER PRIMARY KEY,
+    status TEXT NOT NULL,
+    members INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    source_size INTEGER,
+    source_mtime INTEGER,
+    scanned_at INTEGER NOT NULL
+);
+
+-- an archive's members go when its row does
+CREATE TRIGGER IF NOT EXISTS files_index_ad_members AFTER DELETE ON files_index
+WHEN old.file_type IS NOT 'dir' BEGIN
+  DELETE FROM archive_scans WHERE file_id = old.id;
+  DELETE FROM files_index WHERE path >= old.path || '!/' AND path < old.path || '!0';
+END;
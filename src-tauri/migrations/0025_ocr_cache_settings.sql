@@ -0,0 +1,6 @@
+-- src-tauri/migrations/0025_ocr_cache_settings.sql
+PRAGMA foreign_keys = ON;
+
+-- OCR settings a cached result was produced with (languages, dpi, page limit,
+-- tesseract version); rows from before this column never match and are redone
+ALTER TABLE ocr_cache ADD COLUMN settings TEXT NOT NULL DEFAULT '';
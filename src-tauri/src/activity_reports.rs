@@ -0,0 +1,461 @@
+// src-tauri/src/activity_reports.rs
+//
+// Per-user activity aggregates for the admin dashboard ("what did this
+// operator do this week?"). File actions are read from audit_logs and folded
+// into five categories; hour-of-day / day-of-week buckets use the machine's
+// local time, like the off-hours alert rule. The baseline is the average of
+// the same-length periods immediately preceding the requested one.
+use crate::audit::{ensure_admin_session, window_to_ts};
+use chrono::{Datelike, Local, TimeZone, Timelike, Utc};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{command, AppHandle};
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const TRACKED_ACTIONS: &str = "'open_file','open_path','fs_copy','fs_move','fs_rename','fs_delete','fs_search','fs_search_tag'";
+const DEFAULT_BASELINE_PERIODS: i64 = 4;
+const MAX_BASELINE_PERIODS: i64 = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Open,
+    Copy,
+    Move,
+    Delete,
+    Search,
+}
+
+const CATEGORIES: [Category; 5] = [
+    Category::Open,
+    Category::Copy,
+    Category::Move,
+    Category::Delete,
+    Category::Search,
+];
+
+impl Category {
+    fn from_action(action: &str) -> Option<Self> {
+        match action {
+            "open_file" | "open_path" => Some(Category::Open),
+            "fs_copy" => Some(Category::Copy),
+            "fs_move" | "fs_rename" => Some(Category::Move),
+            "fs_delete" => Some(Category::Delete),
+            "fs_search" | "fs_search_tag" => Some(Category::Search),
+            _ => None,
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Category::Open => "opens",
+            Category::Copy => "copies",
+            Category::Move => "moves",
+            Category::Delete => "deletes",
+            Category::Search => "searches",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ActivityCounts {
+    pub opens: i64,
+    pub copies: i64,
+    pub moves: i64,
+    pub deletes: i64,
+    pub searches: i64,
+    pub total: i64,
+}
+
+impl ActivityCounts {
+    fn add(&mut self, c: Category) {
+        match c {
+            Category::Open => self.opens += 1,
+            Category::Copy => self.copies += 1,
+            Category::Move => self.moves += 1,
+            Category::Delete => self.deletes += 1,
+            Category::Search => self.searches += 1,
+        }
+        self.total += 1;
+    }
+
+    fn get(&self, c: Category) -> i64 {
+        match c {
+            Category::Open => self.opens,
+            Category::Copy => self.copies,
+            Category::Move => self.moves,
+            Category::Delete => self.deletes,
+            Category::Search => self.searches,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct HourBucket {
+    pub hour: u32,
+    pub counts: ActivityCounts,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WeekdayBucket {
+    /// 0 = Monday
+    pub weekday: u32,
+    pub label: String,
+    pub counts: ActivityCounts,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FolderActivity {
+    pub folder: String,
+    pub count: i64,
+    pub last_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryDeviation {
+    pub category: String,
+    pub current: i64,
+    pub baseline_avg: f64,
+    /// None when the baseline is zero.
+    pub change_pct: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BaselineComparison {
+    pub baseline_from: i64,
+    pub baseline_to: i64,
+    pub periods: i64,
+    pub deviations: Vec<CategoryDeviation>,
+    pub total_current: i64,
+    pub total_baseline_avg: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserActivityReport {
+    pub user_id: i64,
+    pub username: String,
+    pub from_ts: i64,
+    pub to_ts: i64,
+    pub totals: ActivityCounts,
+    pub by_hour: Vec<HourBucket>,
+    pub by_weekday: Vec<WeekdayBucket>,
+    /// 7 rows (Mon..Sun) x 24 columns of total actions.
+    pub heatmap: Vec<Vec<i64>>,
+    pub top_folders: Vec<FolderActivity>,
+    pub baseline: BaselineComparison,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserActivitySummary {
+    pub user_id: i64,
+    pub username: String,
+    pub counts: ActivityCounts,
+    pub last_activity_at: Option<i64>,
+}
+
+struct ActivityRow {
+    action: String,
+    target_path: Option<String>,
+    created_at: i64,
+}
+
+fn load_rows(conn: &Connection, user_id: i64, from: i64, to: i64) -> Result<Vec<ActivityRow>, String> {
+    let sql = format!(
+        "SELECT action, target_path, created_at FROM audit_logs
+         WHERE actor_user_id = ?1 AND created_at >= ?2 AND created_at < ?3 AND action IN ({})
+         ORDER BY created_at ASC",
+        TRACKED_ACTIONS
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![user_id, from, to], |r| {
+            Ok(ActivityRow {
+                action: r.get(0)?,
+                target_path: r.get(1)?,
+                created_at: r.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(out)
+}
+
+fn count_rows(rows: &[ActivityRow]) -> ActivityCounts {
+    let mut c = ActivityCounts::default();
+    for r in rows {
+        if let Some(cat) = Category::from_action(&r.action) {
+            c.add(cat);
+        }
+    }
+    c
+}
+
+/// Parent folder of a path, accepting both separators.
+fn parent_folder(path: &str) -> Option<String> {
+    let trimmed = path.trim_end_matches(['/', '\\']);
+    let idx = trimmed.rfind(['/', '\\'])?;
+    let parent = &trimmed[..idx];
+    if parent.is_empty() {
+        Some(trimmed[..=idx].to_string())
+    } else if parent.ends_with(':') {
+        // "C:\file" -> "C:\"
+        Some(trimmed[..=idx].to_string())
+    } else {
+        Some(parent.to_string())
+    }
+}
+
+fn top_folders(rows: &[ActivityRow], limit: usize) -> Vec<FolderActivity> {
+    let mut by_folder: HashMap<String, (i64, i64)> = HashMap::new();
+    for r in rows {
+        let path = match r.target_path.as_deref() {
+            Some(p) => p,
+            None => continue,
+        };
+        let folder = match parent_folder(path) {
+            Some(f) => f,
+            None => continue,
+        };
+        let e = by_folder.entry(folder).or_insert((0, 0));
+        e.0 += 1;
+        e.1 = e.1.max(r.created_at);
+    }
+    let mut out: Vec<FolderActivity> = by_folder
+        .into_iter()
+        .map(|(folder, (count, last_at))| FolderActivity {
+            folder,
+            count,
+            last_at,
+        })
+        .collect();
+    out.sort_by(|a, b| b.count.cmp(&a.count).then(b.last_at.cmp(&a.last_at)));
+    out.truncate(limit);
+    out
+}
+
+fn build_report(
+    conn: &Connection,
+    user_id: i64,
+    username: String,
+    from: i64,
+    to: i64,
+    baseline_periods: i64,
+    folder_limit: usize,
+) -> Result<UserActivityReport, String> {
+    let rows = load_rows(conn, user_id, from, to.saturating_add(1))?;
+
+    let mut totals = ActivityCounts::default();
+    let mut by_hour: Vec<ActivityCounts> = vec![ActivityCounts::default(); 24];
+    let mut by_weekday: Vec<ActivityCounts> = vec![ActivityCounts::default(); 7];
+    let mut heatmap = vec![vec![0i64; 24]; 7];
+    for r in &rows {
+        let cat = match Category::from_action(&r.action) {
+            Some(c) => c,
+            None => continue,
+        };
+        let local = match Local.timestamp_opt(r.created_at, 0).single() {
+            Some(t) => t,
+            None => continue,
+        };
+        let hour = local.hour() as usize;
+        let wd = local.weekday().num_days_from_monday() as usize;
+        totals.add(cat);
+        by_hour[hour].add(cat);
+        by_weekday[wd].add(cat);
+        heatmap[wd][hour] += 1;
+    }
+
+    let span = to.saturating_sub(from).max(1);
+    let baseline_to = from;
+    let baseline_from = from.saturating_sub(span.saturating_mul(baseline_periods));
+    let baseline_rows = load_rows(conn, user_id, baseline_from, baseline_to)?;
+    let baseline_counts = count_rows(&baseline_rows);
+    let periods = baseline_periods.max(1) as f64;
+    let deviations = CATEGORIES
+        .iter()
+        .map(|c| {
+            let current = totals.get(*c);
+            let avg = baseline_counts.get(*c) as f64 / periods;
+            CategoryDeviation {
+                category: c.key().to_string(),
+                current,
+                baseline_avg: avg,
+                change_pct: if avg > 0.0 {
+                    Some((current as f64 - avg) / avg * 100.0)
+                } else {
+                    None
+                },
+            }
+        })
+        .collect();
+
+    Ok(UserActivityReport {
+        user_id,
+        username,
+        from_ts: from,
+        to_ts: to,
+        by_hour: by_hour
+            .into_iter()
+            .enumerate()
+            .map(|(h, counts)| HourBucket {
+                hour: h as u32,
+                counts,
+            })
+            .collect(),
+        by_weekday: by_weekday
+            .into_iter()
+            .enumerate()
+            .map(|(d, counts)| WeekdayBucket {
+                weekday: d as u32,
+                label: WEEKDAYS[d].to_string(),
+                counts,
+            })
+            .collect(),
+        heatmap,
+        top_folders: top_folders(&rows, folder_limit),
+        baseline: BaselineComparison {
+            baseline_from,
+            baseline_to,
+            periods: baseline_periods,
+            deviations,
+            total_current: totals.total,
+            total_baseline_avg: baseline_counts.total as f64 / periods,
+        },
+        totals,
+    })
+}
+
+fn activity_overview(conn: &Connection, from: i64, to: i64) -> Result<Vec<UserActivitySummary>, String> {
+    let sql = format!(
+        "SELECT u.id, u.username,
+                COALESCE(SUM(a.action IN ('open_file','open_path')), 0),
+                COALESCE(SUM(a.action = 'fs_copy'), 0),
+                COALESCE(SUM(a.action IN ('fs_move','fs_rename')), 0),
+                COALESCE(SUM(a.action = 'fs_delete'), 0),
+                COALESCE(SUM(a.action IN ('fs_search','fs_search_tag')), 0),
+                MAX(a.created_at)
+         FROM users u
+         LEFT JOIN audit_logs a
+           ON a.actor_user_id = u.id AND a.created_at >= ?1 AND a.created_at < ?2
+          AND a.action IN ({})
+         GROUP BY u.id
+         ORDER BY COUNT(a.id) DESC, u.username ASC",
+        TRACKED_ACTIONS
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![from, to], |r| {
+            let mut counts = ActivityCounts {
+                opens: r.get(2)?,
+                copies: r.get(3)?,
+                moves: r.get(4)?,
+                deletes: r.get(5)?,
+                searches: r.get(6)?,
+                total: 0,
+            };
+            counts.total = counts.opens + counts.copies + counts.moves + counts.deletes + counts.searches;
+            Ok(UserActivitySummary {
+                user_id: r.get(0)?,
+                username: r.get(1)?,
+                counts,
+                last_activity_at: r.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(out)
+}
+
+/// Opens/copies/moves/deletes/searches for one user, bucketed by hour and
+/// weekday, with top folders and a comparison against the user's baseline.
+#[command]
+pub fn admin_user_activity_report(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    user_id: Option<i64>,
+    username: Option<String>,
+    window: Option<String>,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+    baseline_periods: Option<i64>,
+    top_folders: Option<i64>,
+) -> Result<UserActivityReport, String> {
+    let conn = crate::db::open_connection()?;
+    crate::db::init_db_schema(&conn)?;
+    let (admin_id, admin_username) = ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let user = match (user_id, username) {
+        (Some(id), _) => crate::db::get_user_by_id_row(&conn, id)?,
+        (None, Some(name)) => crate::db::get_user_by_username_row(&conn, &name)?,
+        (None, None) => return Err("user_id or username required".into()),
+    }
+    .ok_or("User not found".to_string())?;
+
+    let from = from_ts.unwrap_or_else(|| window_to_ts(window.as_deref().unwrap_or("1w")));
+    let to = to_ts.unwrap_or_else(|| Utc::now().timestamp());
+    if to <= from {
+        return Err("to_ts must be after from_ts".into());
+    }
+    let periods = baseline_periods
+        .unwrap_or(DEFAULT_BASELINE_PERIODS)
+        .clamp(1, MAX_BASELINE_PERIODS);
+    let report = build_report(
+        &conn,
+        user.id,
+        user.username.clone(),
+        from,
+        to,
+        periods,
+        top_folders.unwrap_or(10).clamp(1, 100) as usize,
+    )?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_report_user_activity",
+        Some(user.id),
+        Some(&format!("Activity report for '{}' ({} actions)", user.username, report.totals.total)),
+    );
+    Ok(report)
+}
+
+/// Per-user category totals for everyone over the same window, for the
+/// dashboard's leaderboard view.
+#[command]
+pub fn admin_activity_overview(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    window: Option<String>,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+) -> Result<Vec<UserActivitySummary>, String> {
+    let conn = crate::db::open_connection()?;
+    crate::db::init_db_schema(&conn)?;
+    let (admin_id, admin_username) = ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let from = from_ts.unwrap_or_else(|| window_to_ts(window.as_deref().unwrap_or("1w")));
+    let to = to_ts.unwrap_or_else(|| Utc::now().timestamp());
+    if to <= from {
+        return Err("to_ts must be after from_ts".into());
+    }
+    let out = activity_overview(&conn, from, to.saturating_add(1))?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_report_activity_overview",
+        None,
+        Some(&format!("Activity overview for {} users", out.len())),
+    );
+    Ok(out)
+}
@@ -0,0 +1,726 @@
+// src-tauri/src/alerts.rs
+//
+// Rules engine over the audit stream. `db::insert_audit_log` schedules a
+// background pass after every insert; the pass walks audit rows past its
+// cursor (`alerts.last_audit_id` in app_settings), evaluates each enabled
+// rule and raises rows into `alerts`. A rule does not fire again for the same
+// actor while an alert of that rule is younger than its window (cooldown).
+// On first use the cursor starts at the newest audit row, so history recorded
+// before the engine existed is not replayed as alerts.
+use chrono::{Local, TimeZone, Timelike, Utc};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{command, AppHandle};
+
+const CURSOR_KEY: &str = "alerts.last_audit_id";
+const BATCH: i64 = 500;
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static RERUN: AtomicBool = AtomicBool::new(false);
+
+pub const RULE_MASS_DELETE: &str = "mass_delete";
+pub const RULE_OPEN_BURST: &str = "open_burst";
+pub const RULE_OFF_HOURS: &str = "off_hours";
+pub const RULE_REPEATED_DENIALS: &str = "repeated_denials";
+pub const RULE_NEW_DRIVE: &str = "new_drive";
+pub const RULE_DISK_FULL: &str = "disk_full";
+
+const SEVERITIES: &[&str] = &["low", "medium", "high", "critical"];
+
+// (rule_key, severity, threshold, window_minutes, params)
+const DEFAULT_RULES: &[(&str, &str, i64, i64, &str)] = &[
+    (RULE_MASS_DELETE, "high", 20, 10, "{}"),
+    (RULE_OPEN_BURST, "medium", 100, 5, "{}"),
+    (
+        RULE_OFF_HOURS,
+        "low",
+        0,
+        60,
+        r#"{"start_hour":8,"end_hour":20,"weekdays_only":false}"#,
+    ),
+    (RULE_REPEATED_DENIALS, "high", 5, 10, "{}"),
+    (RULE_NEW_DRIVE, "medium", 0, 0, "{}"),
+    // threshold is percent used; params.drives may override it per drive
+    (RULE_DISK_FULL, "high", 90, 0, r#"{"drives":{}}"#),
+];
+
+/// Only file deletions count towards mass_delete; admin_delete_* are config changes.
+const FILE_DELETE_ACTION: &str = "fs_delete";
+const DELETE_ACTIONS_SQL: &str = "(action = 'fs_delete')";
+const OPEN_ACTIONS_SQL: &str = "(action IN ('open_file', 'open_path'))";
+const DENIAL_ACTIONS_SQL: &str = "(action IN ('login_failed', 'login_rate_limited')
+    OR action LIKE '%\\_blocked' ESCAPE '\\'
+    OR action LIKE '%\\_denied' ESCAPE '\\')";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertRule {
+    pub rule_key: String,
+    pub enabled: bool,
+    pub severity: String,
+    pub threshold: i64,
+    pub window_minutes: i64,
+    pub params: serde_json::Value,
+    pub updated_by: Option<String>,
+    pub updated_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Alert {
+    pub id: i64,
+    pub rule_key: String,
+    pub severity: String,
+    pub actor_user_id: Option<i64>,
+    pub actor_username: Option<String>,
+    pub summary: String,
+    pub details: Option<String>,
+    pub first_event_id: Option<i64>,
+    pub last_event_id: Option<i64>,
+    pub created_at: i64,
+    pub acknowledged: bool,
+    pub acknowledged_by: Option<String>,
+    pub acknowledged_at: Option<i64>,
+    pub ack_note: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct AuditEvent {
+    id: i64,
+    actor_user_id: Option<i64>,
+    actor_username: Option<String>,
+    action: String,
+    details: Option<String>,
+    created_at: i64,
+}
+
+// -----------------------------------------------------------------------------
+// Rules
+// -----------------------------------------------------------------------------
+
+fn ensure_default_rules(conn: &Connection) -> Result<(), String> {
+    for (key, severity, threshold, window, params_json) in DEFAULT_RULES {
+        conn.execute(
+            "INSERT OR IGNORE INTO alert_rules (rule_key, enabled, severity, threshold, window_minutes, params)
+             VALUES (?1, 1, ?2, ?3, ?4, ?5)",
+            params![key, severity, threshold, window, params_json],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+pub fn list_rules(conn: &Connection) -> Result<Vec<AlertRule>, String> {
+    ensure_default_rules(conn)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT rule_key, enabled, severity, threshold, window_minutes, params, updated_by, updated_at
+             FROM alert_rules ORDER BY rule_key",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |r| {
+            let params_text: Option<String> = r.get(5)?;
+            Ok(AlertRule {
+                rule_key: r.get(0)?,
+                enabled: r.get::<_, i64>(1)? != 0,
+                severity: r.get(2)?,
+                threshold: r.get(3)?,
+                window_minutes: r.get(4)?,
+                params: params_text
+                    .and_then(|t| serde_json::from_str(&t).ok())
+                    .unwrap_or(serde_json::Value::Null),
+                updated_by: r.get(6)?,
+                updated_at: r.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(out)
+}
+
+/// Actor predicate for window queries (user id when known, otherwise the username).
+fn actor_filter(ev: &AuditEvent) -> Option<(String, rusqlite::types::Value)> {
+    if let Some(uid) = ev.actor_user_id {
+        Some(("actor_user_id = ?".into(), rusqlite::types::Value::from(uid)))
+    } else {
+        ev.actor_username.as_ref().map(|u| {
+            (
+                "actor_username = ?".to_string(),
+                rusqlite::types::Value::from(u.clone()),
+            )
+        })
+    }
+}
+
+/// Count this actor's events matching `action_sql` in the window ending at `ev`.
+fn count_in_window(
+    conn: &Connection,
+    ev: &AuditEvent,
+    action_sql: &str,
+    window_minutes: i64,
+) -> Result<(i64, Option<i64>), String> {
+    let (actor_sql, actor_val) = match actor_filter(ev) {
+        Some(a) => a,
+        None => return Ok((0, None)),
+    };
+    let sql = format!(
+        "SELECT COUNT(*), MIN(id) FROM audit_logs
+         WHERE id <= ? AND created_at >= ? AND {} AND {}",
+        actor_sql, action_sql
+    );
+    let since = ev.created_at - window_minutes * 60;
+    let values = vec![
+        rusqlite::types::Value::from(ev.id),
+        rusqlite::types::Value::from(since),
+        actor_val,
+    ];
+    conn.query_row(&sql, rusqlite::params_from_iter(values), |r| {
+        Ok((r.get::<_, i64>(0)?, r.get::<_, Option<i64>>(1)?))
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// True when the same rule already fired for this actor within the cooldown.
+fn in_cooldown(
+    conn: &Connection,
+    rule: &AlertRule,
+    ev: &AuditEvent,
+    cooldown_minutes: i64,
+) -> Result<bool, String> {
+    let since = ev.created_at - cooldown_minutes.max(1) * 60;
+    let n: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM alerts
+             WHERE rule_key = ?1 AND created_at >= ?2
+               AND COALESCE(actor_user_id, -1) = COALESCE(?3, -1)
+               AND COALESCE(actor_username, '') = COALESCE(?4, '')",
+            params![rule.rule_key, since, ev.actor_user_id, ev.actor_username],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(n > 0)
+}
+
+fn raise(
+    conn: &Connection,
+    rule: &AlertRule,
+    ev: &AuditEvent,
+    summary: String,
+    details: serde_json::Value,
+    first_event_id: Option<i64>,
+) -> Result<i64, String> {
+    conn.execute(
+        "INSERT INTO alerts (rule_key, severity, actor_user_id, actor_username, summary, details, first_event_id, last_event_id, created_at, acknowledged)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 0)",
+        params![
+            rule.rule_key,
+            rule.severity,
+            ev.actor_user_id,
+            ev.actor_username,
+            summary,
+            details.to_string(),
+            first_event_id.unwrap_or(ev.id),
+            ev.id,
+            ev.created_at
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(conn.last_insert_rowid())
+}
+
+fn actor_label(ev: &AuditEvent) -> String {
+    ev.actor_username
+        .clone()
+        .or_else(|| ev.actor_user_id.map(|id| format!("user #{}", id)))
+        .unwrap_or_else(|| "unknown".into())
+}
+
+fn check_threshold(
+    conn: &Connection,
+    rule: &AlertRule,
+    ev: &AuditEvent,
+    action_sql: &str,
+    what: &str,
+) -> Result<(), String> {
+    let (count, first_id) = count_in_window(conn, ev, action_sql, rule.window_minutes)?;
+    if count > rule.threshold && !in_cooldown(conn, rule, ev, rule.window_minutes)? {
+        raise(
+            conn,
+            rule,
+            ev,
+            format!(
+                "{}: {} {} in {} minutes (limit {})",
+                actor_label(ev),
+                count,
+                what,
+                rule.window_minutes,
+                rule.threshold
+            ),
+            serde_json::json!({
+                "count": count,
+                "threshold": rule.threshold,
+                "window_minutes": rule.window_minutes,
+                "last_action": ev.action,
+            }),
+            first_id,
+        )?;
+    }
+    Ok(())
+}
+
+fn on_duty(rule: &AlertRule, ts: i64) -> bool {
+    let start = rule
+        .params
+        .get("start_hour")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(8) as u32;
+    let end = rule
+        .params
+        .get("end_hour")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(20) as u32;
+    let weekdays_only = rule
+        .params
+        .get("weekdays_only")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let local = match Local.timestamp_opt(ts, 0).single() {
+        Some(t) => t,
+        None => return true,
+    };
+    if weekdays_only {
+        use chrono::Datelike;
+        if local.weekday().number_from_monday() > 5 {
+            return false;
+        }
+    }
+    let h = local.hour();
+    if start <= end {
+        h >= start && h < end
+    } else {
+        // overnight shift, e.g. 20 -> 8
+        h >= start || h < end
+    }
+}
+
+/// Best-effort path extraction from free-text `details` for file actions.
+fn path_from_details(action: &str, details: Option<&str>) -> Option<String> {
+    let d = details?.trim();
+    if d.is_empty() {
+        return None;
+    }
+    match action {
+        "open_file" | "open_path" | "fs_delete" | "fs_mkdir" | "fs_create_file" => {
+            Some(d.to_string())
+        }
+        "fs_move" | "fs_copy" | "fs_rename" => {
+            // "move: a -> b" / "copy: a -> b" / "from: a -> to: b" / "from:a to:b"
+            let rest = d
+                .trim_start_matches("move:")
+                .trim_start_matches("copy:")
+                .trim_start_matches("from:")
+                .trim();
+            let src = rest
+                .split(" -> ")
+                .next()
+                .and_then(|s| s.split(" to:").next())
+                .unwrap_or(rest);
+            Some(src.trim().to_string())
+        }
+        "fs_tag" | "fs_untag" => d.rsplit_once(" on ").map(|(_, p)| p.trim().to_string()),
+        _ => None,
+    }
+}
+
+fn check_new_drive(conn: &Connection, rule: &AlertRule, ev: &AuditEvent) -> Result<(), String> {
+    let path = match path_from_details(&ev.action, ev.details.as_deref()) {
+        Some(p) => p,
+        None => return Ok(()),
+    };
+    let drive = crate::fs_ops::normalize_drive_for_storage(&path);
+    let inserted = conn
+        .execute(
+            "INSERT OR IGNORE INTO alert_seen_drives (drive, first_seen_at, first_event_id) VALUES (?1, ?2, ?3)",
+            params![drive, ev.created_at, ev.id],
+        )
+        .map_err(|e| e.to_string())?;
+    if inserted > 0 {
+        raise(
+            conn,
+            rule,
+            ev,
+            format!("{}: first access to drive {}", actor_label(ev), drive),
+            serde_json::json!({ "drive": drive, "path": path, "action": ev.action }),
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+/// Fullness (percent used) at which `drive` alerts, if the rule is enabled.
+/// Checked by the storage sampler, which reports crossings to the audit log.
+pub(crate) fn disk_full_threshold(conn: &Connection, drive: &str) -> Result<Option<f64>, String> {
+    ensure_default_rules(conn)?;
+    let rule = match list_rules(conn)?
+        .into_iter()
+        .find(|r| r.rule_key == RULE_DISK_FULL && r.enabled)
+    {
+        Some(r) => r,
+        None => return Ok(None),
+    };
+    let pct = rule
+        .params
+        .get("drives")
+        .and_then(|d| d.get(drive))
+        .and_then(|v| v.as_f64())
+        .unwrap_or(rule.threshold as f64);
+    Ok(Some(pct))
+}
+
+fn evaluate_event(conn: &Connection, rules: &[AlertRule], ev: &AuditEvent) -> Result<(), String> {
+    // the engine's own bookkeeping never feeds back into the rules
+    if ev.action.starts_with("admin_") && ev.action.contains("alert") {
+        return Ok(());
+    }
+    let is_denial = ev.action == "login_failed"
+        || ev.action == "login_rate_limited"
+        || ev.action.ends_with("_blocked")
+        || ev.action.ends_with("_denied");
+
+    for rule in rules.iter().filter(|r| r.enabled) {
+        match rule.rule_key.as_str() {
+            RULE_MASS_DELETE if ev.action == FILE_DELETE_ACTION => {
+                check_threshold(conn, rule, ev, DELETE_ACTIONS_SQL, "deletes")?
+            }
+            RULE_OPEN_BURST if ev.action == "open_file" || ev.action == "open_path" => {
+                check_threshold(conn, rule, ev, OPEN_ACTIONS_SQL, "opens")?
+            }
+            RULE_REPEATED_DENIALS if is_denial => {
+                check_threshold(conn, rule, ev, DENIAL_ACTIONS_SQL, "denials")?
+            }
+            RULE_OFF_HOURS if ev.actor_user_id.is_some() && !is_denial => {
+                if !on_duty(rule, ev.created_at) && !in_cooldown(conn, rule, ev, rule.window_minutes)? {
+                    raise(
+                        conn,
+                        rule,
+                        ev,
+                        format!("{}: {} outside duty hours", actor_label(ev), ev.action),
+                        serde_json::json!({ "action": ev.action, "params": rule.params }),
+                        None,
+                    )?;
+                }
+            }
+            RULE_NEW_DRIVE => check_new_drive(conn, rule, ev)?,
+            RULE_DISK_FULL if ev.action == crate::storage_history::THRESHOLD_ACTION => {
+                raise(
+                    conn,
+                    rule,
+                    ev,
+                    format!("{}: {}", actor_label(ev), ev.details.as_deref().unwrap_or("")),
+                    serde_json::json!({ "action": ev.action, "details": ev.details }),
+                    None,
+                )?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn evaluate_batch(conn: &Connection) -> Result<usize, String> {
+    ensure_default_rules(conn)?;
+    let rules = list_rules(conn)?;
+    let mut cursor: i64 = match crate::db::get_setting(conn, CURSOR_KEY)?
+        .and_then(|v| v.parse().ok())
+    {
+        Some(c) => c,
+        None => {
+            let newest: i64 = conn
+                .query_row("SELECT COALESCE(MAX(id), 0) FROM audit_logs", [], |r| r.get(0))
+                .map_err(|e| e.to_string())?;
+            crate::db::set_setting(conn, CURSOR_KEY, &newest.to_string())?;
+            return Ok(0);
+        }
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, actor_user_id, actor_username, action, details, created_at
+             FROM audit_logs WHERE id > ?1 ORDER BY id ASC LIMIT ?2",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![cursor, BATCH], |r| {
+            Ok(AuditEvent {
+                id: r.get(0)?,
+                actor_user_id: r.get(1)?,
+                actor_username: r.get(2)?,
+                action: r.get(3)?,
+                details: r.get(4)?,
+                created_at: r.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    let mut events = Vec::new();
+    for r in rows {
+        events.push(r.map_err(|e| e.to_string())?);
+    }
+
+    for ev in &events {
+        evaluate_event(conn, &rules, ev)?;
+        cursor = ev.id;
+    }
+    if !events.is_empty() {
+        crate::db::set_setting(conn, CURSOR_KEY, &cursor.to_string())?;
+    }
+    Ok(events.len())
+}
+
+/// Evaluate audit rows that arrived since the last run. Serialised through an
+/// IMMEDIATE transaction so concurrent writers do not raise the same alert twice.
+pub fn evaluate_new_events(conn: &Connection) -> Result<usize, String> {
+    if !conn.is_autocommit() {
+        return evaluate_batch(conn);
+    }
+    conn.execute_batch("BEGIN IMMEDIATE")
+        .map_err(|e| e.to_string())?;
+    match evaluate_batch(conn) {
+        Ok(n) => {
+            conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+            Ok(n)
+        }
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            Err(e)
+        }
+    }
+}
+
+/// Queue an evaluation pass on a background thread; audit writers never wait
+/// on the rules. A request arriving during a pass triggers one more pass.
+pub fn schedule_pass() {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        RERUN.store(true, Ordering::SeqCst);
+        return;
+    }
+    let spawned = std::thread::Builder::new()
+        .name("alerts".into())
+        .spawn(|| loop {
+            RERUN.store(false, Ordering::SeqCst);
+            if let Err(e) = run_pass() {
+                eprintln!("[alerts] evaluation failed: {}", e);
+            }
+            if !RERUN.load(Ordering::SeqCst) {
+                RUNNING.store(false, Ordering::SeqCst);
+                if RERUN.load(Ordering::SeqCst) && !RUNNING.swap(true, Ordering::SeqCst) {
+                    continue;
+                }
+                break;
+            }
+        });
+    if spawned.is_err() {
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+fn run_pass() -> Result<usize, String> {
+    let conn = crate::db::open_connection()?;
+    let mut total = 0;
+    loop {
+        let n = evaluate_new_events(&conn)?;
+        total += n;
+        if (n as i64) < BATCH {
+            return Ok(total);
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Commands
+// -----------------------------------------------------------------------------
+
+fn alert_from_row(r: &rusqlite::Row<'_>) -> rusqlite::Result<Alert> {
+    Ok(Alert {
+        id: r.get(0)?,
+        rule_key: r.get(1)?,
+        severity: r.get(2)?,
+        actor_user_id: r.get(3)?,
+        actor_username: r.get(4)?,
+        summary: r.get(5)?,
+        details: r.get(6)?,
+        first_event_id: r.get(7)?,
+        last_event_id: r.get(8)?,
+        created_at: r.get(9)?,
+        acknowledged: r.get::<_, i64>(10)? != 0,
+        acknowledged_by: r.get(11)?,
+        acknowledged_at: r.get(12)?,
+        ack_note: r.get(13)?,
+    })
+}
+
+/// List alerts. status: "open" (default) | "acknowledged" | "all".
+#[command]
+pub fn admin_list_alerts(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    status: Option<String>,
+    severity: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<Alert>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    // pick up anything not yet evaluated (e.g. rows written by other processes)
+    let _ = evaluate_new_events(&conn);
+
+    let mut clauses: Vec<&str> = Vec::new();
+    match status.as_deref().unwrap_or("open") {
+        "open" => clauses.push("acknowledged = 0"),
+        "acknowledged" => clauses.push("acknowledged = 1"),
+        _ => {}
+    }
+    let mut values: Vec<rusqlite::types::Value> = Vec::new();
+    if let Some(sev) = severity {
+        clauses.push("severity = ?");
+        values.push(rusqlite::types::Value::from(sev));
+    }
+    values.push(rusqlite::types::Value::from(limit.unwrap_or(200)));
+
+    let where_sql = if clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", clauses.join(" AND "))
+    };
+    let sql = format!(
+        "SELECT id, rule_key, severity, actor_user_id, actor_username, summary, details,
+                first_event_id, last_event_id, created_at, acknowledged, acknowledged_by,
+                acknowledged_at, ack_note
+         FROM alerts {} ORDER BY created_at DESC, id DESC LIMIT ?",
+        where_sql
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(values), alert_from_row)
+        .map_err(|e| e.to_string())?;
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(out)
+}
+
+#[command]
+pub fn admin_acknowledge_alert(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    id: i64,
+    note: Option<String>,
+) -> Result<(), String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let n = conn
+        .execute(
+            "UPDATE alerts SET acknowledged = 1, acknowledged_by = ?1, acknowledged_at = ?2, ack_note = ?3
+             WHERE id = ?4 AND acknowledged = 0",
+            params![admin_username, Utc::now().timestamp(), note, id],
+        )
+        .map_err(|e| e.to_string())?;
+    if n == 0 {
+        return Err("Alert not found or already acknowledged".into());
+    }
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_acknowledge_alert",
+        None,
+        Some(&format!("Acknowledged alert id={}", id)),
+    );
+    Ok(())
+}
+
+#[command]
+pub fn admin_list_alert_rules(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<Vec<AlertRule>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    list_rules(&conn)
+}
+
+/// Update one rule; omitted fields keep their current value.
+#[command]
+pub fn admin_update_alert_rule(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    rule_key: String,
+    enabled: Option<bool>,
+    severity: Option<String>,
+    threshold: Option<i64>,
+    window_minutes: Option<i64>,
+    params: Option<serde_json::Value>,
+) -> Result<Vec<AlertRule>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let current = list_rules(&conn)?
+        .into_iter()
+        .find(|r| r.rule_key == rule_key)
+        .ok_or("Unknown alert rule")?;
+
+    let final_severity = severity.unwrap_or(current.severity);
+    if !SEVERITIES.contains(&final_severity.as_str()) {
+        return Err(format!("severity must be one of {}", SEVERITIES.join(", ")));
+    }
+    let final_threshold = threshold.unwrap_or(current.threshold);
+    let final_window = window_minutes.unwrap_or(current.window_minutes);
+    if final_threshold < 0 || final_window < 0 {
+        return Err("threshold and window_minutes must not be negative".into());
+    }
+    let final_params = params.unwrap_or(current.params);
+
+    conn.execute(
+        "UPDATE alert_rules SET enabled = ?1, severity = ?2, threshold = ?3, window_minutes = ?4,
+                params = ?5, updated_by = ?6, updated_at = ?7
+         WHERE rule_key = ?8",
+        params![
+            enabled.unwrap_or(current.enabled) as i64,
+            final_severity,
+            final_threshold,
+            final_window,
+            final_params.to_string(),
+            admin_username,
+            Utc::now().timestamp(),
+            rule_key
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_update_alert_rule",
+        None,
+        Some(&format!("Updated alert rule '{}'", rule_key)),
+    );
+
+    list_rules(&conn)
+}
@@ -0,0 +1,1144 @@
+// src-tauri/src/archive_index.rs
+//
+// Contents of ZIP, TAR (plain or gzip-compressed) and 7z archives. A
+// background pass lists the members of every indexed archive that is new or
+// changed since its last scan and stores each one as a virtual files_index
+// row (file_type 'member') whose path is the archive's path, "!/", and the
+// member's path inside it: `/share/bundle.zip!/Appendix A.pdf`. Archives
+// inside archives are opened in memory up to a configured depth, giving
+// paths like `outer.zip!/inner.tar!/notes.txt`. Members carry the archive's
+// drive and volume, so location and access rules apply to them unchanged.
+//
+// Supported documents among the members go through the same extractors as
+// files on disk (content_index.rs). Member counts, nested archive sizes and
+// the total number of bytes decompressed per archive are bounded, so a
+// crafted archive cannot stall the pass or exhaust memory.
+//
+// Deleting an archive's row removes its members (trigger below); renames and
+// volume rebases carry them along (indexer.rs, volumes.rs). Members have no
+// path on disk: opening one extracts it to a temporary file first; those
+// copies are removed a day later (at the next open or startup).
+use crate::content_extract;
+use rusqlite::{params, Connection, Transaction, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{command, AppHandle};
+
+const CONFIG_KEY: &str = "archives.config";
+const BATCH: i64 = 100;
+/// Member rows written per transaction while scanning an archive.
+const WRITE_BATCH: usize = 500;
+/// Separates the path of an archive from the path of a member inside it.
+pub(crate) const MEMBER_SEP: &str = "!/";
+const BUDGET_EXCEEDED: &str = "archive expands beyond max_total_bytes";
+/// How long members extracted for opening are kept.
+const OPEN_COPY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+/// Set when a pass is requested while one is already running.
+static RERUN: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Zip,
+    Tar,
+    TarGz,
+    SevenZ,
+}
+
+/// Archive name suffixes read here (a plain `.gz` holds a single file and
+/// is not listed).
+const ARCHIVE_SUFFIXES: &[(&str, Format)] = &[
+    (".tar.gz", Format::TarGz),
+    (".tgz", Format::TarGz),
+    (".tar", Format::Tar),
+    (".zip", Format::Zip),
+    (".7z", Format::SevenZ),
+];
+
+fn format_for_name(name: &str) -> Option<Format> {
+    let lower = name.to_ascii_lowercase();
+    ARCHIVE_SUFFIXES
+        .iter()
+        .find(|(suffix, _)| lower.ends_with(suffix))
+        .map(|(_, f)| *f)
+}
+
+/// SQL predicate selecting rows whose name is an archive this module reads.
+fn archive_filter() -> String {
+    ARCHIVE_SUFFIXES
+        .iter()
+        .map(|(suffix, _)| format!("lower(fi.name) LIKE '%{}'", suffix))
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
+
+/// Byte offsets of the separators in `path` that follow an archive name.
+fn member_cuts(path: &str) -> Vec<usize> {
+    path.match_indices(MEMBER_SEP)
+        .map(|(i, _)| i)
+        .filter(|&i| format_for_name(&path[..i]).is_some())
+        .collect()
+}
+
+/// Archive on disk holding a member path, and the chain of member paths
+/// leading to it: `a.zip!/b.tar!/c.pdf` gives (`a.zip`, [`b.tar`, `c.pdf`]).
+/// None for a path that does not point into an archive.
+pub(crate) fn split_member_path(path: &str) -> Option<(&str, Vec<&str>)> {
+    let cuts = member_cuts(path);
+    let first = *cuts.first()?;
+    let chain = cuts
+        .iter()
+        .enumerate()
+        .map(|(n, &cut)| {
+            let end = cuts.get(n + 1).copied().unwrap_or(path.len());
+            &path[cut + MEMBER_SEP.len()..end]
+        })
+        .collect();
+    Some((&path[..first], chain))
+}
+
+/// Archive (possibly itself a member) directly holding a member path.
+pub(crate) fn member_parent(path: &str) -> Option<&str> {
+    member_cuts(path).last().map(|&i| &path[..i])
+}
+
+/// Path range of the members listed under `archive`, nested ones included.
+pub(crate) fn member_range(archive: &str) -> (String, String) {
+    (format!("{}!/", archive), format!("{}!0", archive))
+}
+
+/// Member path as stored: forward slashes, no leading `/` or `./`. None for
+/// directories and names that cannot be extracted safely.
+fn clean_member_name(raw: &str) -> Option<String> {
+    let name = raw.replace('\\', "/");
+    if name.ends_with('/') {
+        return None;
+    }
+    let parts: Vec<&str> = name
+        .split('/')
+        .filter(|p| !p.is_empty() && *p != ".")
+        .collect();
+    if parts.is_empty() || parts.contains(&"..") {
+        return None;
+    }
+    Some(parts.join("/"))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Extract text and metadata of supported documents inside archives.
+    #[serde(default = "default_true")]
+    pub extract_content: bool,
+    /// Archive levels opened: 1 lists only the archive on disk, 2 also the
+    /// archives inside it, and so on.
+    #[serde(default = "default_max_depth")]
+    pub max_depth: u32,
+    /// Members listed per archive on disk, nested ones included; the rest
+    /// are left out and the scan is marked truncated.
+    #[serde(default = "default_max_members")]
+    pub max_members: u32,
+    /// Largest nested archive opened; nested archives are read into memory.
+    #[serde(default = "default_max_nested_bytes")]
+    pub max_nested_bytes: u64,
+    /// Bytes decompressed while scanning one archive on disk.
+    #[serde(default = "default_max_total_bytes")]
+    pub max_total_bytes: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_depth() -> u32 {
+    3
+}
+
+fn default_max_members() -> u32 {
+    20_000
+}
+
+fn default_max_nested_bytes() -> u64 {
+    64 * 1024 * 1024
+}
+
+fn default_max_total_bytes() -> u64 {
+    1024 * 1024 * 1024
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        ArchiveConfig {
+            enabled: true,
+            extract_content: true,
+            max_depth: default_max_depth(),
+            max_members: default_max_members(),
+            max_nested_bytes: default_max_nested_bytes(),
+            max_total_bytes: default_max_total_bytes(),
+        }
+    }
+}
+
+pub fn load_config(conn: &Connection) -> Result<ArchiveConfig, String> {
+    match crate::db::get_setting(conn, CONFIG_KEY)? {
+        Some(raw) => serde_json::from_str(&raw).map_err(|e| format!("archive config: {}", e)),
+        None => Ok(ArchiveConfig::default()),
+    }
+}
+
+fn validate_config(cfg: &ArchiveConfig) -> Result<(), String> {
+    if !(1..=8).contains(&cfg.max_depth) {
+        return Err("max_depth must be between 1 and 8".into());
+    }
+    if !(1..=1_000_000).contains(&cfg.max_members) {
+        return Err("max_members must be between 1 and 1000000".into());
+    }
+    if cfg.max_nested_bytes > 1024 * 1024 * 1024 {
+        return Err("max_nested_bytes must be at most 1 GiB".into());
+    }
+    if cfg.max_total_bytes < 1024 * 1024 {
+        return Err("max_total_bytes must be at least 1 MiB".into());
+    }
+    Ok(())
+}
+
+/// Called from fs_ops::ensure_files_index_tables.
+pub(crate) fn ensure_tables(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS archive_scans (
+            file_id INTEGER PRIMARY KEY,
+            status TEXT NOT NULL,
+            members INTEGER NOT NULL DEFAULT 0,
+            error TEXT,
+            source_size INTEGER,
+            source_mtime INTEGER,
+            scanned_at INTEGER NOT NULL
+        );
+        CREATE TRIGGER IF NOT EXISTS files_index_ad_members AFTER DELETE ON files_index
+        WHEN old.file_type IS NOT 'dir' BEGIN
+          DELETE FROM archive_scans WHERE file_id = old.id;
+          DELETE FROM files_index WHERE path >= old.path || '!/' AND path < old.path || '!0';
+        END;",
+    )
+    .map_err(|e| format!("ensure archive_scans: {}", e))
+}
+
+// -----------------------------------------------------------------------------
+// Reading archives
+// -----------------------------------------------------------------------------
+
+#[derive(Clone)]
+struct Member {
+    name: String,
+    size: u64,
+    mtime: Option<i64>,
+}
+
+type Visit<'a> = dyn FnMut(&Member, &mut dyn Read) -> Result<bool, String> + 'a;
+
+fn zip_mtime(t: zip::DateTime) -> Option<i64> {
+    use chrono::TimeZone;
+    // zip stores local time without a zone; read it as UTC
+    let date = chrono::NaiveDate::from_ymd_opt(t.year() as i32, t.month() as u32, t.day() as u32)?;
+    let time = date.and_hms_opt(t.hour() as u32, t.minute() as u32, t.second() as u32)?;
+    Some(chrono::Utc.from_utc_datetime(&time).timestamp())
+}
+
+fn tar_members<R: Read>(source: R, budget: &Cell<u64>, visit: &mut Visit) -> Result<(), String> {
+    let mut archive = tar::Archive::new(source);
+    for entry in archive.entries().map_err(|e| format!("tar: {}", e))? {
+        let mut entry = entry.map_err(|e| format!("tar: {}", e))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let member = Member {
+            name: entry
+                .path()
+                .map_err(|e| format!("tar: {}", e))?
+                .to_string_lossy()
+                .to_string(),
+            size: entry.size(),
+            mtime: entry.header().mtime().ok().map(|t| t as i64),
+        };
+        if !visit(&member, &mut entry)? {
+            break;
+        }
+        // the next header is only reached by reading past this entry's data
+        skip_rest(&mut entry, budget)?;
+    }
+    Ok(())
+}
+
+/// Call `visit` with each file in the archive, in archive order, until it
+/// returns false. Bytes the visitor leaves unread are skipped; in tar streams
+/// and solid 7z blocks that still means reading or decompressing them, which
+/// is charged to `budget`.
+fn for_each_member<R: Read + Seek>(
+    format: Format,
+    source: R,
+    len: u64,
+    budget: &Cell<u64>,
+    visit: &mut Visit,
+) -> Result<(), String> {
+    match format {
+        Format::Zip => {
+            let mut zip = zip::ZipArchive::new(source).map_err(|e| format!("zip: {}", e))?;
+            for i in 0..zip.len() {
+                let mut file = zip.by_index(i).map_err(|e| format!("zip: {}", e))?;
+                if file.is_dir() {
+                    continue;
+                }
+                let member = Member {
+                    name: file.name().to_string(),
+                    size: file.size(),
+                    mtime: zip_mtime(file.last_modified()),
+                };
+                if !visit(&member, &mut file)? {
+                    break;
+                }
+            }
+            Ok(())
+        }
+        Format::Tar => tar_members(source, budget, visit),
+        Format::TarGz => tar_members(flate2::read::GzDecoder::new(source), budget, visit),
+        Format::SevenZ => {
+            let mut reader =
+                sevenz_rust::SevenZReader::new(source, len, sevenz_rust::Password::empty())
+                    .map_err(|e| format!("7z: {}", e))?;
+            let mut failed = None;
+            reader
+                .for_each_entries(|entry, data| {
+                    if entry.is_directory || entry.is_anti_item {
+                        return Ok(true);
+                    }
+                    let member = Member {
+                        name: entry.name.clone(),
+                        size: entry.size,
+                        mtime: entry
+                            .has_last_modified_date
+                            .then(|| entry.last_modified_date.to_unix_time()),
+                    };
+                    let more = visit(&member, data).and_then(|more| {
+                        if more {
+                            skip_rest(data, budget)?;
+                        }
+                        Ok(more)
+                    });
+                    match more {
+                        Ok(more) => Ok(more),
+                        Err(e) => {
+                            failed = Some(e);
+                            Ok(false)
+                        }
+                    }
+                })
+                .map_err(|e| format!("7z: {}", e))?;
+            match failed {
+                Some(e) => Err(e),
+                None => Ok(()),
+            }
+        }
+    }
+}
+
+fn skip_rest(data: &mut dyn Read, budget: &Cell<u64>) -> Result<(), String> {
+    let left = budget.get();
+    let skipped = std::io::copy(&mut Read::take(data, left + 1), &mut std::io::sink())
+        .map_err(|e| e.to_string())?;
+    if skipped > left {
+        return Err(BUDGET_EXCEEDED.into());
+    }
+    budget.set(left - skipped);
+    Ok(())
+}
+
+/// Read a member of at most `limit` bytes, charging what is read to
+/// `budget`. None when the member turns out to be larger than `limit`.
+fn read_member(
+    data: &mut dyn Read,
+    limit: u64,
+    budget: &Cell<u64>,
+) -> Result<Option<Vec<u8>>, String> {
+    let left = budget.get();
+    let allowed = limit.min(left);
+    let mut bytes = Vec::new();
+    Read::take(data, allowed + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| e.to_string())?;
+    budget.set(left.saturating_sub(bytes.len() as u64));
+    if bytes.len() as u64 > allowed {
+        if allowed < limit {
+            return Err(BUDGET_EXCEEDED.into());
+        }
+        return Ok(None);
+    }
+    Ok(Some(bytes))
+}
+
+// -----------------------------------------------------------------------------
+// Scanning
+// -----------------------------------------------------------------------------
+
+struct ArchiveRow {
+    id: i64,
+    path: String,
+    size: Option<i64>,
+    mtime: Option<i64>,
+    drive: Option<String>,
+    volume_id: Option<i64>,
+    rel_path: Option<String>,
+}
+
+fn upsert_member(
+    conn: &Connection,
+    archive: &ArchiveRow,
+    path: &str,
+    parent: &str,
+    member: &Member,
+    now: i64,
+) -> Result<(), String> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let rel_path = archive
+        .rel_path
+        .as_ref()
+        .map(|rel| format!("{}{}", rel, &path[archive.path.len()..]));
+    conn.prepare_cached(
+        "INSERT INTO files_index (path, name, file_type, doc_type, size, indexed_at, drive, parent, mtime, volume_id, rel_path)
+         VALUES (?1, ?2, 'member', ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+         ON CONFLICT(path)
+         DO UPDATE SET
+           name=excluded.name,
+           file_type=excluded.file_type,
+           doc_type=excluded.doc_type,
+           size=excluded.size,
+           indexed_at=excluded.indexed_at,
+           drive=excluded.drive,
+           parent=excluded.parent,
+           mtime=excluded.mtime,
+           volume_id=excluded.volume_id,
+           rel_path=excluded.rel_path",
+    )
+    .and_then(|mut stmt| {
+        stmt.execute(params![
+            path,
+            name,
+            crate::fs_ops::doc_type_for_path(path, false),
+            member.size as i64,
+            now,
+            archive.drive,
+            parent,
+            member.mtime,
+            archive.volume_id,
+            rel_path
+        ])
+    })
+    .map(|_| ())
+    .map_err(|e| format!("db upsert failed at {}: {}", path, e))
+}
+
+struct Scan<'a> {
+    conn: &'a Connection,
+    cfg: &'a ArchiveConfig,
+    archive: &'a ArchiveRow,
+    budget: &'a Cell<u64>,
+    now: i64,
+    seen: HashSet<String>,
+    /// Members listed but not written yet: (path, parent, member).
+    pending: Vec<(String, String, Member)>,
+    truncated: bool,
+}
+
+impl Scan<'_> {
+    /// Write the pending members in one short transaction. Reading the
+    /// archive and extracting text happen outside it, so the write lock is
+    /// never held for longer than the inserts themselves.
+    fn flush(&mut self) -> Result<(), String> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let tx = Transaction::new_unchecked(self.conn, TransactionBehavior::Immediate)
+            .map_err(|e| e.to_string())?;
+        for (path, parent, member) in self.pending.drain(..) {
+            upsert_member(&tx, self.archive, &path, &parent, &member, self.now)?;
+        }
+        tx.commit().map_err(|e| e.to_string())
+    }
+
+    fn walk<R: Read + Seek>(
+        &mut self,
+        format: Format,
+        source: R,
+        len: u64,
+        prefix: &str,
+        depth: u32,
+    ) -> Result<(), String> {
+        let budget = self.budget;
+        for_each_member(format, source, len, budget, &mut |member, data| {
+            let name = match clean_member_name(&member.name) {
+                Some(n) => n,
+                None => return Ok(true),
+            };
+            if self.seen.len() >= self.cfg.max_members as usize {
+                self.truncated = true;
+                return Ok(false);
+            }
+            let path = format!("{}{}{}", prefix, MEMBER_SEP, name);
+            // a name repeated in one zip: the later entry wins on extraction
+            // too, but list it once
+            if !self.seen.insert(path.clone()) {
+                return Ok(true);
+            }
+            self.pending
+                .push((path.clone(), prefix.to_string(), member.clone()));
+            if self.pending.len() >= WRITE_BATCH {
+                self.flush()?;
+            }
+
+            if let Some(inner) = format_for_name(&name) {
+                if depth < self.cfg.max_depth && member.size <= self.cfg.max_nested_bytes {
+                    if let Some(bytes) = read_member(data, self.cfg.max_nested_bytes, budget)? {
+                        let len = bytes.len() as u64;
+                        match self.walk(inner, Cursor::new(bytes), len, &path, depth + 1) {
+                            Err(e) if e == BUDGET_EXCEEDED => return Err(e),
+                            // a damaged nested archive stays listed, without members
+                            Err(e) => eprintln!("[archive_index] {}: {}", path, e),
+                            Ok(()) => {}
+                        }
+                    }
+                }
+                return Ok(!self.truncated);
+            }
+            if self.cfg.extract_content {
+                self.extract(&path, &name, member, data)?;
+            }
+            Ok(true)
+        })
+    }
+
+    fn extract(
+        &mut self,
+        path: &str,
+        name: &str,
+        member: &Member,
+        data: &mut dyn Read,
+    ) -> Result<(), String> {
+        let kind = match content_extract::kind_for_name(name) {
+            Some(k) => k,
+            None => return Ok(()),
+        };
+        // the text is stored against the member's row, so write it first
+        self.flush()?;
+        let file_id: i64 = self
+            .conn
+            .query_row(
+                "SELECT id FROM files_index WHERE path = ?1",
+                params![path],
+                |r| r.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        let size = Some(member.size as i64);
+        if crate::content_index::is_current(self.conn, file_id, size, member.mtime)? {
+            return Ok(());
+        }
+        let bytes = if member.size > kind.max_input_bytes() {
+            None
+        } else {
+            read_member(data, kind.max_input_bytes(), self.budget)?
+        };
+        crate::content_index::index_bytes(self.conn, file_id, kind, bytes, size, member.mtime)
+    }
+}
+
+/// List the members of one archive on disk, replacing those from its last
+/// scan. Rows are written in batches as the archive is read; members the
+/// scan no longer finds are removed at the end, in a transaction of their
+/// own. Returns (status, members, error).
+fn scan_archive(
+    conn: &Connection,
+    cfg: &ArchiveConfig,
+    archive: &ArchiveRow,
+) -> (&'static str, usize, Option<String>) {
+    let format = match format_for_name(&archive.path) {
+        Some(f) => f,
+        None => return ("error", 0, Some("not an archive".into())),
+    };
+    let budget = Cell::new(cfg.max_total_bytes);
+    let mut scan = Scan {
+        conn,
+        cfg,
+        archive,
+        budget: &budget,
+        now: chrono::Utc::now().timestamp(),
+        seen: HashSet::new(),
+        pending: Vec::new(),
+        truncated: false,
+    };
+    let walked = File::open(&archive.path)
+        .and_then(|f| Ok((f.metadata()?.len(), f)))
+        .map_err(|e| e.to_string())
+        .and_then(|(len, f)| scan.walk(format, BufReader::new(f), len, &archive.path, 1));
+    let (status, error) = match walked {
+        Ok(()) if scan.truncated => (
+            "truncated",
+            Some(format!("more than {} members", cfg.max_members)),
+        ),
+        Ok(()) => ("ok", None),
+        Err(e) if e == BUDGET_EXCEEDED => ("truncated", Some(e)),
+        // unreadable or damaged: keep what the last good scan listed (rows
+        // already written were read intact and stay)
+        Err(e) => return ("error", 0, Some(e)),
+    };
+    let members = scan.seen.len();
+    let removed = scan.flush().and_then(|()| {
+        let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)
+            .map_err(|e| e.to_string())?;
+        remove_stale(&tx, &archive.path, &scan.seen)?;
+        tx.commit().map_err(|e| e.to_string())
+    });
+    if let Err(e) = removed {
+        return ("error", members, Some(e));
+    }
+    (status, members, error)
+}
+
+/// Delete members of `archive` that the latest scan did not list.
+fn remove_stale(conn: &Connection, archive: &str, seen: &HashSet<String>) -> Result<(), String> {
+    let (lo, hi) = member_range(archive);
+    let stale: Vec<String> = {
+        let mut stmt = conn
+            .prepare("SELECT path FROM files_index WHERE path >= ?1 AND path < ?2")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![lo, hi], |r| r.get::<_, String>(0))
+            .map_err(|e| e.to_string())?;
+        rows.filter_map(|r| r.ok())
+            .filter(|p| !seen.contains(p))
+            .collect()
+    };
+    for path in stale {
+        conn.execute("DELETE FROM files_index WHERE path = ?1", params![path])
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Whether a archive pass is running (the scheduler keeps rescans apart from it).
+pub(crate) fn pass_running() -> bool {
+    RUNNING.load(Ordering::SeqCst)
+}
+
+/// Request an archive pass. Returns immediately; at most one pass runs at a
+/// time and a request made during a pass triggers one more pass after it.
+pub fn schedule_pass() {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        RERUN.store(true, Ordering::SeqCst);
+        return;
+    }
+    let spawned = std::thread::Builder::new()
+        .name("archive-index".into())
+        .spawn(|| loop {
+            RERUN.store(false, Ordering::SeqCst);
+            if let Err(e) = run_pass() {
+                eprintln!("[archive_index] pass failed: {}", e);
+            }
+            if !RERUN.load(Ordering::SeqCst) {
+                RUNNING.store(false, Ordering::SeqCst);
+                // a request may have slipped in between the check and the store
+                if RERUN.load(Ordering::SeqCst) && !RUNNING.swap(true, Ordering::SeqCst) {
+                    continue;
+                }
+                break;
+            }
+        });
+    if spawned.is_err() {
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Archives on disk never scanned or changed since their last scan (rows on
+/// unavailable volumes wait until they are back).
+fn pending_filter() -> String {
+    format!(
+        "fi.file_type = 'file'
+           AND (s.file_id IS NULL OR s.source_size IS NOT fi.size
+                OR (fi.mtime IS NOT NULL AND s.source_mtime IS NOT fi.mtime))
+           AND (fi.volume_id IS NULL OR fi.volume_id NOT IN ({}))
+           AND ({})",
+        crate::locations::UNAVAILABLE_VOLUME_IDS,
+        archive_filter()
+    )
+}
+
+fn run_pass() -> Result<usize, String> {
+    let conn = crate::db::open_connection()?;
+    crate::fs_ops::ensure_files_index_tables(&conn)?;
+    let cfg = load_config(&conn)?;
+    if !cfg.enabled {
+        return Ok(0);
+    }
+    let sql = format!(
+        "SELECT fi.id, fi.path, fi.size, fi.mtime, fi.drive, fi.volume_id, fi.rel_path
+         FROM files_index fi
+         LEFT JOIN archive_scans s ON s.file_id = fi.id
+         WHERE fi.id > ?1 AND {}
+         ORDER BY fi.id
+         LIMIT ?2",
+        pending_filter()
+    );
+    let mut cursor = 0i64;
+    let mut done = 0usize;
+    loop {
+        let batch: Vec<ArchiveRow> = {
+            let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map(params![cursor, BATCH], |r| {
+                    Ok(ArchiveRow {
+                        id: r.get(0)?,
+                        path: r.get(1)?,
+                        size: r.get(2)?,
+                        mtime: r.get(3)?,
+                        drive: r.get(4)?,
+                        volume_id: r.get(5)?,
+                        rel_path: r.get(6)?,
+                    })
+                })
+                .map_err(|e| e.to_string())?;
+            rows.filter_map(|r| r.ok()).collect()
+        };
+        if batch.is_empty() {
+            break;
+        }
+        for archive in batch {
+            cursor = archive.id;
+            let (status, members, error) = scan_archive(&conn, &cfg, &archive);
+            if let Some(e) = &error {
+                eprintln!("[archive_index] {}: {}", archive.path, e);
+            }
+            conn.execute(
+                "INSERT OR REPLACE INTO archive_scans
+                   (file_id, status, members, error, source_size, source_mtime, scanned_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    archive.id,
+                    status,
+                    members as i64,
+                    error,
+                    archive.size,
+                    archive.mtime,
+                    chrono::Utc::now().timestamp()
+                ],
+            )
+            .map_err(|e| e.to_string())?;
+            done += 1;
+        }
+    }
+    Ok(done)
+}
+
+// -----------------------------------------------------------------------------
+// Reading single members
+// -----------------------------------------------------------------------------
+
+/// Find `chain[0]` in the archive, descending into nested archives for the
+/// rest of the chain, and hand the final member's data to `sink`. Returns
+/// false when a member of the chain is not in its archive.
+fn find_member<R: Read + Seek>(
+    format: Format,
+    source: R,
+    len: u64,
+    chain: &[&str],
+    cfg: &ArchiveConfig,
+    budget: &Cell<u64>,
+    sink: &mut dyn FnMut(&Member, &mut dyn Read) -> Result<(), String>,
+) -> Result<bool, String> {
+    let (wanted, rest) = match chain.split_first() {
+        Some(x) => x,
+        None => return Ok(false),
+    };
+    let mut found = false;
+    for_each_member(format, source, len, budget, &mut |member, data| {
+        if clean_member_name(&member.name).as_deref() != Some(*wanted) {
+            return Ok(true);
+        }
+        if rest.is_empty() {
+            sink(member, data)?;
+            found = true;
+            return Ok(false);
+        }
+        let inner =
+            format_for_name(wanted).ok_or_else(|| format!("{} is not an archive", wanted))?;
+        let bytes = read_member(data, cfg.max_nested_bytes, budget)?
+            .ok_or_else(|| format!("{} exceeds max_nested_bytes", wanted))?;
+        let len = bytes.len() as u64;
+        found = find_member(inner, Cursor::new(bytes), len, rest, cfg, budget, sink)?;
+        Ok(false)
+    })?;
+    Ok(found)
+}
+
+/// Write the member at `path` (an archive path, "!/", member path) to a new
+/// file `dest`. Refuses to overwrite; a partial file is removed on failure.
+/// No more than the member's declared size, and no more than what is left
+/// of max_total_bytes, is written.
+fn extract_to(conn: &Connection, path: &str, dest: &Path) -> Result<(), String> {
+    let (archive, chain) =
+        split_member_path(path).ok_or_else(|| "not an archive member path".to_string())?;
+    let format = format_for_name(archive).ok_or_else(|| "not an archive".to_string())?;
+    let cfg = load_config(conn)?;
+    let budget = Cell::new(cfg.max_total_bytes);
+    let file = File::open(archive).map_err(|e| format!("open {}: {}", archive, e))?;
+    let len = file.metadata().map_err(|e| e.to_string())?.len();
+
+    let mut out = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(dest)
+        .map_err(|e| format!("create {}: {}", dest.display(), e))?;
+    let found = find_member(
+        format,
+        BufReader::new(file),
+        len,
+        &chain,
+        &cfg,
+        &budget,
+        &mut |member, data| {
+            let left = budget.get();
+            let limit = member.size.min(left);
+            let copied = std::io::copy(&mut Read::take(data, limit + 1), &mut out)
+                .map_err(|e| format!("write {}: {}", dest.display(), e))?;
+            if copied > limit {
+                return Err(if limit < member.size {
+                    BUDGET_EXCEEDED.to_string()
+                } else {
+                    "member is larger than its declared size".to_string()
+                });
+            }
+            budget.set(left - copied);
+            Ok(())
+        },
+    );
+    drop(out);
+    match found {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            let _ = std::fs::remove_file(dest);
+            Err("member not found in archive".into())
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(dest);
+            Err(e)
+        }
+    }
+}
+
+/// File name a member is extracted under.
+fn member_file_name(path: &str) -> Result<&str, String> {
+    path.rsplit(['/', '\\'])
+        .next()
+        .filter(|n| !n.is_empty() && *n != "." && *n != "..")
+        .ok_or_else(|| "member has no file name".to_string())
+}
+
+fn check_member(conn: &Connection, uid: i64, path: &str) -> Result<(), String> {
+    crate::fs_ops::ensure_files_index_tables(conn)?;
+    if split_member_path(path).is_none() {
+        return Err("not an archive member path".into());
+    }
+    crate::fs_ops::visible_file_id(conn, uid, path)?
+        .ok_or_else(|| "member is not indexed or not accessible".to_string())?;
+    Ok(())
+}
+
+fn open_copies_dir() -> PathBuf {
+    std::env::temp_dir().join("vaultguard-open")
+}
+
+/// Remove members extracted for opening more than OPEN_COPY_TTL ago (the
+/// application they were handed to has long had them open by then).
+pub fn prune_open_copies() {
+    let entries = match std::fs::read_dir(open_copies_dir()) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let expired = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.elapsed().ok())
+            .is_some_and(|age| age > OPEN_COPY_TTL);
+        if expired {
+            let _ = std::fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+/// Extract a member to a temporary file and open it with the platform's
+/// default application (open_file_by_session hands member paths here).
+pub(crate) fn open_member(conn: &Connection, uid: i64, path: &str) -> Result<(), String> {
+    check_member(conn, uid, path)?;
+    prune_open_copies();
+    let mut dir = open_copies_dir();
+    dir.push(uuid::Uuid::new_v4().to_string());
+    std::fs::create_dir_all(&dir).map_err(|e| format!("create {}: {}", dir.display(), e))?;
+    let dest = dir.join(member_file_name(path)?);
+    extract_to(conn, path, &dest)?;
+
+    let username = crate::db::get_user_by_id_row(conn, uid)
+        .ok()
+        .flatten()
+        .map(|u| u.username);
+    let _ = crate::db::insert_file_audit_log(
+        conn,
+        Some(uid),
+        username.as_deref(),
+        "open_file",
+        path,
+        None,
+        Some(&format!("archive member extracted to {}", dest.display())),
+    );
+    crate::fs_ops::open_with_default_app(&dest.to_string_lossy());
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Commands
+// -----------------------------------------------------------------------------
+
+/// Extract one archive member (a search result path such as
+/// `/share/bundle.zip!/Appendix A.pdf`) into `dest_dir`. Returns the path
+/// written; an existing file of that name is not overwritten.
+#[command]
+pub fn extract_archive_member(
+    session_token: String,
+    path: String,
+    dest_dir: String,
+) -> Result<String, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("db open: {}", e))?;
+    let uid = crate::session::validate_session(&conn, &session_token)
+        .map_err(|e| format!("session validation: {}", e))?
+        .ok_or_else(|| "invalid session".to_string())?;
+    check_member(&conn, uid, &path)?;
+
+    let dir = PathBuf::from(&dest_dir);
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dest_dir));
+    }
+    let dest = dir.join(member_file_name(&path)?);
+    extract_to(&conn, &path, &dest)?;
+    let dest = dest.to_string_lossy().to_string();
+
+    let username = crate::db::get_user_by_id_row(&conn, uid)
+        .ok()
+        .flatten()
+        .map(|u| u.username);
+    let _ = crate::db::insert_file_audit_log(
+        &conn,
+        Some(uid),
+        username.as_deref(),
+        "extract_archive_member",
+        &path,
+        Some(&dest),
+        None,
+    );
+    Ok(dest)
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArchiveIndexStatus {
+    pub config: ArchiveConfig,
+    pub running: bool,
+    /// Archives not yet scanned (or changed since their scan).
+    pub pending: i64,
+    /// (status, count) over archive_scans.
+    pub by_status: Vec<(String, i64)>,
+    /// Members listed across all archives.
+    pub members: i64,
+}
+
+fn status(conn: &Connection) -> Result<ArchiveIndexStatus, String> {
+    let pending: i64 = conn
+        .query_row(
+            &format!(
+                "SELECT COUNT(*) FROM files_index fi
+                 LEFT JOIN archive_scans s ON s.file_id = fi.id
+                 WHERE {}",
+                pending_filter()
+            ),
+            [],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare("SELECT status, COUNT(*) FROM archive_scans GROUP BY status ORDER BY status")
+        .map_err(|e| e.to_string())?;
+    let by_status = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    let members: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM files_index WHERE file_type = 'member'",
+            [],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(ArchiveIndexStatus {
+        config: load_config(conn)?,
+        running: RUNNING.load(Ordering::SeqCst),
+        pending,
+        by_status,
+        members,
+    })
+}
+
+#[command]
+pub fn admin_get_archive_settings(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<ArchiveIndexStatus, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    crate::fs_ops::ensure_files_index_tables(&conn)?;
+    status(&conn)
+}
+
+/// Save the archive settings and rescan every archive under them. Members
+/// already listed stay when scanning is disabled.
+#[command]
+pub fn admin_set_archive_settings(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    config: ArchiveConfig,
+) -> Result<ArchiveIndexStatus, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    crate::fs_ops::ensure_files_index_tables(&conn)?;
+
+    validate_config(&config)?;
+    let raw = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    crate::db::set_setting(&conn, CONFIG_KEY, &raw)?;
+    conn.execute("DELETE FROM archive_scans", [])
+        .map_err(|e| e.to_string())?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_set_archive_settings",
+        None,
+        Some(&format!(
+            "enabled={} extract_content={} max_depth={} max_members={} max_nested_bytes={} max_total_bytes={}",
+            config.enabled,
+            config.extract_content,
+            config.max_depth,
+            config.max_members,
+            config.max_nested_bytes,
+            config.max_total_bytes
+        )),
+    );
+
+    schedule_pass();
+    status(&conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn member_paths_split_at_archive_names() {
+        assert_eq!(
+            split_member_path("/share/bundle.zip!/Appendix A.pdf"),
+            Some(("/share/bundle.zip", vec!["Appendix A.pdf"]))
+        );
+        assert_eq!(
+            split_member_path("/s/outer.zip!/in/inner.TAR.GZ!/notes.txt"),
+            Some(("/s/outer.zip", vec!["in/inner.TAR.GZ", "notes.txt"]))
+        );
+        // "!/" after something that is not an archive is part of a name
+        assert_eq!(
+            split_member_path("/s/wow!/a.7z!/x!/y.txt"),
+            Some(("/s/wow!/a.7z", vec!["x!/y.txt"]))
+        );
+        assert_eq!(split_member_path("/s/plain!/file.txt"), None);
+        assert_eq!(split_member_path("/s/bundle.zip"), None);
+        assert_eq!(
+            member_parent("/s/a.zip!/b.tar!/c.pdf"),
+            Some("/s/a.zip!/b.tar")
+        );
+    }
+
+    #[test]
+    fn member_names_are_cleaned_or_refused() {
+        assert_eq!(
+            clean_member_name("docs/a.pdf").as_deref(),
+            Some("docs/a.pdf")
+        );
+        assert_eq!(
+            clean_member_name("/abs//./docs\\a.pdf").as_deref(),
+            Some("abs/docs/a.pdf")
+        );
+        assert_eq!(clean_member_name("./a.txt").as_deref(), Some("a.txt"));
+        assert_eq!(clean_member_name("docs/"), None);
+        assert_eq!(clean_member_name("../etc/passwd"), None);
+        assert_eq!(clean_member_name("a/../../b"), None);
+        assert_eq!(clean_member_name("./."), None);
+        assert_eq!(clean_member_name(""), None);
+    }
+
+    #[test]
+    fn skipped_tar_data_is_charged_to_the_budget() {
+        let mut builder = tar::Builder::new(Vec::new());
+        for name in ["a.bin", "b.bin", "c.bin"] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(4096);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, &[0u8; 4096][..])
+                .unwrap();
+        }
+        let bytes = builder.into_inner().unwrap();
+        let len = bytes.len() as u64;
+
+        let mut listed = 0;
+        let budget = Cell::new(3 * 4096);
+        for_each_member(
+            Format::Tar,
+            Cursor::new(&bytes),
+            len,
+            &budget,
+            &mut |_, _| {
+                listed += 1;
+                Ok(true)
+            },
+        )
+        .unwrap();
+        assert_eq!((listed, budget.get()), (3, 0));
+
+        let budget = Cell::new(2 * 4096 + 100);
+        let listed = for_each_member(
+            Format::Tar,
+            Cursor::new(&bytes),
+            len,
+            &budget,
+            &mut |_, _| Ok(true),
+        );
+        assert_eq!(listed, Err(BUDGET_EXCEEDED.to_string()));
+    }
+}
@@ -20,7 +20,7 @@ fn pick_session_token(
     sessionToken.or(session_token)
 }
 
-fn ensure_admin_session(
+pub(crate) fn ensure_admin_session(
     conn: &Connection,
     token_opt: Option<String>,
     token_opt2: Option<String>,
@@ -119,7 +119,7 @@ pub struct PortalLog {
     pub details: Option<String>,
 }
 
-fn window_to_ts(window: &str) -> i64 {
+pub(crate) fn window_to_ts(window: &str) -> i64 {
     let now = Utc::now();
     match window {
         "48h" => (now - Duration::hours(48)).timestamp(),
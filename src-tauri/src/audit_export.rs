@@ -0,0 +1,852 @@
+// src-tauri/src/audit_export.rs
+//
+// Signed export of `audit_logs` and `login_history` for inspection boards.
+//
+// An export is a directory containing, per table, a CSV and a JSONL file plus
+// `manifest.json` (row counts, file hashes, hash-chain anchors) and
+// `manifest.sig` (detached Ed25519 signature over the exact manifest bytes,
+// made with the installation key from `security.rs`).
+//
+// Hash chain: for every table, `h0 = sha256("prabal-audit-chain-v1|<table>|<export_id>|<previous_anchor>")`
+// and `h(n) = sha256(h(n-1) + "\n" + <jsonl line n>)`. The last value is the
+// table's `chain_anchor`. The export anchor (sha256 over the table anchors) is
+// kept in `audit_exports` and seeds the next export, so consecutive exports
+// form one chain.
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle};
+
+use crate::security::sha256_hex;
+
+const EXPORT_FORMAT_VERSION: u32 = 1;
+const MANIFEST_FILE: &str = "manifest.json";
+const SIGNATURE_FILE: &str = "manifest.sig";
+const CHAIN_DOMAIN: &str = "prabal-audit-chain-v1";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditExportFilter {
+    pub from_ts: Option<i64>,
+    pub to_ts: Option<i64>,
+    /// Only applies to `audit_logs`
+    pub actions: Option<Vec<String>>,
+    pub username: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedFile {
+    pub name: String,
+    pub sha256: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedTable {
+    pub table: String,
+    pub row_count: u64,
+    pub first_id: Option<i64>,
+    pub last_id: Option<i64>,
+    pub chain_seed: String,
+    pub chain_anchor: String,
+    pub files: Vec<ExportedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditExportManifest {
+    pub format_version: u32,
+    pub export_id: String,
+    pub generated_at: i64,
+    pub exported_by: String,
+    pub filter: AuditExportFilter,
+    pub previous_anchor: Option<String>,
+    pub export_anchor: String,
+    pub tables: Vec<ExportedTable>,
+    pub public_key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DetachedSignature {
+    algorithm: String,
+    public_key: String,
+    signature: String,
+}
+
+#[derive(Serialize)]
+pub struct AuditExportResult {
+    pub export_dir: String,
+    pub manifest: AuditExportManifest,
+    pub signature: String,
+}
+
+#[derive(Serialize)]
+pub struct AuditExportVerification {
+    pub ok: bool,
+    pub signature_valid: bool,
+    pub signed_by_this_installation: bool,
+    pub manifest: Option<AuditExportManifest>,
+    pub problems: Vec<String>,
+}
+
+// -----------------------------------------------------------------------------
+// Row types
+// -----------------------------------------------------------------------------
+
+trait ExportRow: Serialize {
+    fn csv_header() -> &'static str;
+    fn csv_record(&self) -> String;
+    fn row_id(&self) -> i64;
+}
+
+#[derive(Serialize)]
+struct AuditLogExportRow {
+    id: i64,
+    actor_user_id: Option<i64>,
+    actor_username: Option<String>,
+    action: String,
+    target_user_id: Option<i64>,
+    details: Option<String>,
+    created_at: i64,
+    target_type: Option<String>,
+    target_path: Option<String>,
+    target_dest_path: Option<String>,
+    target_file_id: Option<i64>,
+}
+
+impl ExportRow for AuditLogExportRow {
+    fn csv_header() -> &'static str {
+        "id,actor_user_id,actor_username,action,target_user_id,details,created_at,target_type,target_path,target_dest_path,target_file_id"
+    }
+
+    fn csv_record(&self) -> String {
+        [
+            self.id.to_string(),
+            opt_num(self.actor_user_id),
+            csv_field(self.actor_username.as_deref().unwrap_or("")),
+            csv_field(&self.action),
+            opt_num(self.target_user_id),
+            csv_field(self.details.as_deref().unwrap_or("")),
+            self.created_at.to_string(),
+            csv_field(self.target_type.as_deref().unwrap_or("")),
+            csv_field(self.target_path.as_deref().unwrap_or("")),
+            csv_field(self.target_dest_path.as_deref().unwrap_or("")),
+            opt_num(self.target_file_id),
+        ]
+        .join(",")
+    }
+
+    fn row_id(&self) -> i64 {
+        self.id
+    }
+}
+
+#[derive(Serialize)]
+struct LoginHistoryExportRow {
+    id: i64,
+    user_id: Option<i64>,
+    username: Option<String>,
+    success: bool,
+    ip: Option<String>,
+    created_at: i64,
+}
+
+impl ExportRow for LoginHistoryExportRow {
+    fn csv_header() -> &'static str {
+        "id,user_id,username,success,ip,created_at"
+    }
+
+    fn csv_record(&self) -> String {
+        [
+            self.id.to_string(),
+            opt_num(self.user_id),
+            csv_field(self.username.as_deref().unwrap_or("")),
+            if self.success { "1" } else { "0" }.to_string(),
+            csv_field(self.ip.as_deref().unwrap_or("")),
+            self.created_at.to_string(),
+        ]
+        .join(",")
+    }
+
+    fn row_id(&self) -> i64 {
+        self.id
+    }
+}
+
+fn opt_num(v: Option<i64>) -> String {
+    v.map(|n| n.to_string()).unwrap_or_default()
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break (RFC 4180).
+fn csv_field(s: &str) -> String {
+    if s.contains(',') || s.contains('"') || s.contains('\n') || s.contains('\r') {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Count CSV records (excluding the header), honouring quoted line breaks.
+fn csv_record_count(text: &str) -> u64 {
+    let mut in_quotes = false;
+    let mut records: u64 = 0;
+    let mut line_has_data = false;
+    for c in text.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                line_has_data = true;
+            }
+            '\n' if !in_quotes => {
+                if line_has_data {
+                    records += 1;
+                }
+                line_has_data = false;
+            }
+            '\r' => {}
+            _ => line_has_data = true,
+        }
+    }
+    if line_has_data {
+        records += 1;
+    }
+    records.saturating_sub(1)
+}
+
+// -----------------------------------------------------------------------------
+// Queries
+// -----------------------------------------------------------------------------
+
+fn push_range(
+    clauses: &mut Vec<String>,
+    values: &mut Vec<rusqlite::types::Value>,
+    column: &str,
+    filter: &AuditExportFilter,
+) {
+    if let Some(from) = filter.from_ts {
+        clauses.push(format!("{} >= ?", column));
+        values.push(rusqlite::types::Value::from(from));
+    }
+    if let Some(to) = filter.to_ts {
+        clauses.push(format!("{} <= ?", column));
+        values.push(rusqlite::types::Value::from(to));
+    }
+}
+
+fn where_sql(clauses: &[String]) -> String {
+    if clauses.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", clauses.join(" AND "))
+    }
+}
+
+fn query_audit_rows(
+    conn: &Connection,
+    filter: &AuditExportFilter,
+) -> Result<Vec<AuditLogExportRow>, String> {
+    let mut clauses: Vec<String> = Vec::new();
+    let mut values: Vec<rusqlite::types::Value> = Vec::new();
+    push_range(&mut clauses, &mut values, "created_at", filter);
+    if let Some(ref actions) = filter.actions {
+        if !actions.is_empty() {
+            let marks = vec!["?"; actions.len()].join(", ");
+            clauses.push(format!("action IN ({})", marks));
+            for a in actions {
+                values.push(rusqlite::types::Value::from(a.clone()));
+            }
+        }
+    }
+    if let Some(ref u) = filter.username {
+        clauses.push("actor_username = ?".into());
+        values.push(rusqlite::types::Value::from(u.clone()));
+    }
+
+    let sql = format!(
+        "SELECT id, actor_user_id, actor_username, action, target_user_id, details, created_at,
+                target_type, target_path, target_dest_path, target_file_id
+         FROM audit_logs{} ORDER BY id ASC",
+        where_sql(&clauses)
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(values), |r| {
+            Ok(AuditLogExportRow {
+                id: r.get(0)?,
+                actor_user_id: r.get(1)?,
+                actor_username: r.get(2)?,
+                action: r.get(3)?,
+                target_user_id: r.get(4)?,
+                details: r.get(5)?,
+                created_at: r.get(6)?,
+                target_type: r.get(7)?,
+                target_path: r.get(8)?,
+                target_dest_path: r.get(9)?,
+                target_file_id: r.get(10)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(out)
+}
+
+fn query_login_rows(
+    conn: &Connection,
+    filter: &AuditExportFilter,
+) -> Result<Vec<LoginHistoryExportRow>, String> {
+    let mut clauses: Vec<String> = Vec::new();
+    let mut values: Vec<rusqlite::types::Value> = Vec::new();
+    push_range(&mut clauses, &mut values, "created_at", filter);
+    if let Some(ref u) = filter.username {
+        clauses.push("username = ?".into());
+        values.push(rusqlite::types::Value::from(u.clone()));
+    }
+
+    let sql = format!(
+        "SELECT id, user_id, username, success, ip, created_at
+         FROM login_history{} ORDER BY id ASC",
+        where_sql(&clauses)
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(values), |r| {
+            Ok(LoginHistoryExportRow {
+                id: r.get(0)?,
+                user_id: r.get(1)?,
+                username: r.get(2)?,
+                success: r.get::<_, i64>(3)? != 0,
+                ip: r.get(4)?,
+                created_at: r.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(out)
+}
+
+// -----------------------------------------------------------------------------
+// Writing
+// -----------------------------------------------------------------------------
+
+fn chain_seed(table: &str, export_id: &str, previous_anchor: Option<&str>) -> String {
+    sha256_hex(
+        format!(
+            "{}|{}|{}|{}",
+            CHAIN_DOMAIN,
+            table,
+            export_id,
+            previous_anchor.unwrap_or("")
+        )
+        .as_bytes(),
+    )
+}
+
+fn chain_next(prev: &str, line: &str) -> String {
+    sha256_hex(format!("{}\n{}", prev, line).as_bytes())
+}
+
+fn export_anchor(tables: &[ExportedTable]) -> String {
+    let joined = tables
+        .iter()
+        .map(|t| t.chain_anchor.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    sha256_hex(joined.as_bytes())
+}
+
+fn write_file(dir: &Path, name: &str, data: &[u8]) -> Result<ExportedFile, String> {
+    let p = dir.join(name);
+    fs::write(&p, data).map_err(|e| format!("write {}: {}", p.display(), e))?;
+    Ok(ExportedFile {
+        name: name.to_string(),
+        sha256: sha256_hex(data),
+        bytes: data.len() as u64,
+    })
+}
+
+fn write_table<T: ExportRow>(
+    dir: &Path,
+    table: &str,
+    rows: &[T],
+    export_id: &str,
+    previous_anchor: Option<&str>,
+) -> Result<ExportedTable, String> {
+    let seed = chain_seed(table, export_id, previous_anchor);
+    let mut head = seed.clone();
+
+    let mut csv = String::new();
+    csv.push_str(T::csv_header());
+    csv.push('\n');
+    let mut jsonl = String::new();
+
+    for row in rows {
+        csv.push_str(&row.csv_record());
+        csv.push('\n');
+
+        let line = serde_json::to_string(row).map_err(|e| e.to_string())?;
+        head = chain_next(&head, &line);
+        jsonl.push_str(&line);
+        jsonl.push('\n');
+    }
+
+    let files = vec![
+        write_file(dir, &format!("{}.csv", table), csv.as_bytes())?,
+        write_file(dir, &format!("{}.jsonl", table), jsonl.as_bytes())?,
+    ];
+
+    Ok(ExportedTable {
+        table: table.to_string(),
+        row_count: rows.len() as u64,
+        first_id: rows.first().map(|r| r.row_id()),
+        last_id: rows.last().map(|r| r.row_id()),
+        chain_seed: seed,
+        chain_anchor: head,
+        files,
+    })
+}
+
+fn last_export_anchor(conn: &Connection) -> Result<Option<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT anchor FROM audit_exports ORDER BY id DESC LIMIT 1")
+        .map_err(|e| e.to_string())?;
+    let mut rows = stmt.query([]).map_err(|e| e.to_string())?;
+    if let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        Ok(Some(row.get(0).map_err(|e| e.to_string())?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Write a signed export of the filtered rows into a new directory under `dest_dir`.
+pub fn export_audit_to_dir(
+    conn: &Connection,
+    dest_dir: &Path,
+    filter: &AuditExportFilter,
+    exported_by: &str,
+) -> Result<AuditExportResult, String> {
+    if !dest_dir.is_dir() {
+        return Err(format!("destination is not a directory: {}", dest_dir.display()));
+    }
+
+    let now = Utc::now();
+    let export_id = uuid::Uuid::new_v4().to_string();
+    // the export id keeps two exports made within the same second apart
+    let dir: PathBuf = dest_dir.join(format!(
+        "prabal-audit-{}-{}",
+        now.format("%Y%m%d-%H%M%S"),
+        &export_id[..8]
+    ));
+    if dir.exists() {
+        return Err(format!("export directory already exists: {}", dir.display()));
+    }
+    fs::create_dir_all(&dir).map_err(|e| format!("create {}: {}", dir.display(), e))?;
+
+    let previous_anchor = last_export_anchor(conn)?;
+
+    let audit_rows = query_audit_rows(conn, filter)?;
+    let login_rows = query_login_rows(conn, filter)?;
+
+    let tables = vec![
+        write_table(
+            &dir,
+            "audit_logs",
+            &audit_rows,
+            &export_id,
+            previous_anchor.as_deref(),
+        )?,
+        write_table(
+            &dir,
+            "login_history",
+            &login_rows,
+            &export_id,
+            previous_anchor.as_deref(),
+        )?,
+    ];
+
+    let manifest = AuditExportManifest {
+        format_version: EXPORT_FORMAT_VERSION,
+        export_id: export_id.clone(),
+        generated_at: now.timestamp(),
+        exported_by: exported_by.to_string(),
+        filter: filter.clone(),
+        previous_anchor,
+        export_anchor: export_anchor(&tables),
+        tables,
+        public_key: crate::security::installation_public_key_hex()?,
+    };
+
+    let manifest_bytes = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    fs::write(dir.join(MANIFEST_FILE), &manifest_bytes)
+        .map_err(|e| format!("write manifest: {}", e))?;
+
+    let (signature, public_key) = crate::security::sign_detached(&manifest_bytes)?;
+    let sig = DetachedSignature {
+        algorithm: "ed25519".into(),
+        public_key,
+        signature: signature.clone(),
+    };
+    let sig_bytes = serde_json::to_vec_pretty(&sig).map_err(|e| e.to_string())?;
+    fs::write(dir.join(SIGNATURE_FILE), sig_bytes)
+        .map_err(|e| format!("write signature: {}", e))?;
+
+    conn.execute(
+        "INSERT INTO audit_exports (export_id, export_dir, from_ts, to_ts, audit_rows, login_rows, anchor, signature, created_by, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            export_id,
+            dir.to_string_lossy().to_string(),
+            filter.from_ts,
+            filter.to_ts,
+            audit_rows.len() as i64,
+            login_rows.len() as i64,
+            manifest.export_anchor,
+            signature,
+            exported_by,
+            now.timestamp()
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(AuditExportResult {
+        export_dir: dir.to_string_lossy().to_string(),
+        manifest,
+        signature,
+    })
+}
+
+// -----------------------------------------------------------------------------
+// Verification
+// -----------------------------------------------------------------------------
+
+/// Verify an export directory against this installation's key.
+pub fn verify_export_dir(dir: &Path) -> Result<AuditExportVerification, String> {
+    verify_export_dir_with_key(dir, &crate::security::installation_public_key_hex()?)
+}
+
+/// Verify an export directory: signature over the manifest made with
+/// `trusted_key` (hex Ed25519 public key), file hashes and sizes, row counts
+/// in both formats and the JSONL hash chain of every table. The key embedded
+/// in the export is never trusted on its own: anyone can re-sign an edited
+/// export with a key of their own.
+pub fn verify_export_dir_with_key(
+    dir: &Path,
+    trusted_key: &str,
+) -> Result<AuditExportVerification, String> {
+    let manifest_bytes = fs::read(dir.join(MANIFEST_FILE))
+        .map_err(|e| format!("read {}: {}", MANIFEST_FILE, e))?;
+    let sig_bytes = fs::read(dir.join(SIGNATURE_FILE))
+        .map_err(|e| format!("read {}: {}", SIGNATURE_FILE, e))?;
+
+    let mut problems: Vec<String> = Vec::new();
+
+    let sig: DetachedSignature =
+        serde_json::from_slice(&sig_bytes).map_err(|e| format!("parse signature: {}", e))?;
+    if sig.algorithm != "ed25519" {
+        problems.push(format!("unsupported signature algorithm '{}'", sig.algorithm));
+    }
+    let trusted_key = trusted_key.trim().to_ascii_lowercase();
+    if sig.public_key.trim().to_ascii_lowercase() != trusted_key {
+        problems.push("manifest is not signed with the trusted key".into());
+    }
+    let signature_valid =
+        crate::security::verify_detached(&trusted_key, &sig.signature, &manifest_bytes)
+            .unwrap_or(false);
+    if !signature_valid {
+        problems.push("manifest signature does not verify with the trusted key".into());
+    }
+
+    let manifest: AuditExportManifest = match serde_json::from_slice(&manifest_bytes) {
+        Ok(m) => m,
+        Err(e) => {
+            problems.push(format!("manifest is not valid: {}", e));
+            return Ok(AuditExportVerification {
+                ok: false,
+                signature_valid,
+                signed_by_this_installation: false,
+                manifest: None,
+                problems,
+            });
+        }
+    };
+
+    if manifest.public_key != sig.public_key {
+        problems.push("signature key differs from manifest key".into());
+    }
+    let signed_by_this_installation = signature_valid
+        && crate::security::installation_public_key_hex()
+            .map(|pk| pk == trusted_key)
+            .unwrap_or(false);
+
+    for t in &manifest.tables {
+        for f in &t.files {
+            let data = match fs::read(dir.join(&f.name)) {
+                Ok(d) => d,
+                Err(e) => {
+                    problems.push(format!("{}: cannot read ({})", f.name, e));
+                    continue;
+                }
+            };
+            if data.len() as u64 != f.bytes {
+                problems.push(format!(
+                    "{}: size {} does not match manifest {}",
+                    f.name,
+                    data.len(),
+                    f.bytes
+                ));
+            }
+            if sha256_hex(&data) != f.sha256 {
+                problems.push(format!("{}: sha256 does not match manifest", f.name));
+            }
+
+            let text = String::from_utf8_lossy(&data);
+            if f.name.ends_with(".jsonl") {
+                let expected_seed = chain_seed(
+                    &t.table,
+                    &manifest.export_id,
+                    manifest.previous_anchor.as_deref(),
+                );
+                if expected_seed != t.chain_seed {
+                    problems.push(format!("{}: chain seed mismatch", t.table));
+                }
+                let mut head = t.chain_seed.clone();
+                let mut count: u64 = 0;
+                for line in text.lines().filter(|l| !l.is_empty()) {
+                    head = chain_next(&head, line);
+                    count += 1;
+                }
+                if count != t.row_count {
+                    problems.push(format!(
+                        "{}: {} rows, manifest says {}",
+                        f.name, count, t.row_count
+                    ));
+                }
+                if head != t.chain_anchor {
+                    problems.push(format!("{}: hash chain anchor mismatch", f.name));
+                }
+            } else if f.name.ends_with(".csv") {
+                let count = csv_record_count(&text);
+                if count != t.row_count {
+                    problems.push(format!(
+                        "{}: {} rows, manifest says {}",
+                        f.name, count, t.row_count
+                    ));
+                }
+            }
+        }
+    }
+
+    if export_anchor(&manifest.tables) != manifest.export_anchor {
+        problems.push("export anchor does not match table anchors".into());
+    }
+
+    Ok(AuditExportVerification {
+        ok: problems.is_empty(),
+        signature_valid,
+        signed_by_this_installation,
+        manifest: Some(manifest),
+        problems,
+    })
+}
+
+// -----------------------------------------------------------------------------
+// Commands
+// -----------------------------------------------------------------------------
+
+/// Export a filtered range of audit_logs + login_history to `dest_dir` (e.g. a USB stick).
+#[command]
+pub fn admin_export_audit(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    dest_dir: String,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+    actions: Option<Vec<String>>,
+    username: Option<String>,
+) -> Result<AuditExportResult, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let filter = AuditExportFilter {
+        from_ts,
+        to_ts,
+        actions,
+        username,
+    };
+    let res = export_audit_to_dir(&conn, Path::new(&dest_dir), &filter, &admin_username)?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_export_audit",
+        None,
+        Some(&format!(
+            "Exported {} audit rows and {} login rows to {}",
+            res.manifest.tables[0].row_count, res.manifest.tables[1].row_count, res.export_dir
+        )),
+    );
+
+    Ok(res)
+}
+
+/// Verify an export directory produced by `admin_export_audit`. Exports of
+/// another installation are checked against its public key, passed as
+/// `trusted_public_key` (hex); by default this installation's key is used.
+#[command]
+pub fn admin_verify_audit_export(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    export_dir: String,
+    trusted_public_key: Option<String>,
+) -> Result<AuditExportVerification, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let trusted_public_key = trusted_public_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|k| !k.is_empty());
+    let res = match trusted_public_key {
+        Some(key) => verify_export_dir_with_key(Path::new(&export_dir), key)?,
+        None => verify_export_dir(Path::new(&export_dir))?,
+    };
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_verify_audit_export",
+        None,
+        Some(&format!(
+            "Verified {}: ok={} problems={}",
+            export_dir,
+            res.ok,
+            res.problems.len()
+        )),
+    );
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn scratch_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "audit-export-{}-{}",
+            tag,
+            uuid::Uuid::new_v4()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn row(id: i64, action: &str) -> AuditLogExportRow {
+        AuditLogExportRow {
+            id,
+            actor_user_id: Some(1),
+            actor_username: Some("admin".into()),
+            action: action.into(),
+            target_user_id: None,
+            details: None,
+            created_at: 1_700_000_000 + id,
+            target_type: None,
+            target_path: None,
+            target_dest_path: None,
+            target_file_id: None,
+        }
+    }
+
+    /// Write an export of `rows` signed with `key`, as export_audit_to_dir does.
+    fn write_export(dir: &Path, key: &SigningKey, rows: &[AuditLogExportRow]) {
+        let export_id = "test-export";
+        let tables = vec![write_table(dir, "audit_logs", rows, export_id, None).unwrap()];
+        let public_key = hex::encode(key.verifying_key().to_bytes());
+        let manifest = AuditExportManifest {
+            format_version: EXPORT_FORMAT_VERSION,
+            export_id: export_id.into(),
+            generated_at: 1_700_000_000,
+            exported_by: "admin".into(),
+            filter: AuditExportFilter::default(),
+            previous_anchor: None,
+            export_anchor: export_anchor(&tables),
+            tables,
+            public_key: public_key.clone(),
+        };
+        let manifest_bytes = serde_json::to_vec_pretty(&manifest).unwrap();
+        fs::write(dir.join(MANIFEST_FILE), &manifest_bytes).unwrap();
+        let sig = DetachedSignature {
+            algorithm: "ed25519".into(),
+            public_key,
+            signature: hex::encode(key.sign(&manifest_bytes).to_bytes()),
+        };
+        fs::write(dir.join(SIGNATURE_FILE), serde_json::to_vec(&sig).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn genuine_export_verifies_against_trusted_key() {
+        let dir = scratch_dir("genuine");
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        write_export(&dir, &key, &[row(1, "fs_delete"), row(2, "fs_copy")]);
+
+        let trusted = hex::encode(key.verifying_key().to_bytes());
+        let res = verify_export_dir_with_key(&dir, &trusted).unwrap();
+        assert!(res.ok, "{:?}", res.problems);
+        assert!(res.signature_valid);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn export_resigned_with_another_key_is_rejected() {
+        let dir = scratch_dir("forged");
+        let genuine = SigningKey::from_bytes(&[7u8; 32]);
+        let forger = SigningKey::from_bytes(&[9u8; 32]);
+        // rows edited after the fact, manifest rebuilt and re-signed by the forger
+        write_export(&dir, &forger, &[row(1, "fs_copy")]);
+
+        let trusted = hex::encode(genuine.verifying_key().to_bytes());
+        let res = verify_export_dir_with_key(&dir, &trusted).unwrap();
+        assert!(!res.ok);
+        assert!(!res.signature_valid);
+        assert!(!res.signed_by_this_installation);
+        assert!(res
+            .problems
+            .iter()
+            .any(|p| p.contains("not signed with the trusted key")));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn edited_file_is_reported() {
+        let dir = scratch_dir("edited");
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        write_export(&dir, &key, &[row(1, "fs_delete")]);
+        let csv = dir.join("audit_logs.csv");
+        let text = fs::read_to_string(&csv).unwrap().replace("fs_delete", "fs_copy__");
+        fs::write(&csv, text).unwrap();
+
+        let trusted = hex::encode(key.verifying_key().to_bytes());
+        let res = verify_export_dir_with_key(&dir, &trusted).unwrap();
+        assert!(res.signature_valid);
+        assert!(!res.ok);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
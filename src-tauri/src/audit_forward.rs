@@ -0,0 +1,758 @@
+// src-tauri/src/audit_forward.rs
+//
+// Optional forwarding of audit_logs rows to a LAN/local log collector as
+// RFC 5424 syslog or CEF, over UDP, TCP (octet-counted framing, RFC 6587) or a
+// local Unix datagram socket.
+//
+// `db::insert_audit_log` only enqueues: every event is written to the
+// disk-backed `audit_forward_queue` table and the forwarder thread drains it.
+// Rows that cannot be delivered stay queued with exponential back-off, so
+// nothing is lost across collector outages or app restarts.
+use chrono::{TimeZone, Utc};
+use once_cell::sync::Lazy;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+use sysinfo::{System, SystemExt};
+use tauri::{command, AppHandle};
+
+const CONFIG_KEY: &str = "audit_forward.config";
+const LAST_ERROR_KEY: &str = "audit_forward.last_error";
+const LAST_DELIVERED_KEY: &str = "audit_forward.last_delivered_at";
+const DROPPED_KEY: &str = "audit_forward.dropped";
+
+/// Oldest rows are dropped (and counted) beyond this many queued events.
+const MAX_QUEUE: i64 = 100_000;
+const BATCH: i64 = 200;
+const IO_TIMEOUT: Duration = Duration::from_secs(3);
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+const MAX_BACKOFF_SECS: i64 = 600;
+
+/// Structured-data ID (RFC 5424 §7.2.2 example enterprise number).
+const SD_ID: &str = "prabal@32473";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForwardConfig {
+    pub enabled: bool,
+    /// "rfc5424" | "cef"
+    pub format: String,
+    /// "udp" | "tcp" | "unix"
+    pub transport: String,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub socket_path: Option<String>,
+    #[serde(default = "default_app_name")]
+    pub app_name: String,
+    /// syslog facility code (default 13, "log audit")
+    #[serde(default = "default_facility")]
+    pub facility: u8,
+}
+
+fn default_app_name() -> String {
+    "prabal".into()
+}
+
+fn default_facility() -> u8 {
+    13
+}
+
+impl Default for ForwardConfig {
+    fn default() -> Self {
+        ForwardConfig {
+            enabled: false,
+            format: "rfc5424".into(),
+            transport: "udp".into(),
+            host: None,
+            port: Some(514),
+            socket_path: None,
+            app_name: default_app_name(),
+            facility: default_facility(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ForwardStatus {
+    pub config: ForwardConfig,
+    pub queue_depth: i64,
+    pub oldest_pending_at: Option<i64>,
+    pub dropped: i64,
+    pub last_error: Option<String>,
+    pub last_delivered_at: Option<i64>,
+}
+
+/// Snapshot of an audit row, stored in the queue so delivery does not depend
+/// on the row still existing (retention may prune it first).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct QueuedEvent {
+    id: i64,
+    actor_user_id: Option<i64>,
+    actor_username: Option<String>,
+    action: String,
+    target_user_id: Option<i64>,
+    details: Option<String>,
+    created_at: i64,
+    #[serde(default)]
+    target_path: Option<String>,
+    #[serde(default)]
+    target_file_id: Option<i64>,
+}
+
+static WAKE: Lazy<Mutex<Option<Sender<()>>>> = Lazy::new(|| Mutex::new(None));
+
+static HOSTNAME: Lazy<String> = Lazy::new(|| {
+    let sys = System::new();
+    sys.host_name()
+        .map(|h| h.replace(' ', "-"))
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "-".into())
+});
+
+// -----------------------------------------------------------------------------
+// Config
+// -----------------------------------------------------------------------------
+
+pub fn load_config(conn: &Connection) -> Result<ForwardConfig, String> {
+    match crate::db::get_setting(conn, CONFIG_KEY)? {
+        Some(raw) => serde_json::from_str(&raw).map_err(|e| format!("forward config: {}", e)),
+        None => Ok(ForwardConfig::default()),
+    }
+}
+
+fn validate_config(cfg: &ForwardConfig) -> Result<(), String> {
+    if cfg.format != "rfc5424" && cfg.format != "cef" {
+        return Err("format must be 'rfc5424' or 'cef'".into());
+    }
+    if cfg.facility > 23 {
+        return Err("facility must be 0..=23".into());
+    }
+    match cfg.transport.as_str() {
+        "udp" | "tcp" => {
+            if cfg.host.as_deref().unwrap_or("").trim().is_empty() || cfg.port.is_none() {
+                return Err("host and port are required for udp/tcp".into());
+            }
+        }
+        "unix" => {
+            if cfg!(not(unix)) {
+                return Err("unix sockets are not supported on this platform".into());
+            }
+            if cfg.socket_path.as_deref().unwrap_or("").trim().is_empty() {
+                return Err("socket_path is required for unix transport".into());
+            }
+        }
+        _ => return Err("transport must be 'udp', 'tcp' or 'unix'".into()),
+    }
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// Formatting
+// -----------------------------------------------------------------------------
+
+/// syslog severity (0..7) for an audit action.
+fn syslog_severity(action: &str) -> u8 {
+    if action == "login_failed"
+        || action == "login_rate_limited"
+        || action.ends_with("_blocked")
+        || action.ends_with("_denied")
+    {
+        4 // warning
+    } else if action.contains("delete") || action.starts_with("admin_") {
+        5 // notice
+    } else {
+        6 // informational
+    }
+}
+
+fn sd_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+}
+
+fn header_token(s: &str, max: usize) -> String {
+    let t: String = s
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(max)
+        .collect();
+    if t.is_empty() {
+        "-".into()
+    } else {
+        t
+    }
+}
+
+fn rfc3339(ts: i64) -> String {
+    Utc.timestamp_opt(ts, 0)
+        .single()
+        .map(|t| t.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_else(|| "-".into())
+}
+
+fn syslog_header(cfg: &ForwardConfig, ev: &QueuedEvent) -> String {
+    let pri = cfg.facility as u32 * 8 + syslog_severity(&ev.action) as u32;
+    format!(
+        "<{}>1 {} {} {} {} {}",
+        pri,
+        rfc3339(ev.created_at),
+        header_token(&HOSTNAME, 255),
+        header_token(&cfg.app_name, 48),
+        std::process::id(),
+        header_token(&ev.action, 32)
+    )
+}
+
+fn format_rfc5424(cfg: &ForwardConfig, ev: &QueuedEvent) -> String {
+    let mut sd = format!("[{} id=\"{}\"", SD_ID, ev.id);
+    if let Some(uid) = ev.actor_user_id {
+        sd.push_str(&format!(" actorId=\"{}\"", uid));
+    }
+    if let Some(ref u) = ev.actor_username {
+        sd.push_str(&format!(" actor=\"{}\"", sd_escape(u)));
+    }
+    sd.push_str(&format!(" action=\"{}\"", sd_escape(&ev.action)));
+    if let Some(t) = ev.target_user_id {
+        sd.push_str(&format!(" targetUserId=\"{}\"", t));
+    }
+    if let Some(ref p) = ev.target_path {
+        sd.push_str(&format!(" filePath=\"{}\"", sd_escape(p)));
+    }
+    if let Some(f) = ev.target_file_id {
+        sd.push_str(&format!(" fileId=\"{}\"", f));
+    }
+    sd.push(']');
+
+    match ev.details.as_deref() {
+        Some(d) if !d.is_empty() => format!("{} {} {}", syslog_header(cfg, ev), sd, d),
+        _ => format!("{} {}", syslog_header(cfg, ev), sd),
+    }
+}
+
+fn cef_header_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+fn cef_ext_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('=', "\\=")
+        .replace('\r', "")
+        .replace('\n', "\\n")
+}
+
+fn format_cef(cfg: &ForwardConfig, ev: &QueuedEvent) -> String {
+    // CEF severity is 0..10
+    let severity = match syslog_severity(&ev.action) {
+        4 => 7,
+        5 => 5,
+        _ => 3,
+    };
+    let mut ext = vec![
+        format!("rt={}", ev.created_at * 1000),
+        format!("externalId={}", ev.id),
+        format!("act={}", cef_ext_escape(&ev.action)),
+    ];
+    if let Some(uid) = ev.actor_user_id {
+        ext.push(format!("suid={}", uid));
+    }
+    if let Some(ref u) = ev.actor_username {
+        ext.push(format!("suser={}", cef_ext_escape(u)));
+    }
+    if let Some(t) = ev.target_user_id {
+        ext.push(format!("duid={}", t));
+    }
+    if let Some(ref p) = ev.target_path {
+        ext.push(format!("filePath={}", cef_ext_escape(p)));
+    }
+    if let Some(f) = ev.target_file_id {
+        ext.push(format!("fileId={}", f));
+    }
+    if let Some(ref d) = ev.details {
+        ext.push(format!("msg={}", cef_ext_escape(d)));
+    }
+    format!(
+        "{} - CEF:0|PRABAL|VaultGuard|{}|{}|{}|{}|{}",
+        syslog_header(cfg, ev),
+        cef_header_escape(env!("CARGO_PKG_VERSION")),
+        cef_header_escape(&ev.action),
+        cef_header_escape(&ev.action.replace('_', " ")),
+        severity,
+        ext.join(" ")
+    )
+}
+
+fn format_event(cfg: &ForwardConfig, ev: &QueuedEvent) -> String {
+    if cfg.format == "cef" {
+        format_cef(cfg, ev)
+    } else {
+        format_rfc5424(cfg, ev)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Transport
+// -----------------------------------------------------------------------------
+
+enum Link {
+    Udp(UdpSocket, std::net::SocketAddr),
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixDatagram, String),
+}
+
+impl Link {
+    fn open(cfg: &ForwardConfig) -> Result<Link, String> {
+        match cfg.transport.as_str() {
+            "udp" | "tcp" => {
+                let host = cfg.host.clone().unwrap_or_default();
+                let port = cfg.port.unwrap_or(514);
+                let addr = (host.as_str(), port)
+                    .to_socket_addrs()
+                    .map_err(|e| format!("resolve {}:{}: {}", host, port, e))?
+                    .next()
+                    .ok_or_else(|| format!("no address for {}:{}", host, port))?;
+                if cfg.transport == "udp" {
+                    let bind = if addr.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+                    let sock = UdpSocket::bind(bind).map_err(|e| format!("udp bind: {}", e))?;
+                    Ok(Link::Udp(sock, addr))
+                } else {
+                    let stream = TcpStream::connect_timeout(&addr, IO_TIMEOUT)
+                        .map_err(|e| format!("tcp connect {}: {}", addr, e))?;
+                    let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+                    Ok(Link::Tcp(stream))
+                }
+            }
+            #[cfg(unix)]
+            "unix" => {
+                let path = cfg.socket_path.clone().unwrap_or_default();
+                let sock = std::os::unix::net::UnixDatagram::unbound()
+                    .map_err(|e| format!("unix socket: {}", e))?;
+                let _ = sock.set_write_timeout(Some(IO_TIMEOUT));
+                Ok(Link::Unix(sock, path))
+            }
+            other => Err(format!("unsupported transport '{}'", other)),
+        }
+    }
+
+    fn send(&mut self, msg: &str) -> Result<(), String> {
+        match self {
+            Link::Udp(sock, addr) => sock
+                .send_to(msg.as_bytes(), *addr)
+                .map(|_| ())
+                .map_err(|e| format!("udp send: {}", e)),
+            Link::Tcp(stream) => {
+                // octet-counting framing (RFC 6587 §3.4.1)
+                let framed = format!("{} {}", msg.len(), msg);
+                stream
+                    .write_all(framed.as_bytes())
+                    .map_err(|e| format!("tcp send: {}", e))
+            }
+            #[cfg(unix)]
+            Link::Unix(sock, path) => sock
+                .send_to(msg.as_bytes(), path.as_str())
+                .map(|_| ())
+                .map_err(|e| format!("unix send: {}", e)),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Queue
+// -----------------------------------------------------------------------------
+
+fn wake_forwarder() {
+    if let Ok(guard) = WAKE.lock() {
+        if let Some(tx) = guard.as_ref() {
+            let _ = tx.send(());
+        }
+    }
+}
+
+/// Queue an audit row for forwarding (no-op when forwarding is disabled).
+pub fn enqueue(conn: &Connection, audit_id: i64) -> Result<(), String> {
+    if !load_config(conn)?.enabled {
+        return Ok(());
+    }
+
+    let ev = conn
+        .query_row(
+            "SELECT id, actor_user_id, actor_username, action, target_user_id, details, created_at,
+                    target_path, target_file_id
+             FROM audit_logs WHERE id = ?1",
+            params![audit_id],
+            |r| {
+                Ok(QueuedEvent {
+                    id: r.get(0)?,
+                    actor_user_id: r.get(1)?,
+                    actor_username: r.get(2)?,
+                    action: r.get(3)?,
+                    target_user_id: r.get(4)?,
+                    details: r.get(5)?,
+                    created_at: r.get(6)?,
+                    target_path: r.get(7)?,
+                    target_file_id: r.get(8)?,
+                })
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    let payload = serde_json::to_string(&ev).map_err(|e| e.to_string())?;
+    let now = Utc::now().timestamp();
+    conn.execute(
+        "INSERT INTO audit_forward_queue (audit_id, payload, enqueued_at, attempts, next_attempt_at)
+         VALUES (?1, ?2, ?3, 0, ?3)",
+        params![audit_id, payload, now],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // bound the queue: drop the oldest rows and count them
+    let depth: i64 = conn
+        .query_row("SELECT COUNT(*) FROM audit_forward_queue", [], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+    if depth > MAX_QUEUE {
+        let excess = depth - MAX_QUEUE;
+        conn.execute(
+            "DELETE FROM audit_forward_queue WHERE id IN (SELECT id FROM audit_forward_queue ORDER BY id ASC LIMIT ?1)",
+            params![excess],
+        )
+        .map_err(|e| e.to_string())?;
+        let dropped: i64 = crate::db::get_setting(conn, DROPPED_KEY)?
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        crate::db::set_setting(conn, DROPPED_KEY, &(dropped + excess).to_string())?;
+    }
+
+    wake_forwarder();
+    Ok(())
+}
+
+/// Deliver due queue rows in order. Stops at the first failure and backs off only
+/// that row; rows queued behind a backed-off row wait for it, so the collector
+/// never receives events out of order.
+fn drain_queue(conn: &Connection) -> Result<usize, String> {
+    let cfg = load_config(conn)?;
+    if !cfg.enabled {
+        return Ok(0);
+    }
+    let now = Utc::now().timestamp();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, payload, attempts FROM audit_forward_queue
+             WHERE next_attempt_at <= ?1
+               AND id < COALESCE((SELECT MIN(id) FROM audit_forward_queue WHERE next_attempt_at > ?1),
+                                 9223372036854775807)
+             ORDER BY id ASC LIMIT ?2",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![now, BATCH], |r| {
+            Ok((
+                r.get::<_, i64>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, i64>(2)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    let mut due = Vec::new();
+    for r in rows {
+        due.push(r.map_err(|e| e.to_string())?);
+    }
+    if due.is_empty() {
+        return Ok(0);
+    }
+
+    let mut delivered = 0usize;
+    let mut link: Option<Link> = None;
+    for (qid, payload, attempts) in &due {
+        let ev: QueuedEvent = match serde_json::from_str(payload) {
+            Ok(ev) => ev,
+            Err(_) => {
+                // unreadable row can never be delivered
+                let _ = conn.execute("DELETE FROM audit_forward_queue WHERE id = ?1", params![qid]);
+                continue;
+            }
+        };
+        let res = match link.as_mut() {
+            Some(l) => l.send(&format_event(&cfg, &ev)),
+            None => Link::open(&cfg).and_then(|mut l| {
+                let r = l.send(&format_event(&cfg, &ev));
+                link = Some(l);
+                r
+            }),
+        };
+        match res {
+            Ok(()) => {
+                conn.execute("DELETE FROM audit_forward_queue WHERE id = ?1", params![qid])
+                    .map_err(|e| e.to_string())?;
+                delivered += 1;
+            }
+            Err(e) => {
+                let backoff = (5i64 << (*attempts).min(7)).min(MAX_BACKOFF_SECS);
+                conn.execute(
+                    "UPDATE audit_forward_queue SET attempts = attempts + 1, next_attempt_at = ?1, last_error = ?2
+                     WHERE id = ?3",
+                    params![now + backoff, e, qid],
+                )
+                .map_err(|e| e.to_string())?;
+                crate::db::set_setting(conn, LAST_ERROR_KEY, &e)?;
+                break;
+            }
+        }
+    }
+    if delivered > 0 {
+        crate::db::set_setting(conn, LAST_DELIVERED_KEY, &now.to_string())?;
+    }
+    Ok(delivered)
+}
+
+/// Start the background forwarder. Safe to call once at startup.
+pub fn start_forwarder() {
+    let (tx, rx) = channel::<()>();
+    if let Ok(mut guard) = WAKE.lock() {
+        if guard.is_some() {
+            return;
+        }
+        *guard = Some(tx);
+    }
+
+    std::thread::spawn(move || {
+        let mut conn: Option<Connection> = None;
+        loop {
+            match rx.recv_timeout(POLL_INTERVAL) {
+                Ok(()) | Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+            // coalesce bursts of wake-ups
+            while rx.try_recv().is_ok() {}
+
+            if conn.is_none() {
+                match crate::db::open_connection() {
+                    Ok(c) => conn = Some(c),
+                    Err(e) => {
+                        eprintln!("[audit_forward] db open failed: {}", e);
+                        continue;
+                    }
+                }
+            }
+            if let Some(ref c) = conn {
+                loop {
+                    match drain_queue(c) {
+                        Ok(n) if n as i64 == BATCH => continue,
+                        Ok(_) => break,
+                        Err(e) => {
+                            eprintln!("[audit_forward] drain failed: {}", e);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+// -----------------------------------------------------------------------------
+// Commands
+// -----------------------------------------------------------------------------
+
+fn status(conn: &Connection) -> Result<ForwardStatus, String> {
+    let (depth, oldest): (i64, Option<i64>) = conn
+        .query_row(
+            "SELECT COUNT(*), MIN(enqueued_at) FROM audit_forward_queue",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(ForwardStatus {
+        config: load_config(conn)?,
+        queue_depth: depth,
+        oldest_pending_at: oldest,
+        dropped: crate::db::get_setting(conn, DROPPED_KEY)?
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0),
+        last_error: crate::db::get_setting(conn, LAST_ERROR_KEY)?,
+        last_delivered_at: crate::db::get_setting(conn, LAST_DELIVERED_KEY)?
+            .and_then(|v| v.parse().ok()),
+    })
+}
+
+#[command]
+pub fn admin_get_audit_forwarding(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<ForwardStatus, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    status(&conn)
+}
+
+#[command]
+pub fn admin_set_audit_forwarding(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    config: ForwardConfig,
+) -> Result<ForwardStatus, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    if config.enabled {
+        validate_config(&config)?;
+    }
+    let raw = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    crate::db::set_setting(&conn, CONFIG_KEY, &raw)?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_set_audit_forwarding",
+        None,
+        Some(&format!(
+            "enabled={} format={} transport={} target={}",
+            config.enabled,
+            config.format,
+            config.transport,
+            if config.transport == "unix" {
+                config.socket_path.clone().unwrap_or_default()
+            } else {
+                format!(
+                    "{}:{}",
+                    config.host.clone().unwrap_or_default(),
+                    config.port.unwrap_or(0)
+                )
+            }
+        )),
+    );
+    wake_forwarder();
+
+    status(&conn)
+}
+
+/// Send one synthetic event straight to the collector (bypasses the queue).
+#[command]
+pub fn admin_test_audit_forwarding(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<String, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let cfg = load_config(&conn)?;
+    validate_config(&cfg)?;
+    let ev = QueuedEvent {
+        id: 0,
+        actor_user_id: Some(admin_id),
+        actor_username: Some(admin_username),
+        action: "forwarding_test".into(),
+        target_user_id: None,
+        details: Some("PRABAL audit forwarding test".into()),
+        created_at: Utc::now().timestamp(),
+        target_path: None,
+        target_file_id: None,
+    };
+    let msg = format_event(&cfg, &ev);
+    Link::open(&cfg)?.send(&msg)?;
+    Ok(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+
+    fn queue_db(port: u16) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::init_db_schema(&conn).unwrap();
+        let cfg = ForwardConfig {
+            enabled: true,
+            transport: "tcp".into(),
+            host: Some("127.0.0.1".into()),
+            port: Some(port),
+            ..ForwardConfig::default()
+        };
+        crate::db::set_setting(&conn, CONFIG_KEY, &serde_json::to_string(&cfg).unwrap()).unwrap();
+        conn
+    }
+
+    fn queue_event(conn: &Connection, action: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO audit_logs (actor_username, action, created_at) VALUES ('op', ?1, 0)",
+            params![action],
+        )
+        .unwrap();
+        let id = conn.last_insert_rowid();
+        enqueue(conn, id).unwrap();
+        id
+    }
+
+    /// Split an octet-counted stream (RFC 6587 §3.4.1) back into messages.
+    fn frames(mut raw: &str) -> Vec<String> {
+        let mut out = Vec::new();
+        while let Some(sp) = raw.find(' ') {
+            let len: usize = raw[..sp].parse().unwrap();
+            out.push(raw[sp + 1..sp + 1 + len].to_string());
+            raw = &raw[sp + 1 + len..];
+        }
+        out
+    }
+
+    #[test]
+    fn tcp_collector_receives_rows_in_order() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let conn = queue_db(listener.local_addr().unwrap().port());
+        let ids: Vec<i64> = ["fs_copy", "fs_delete", "open_file"]
+            .iter()
+            .map(|a| queue_event(&conn, a))
+            .collect();
+
+        let collector = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut raw = String::new();
+            stream.read_to_string(&mut raw).unwrap();
+            raw
+        });
+        assert_eq!(drain_queue(&conn).unwrap(), 3);
+        drop(conn); // closes the link so the collector sees EOF
+
+        let got = frames(&collector.join().unwrap());
+        assert_eq!(got.len(), 3);
+        for ((msg, id), action) in got.iter().zip(&ids).zip(["fs_copy", "fs_delete", "open_file"]) {
+            assert!(msg.contains(&format!("id=\"{}\"", id)), "{}", msg);
+            assert!(msg.contains(&format!("action=\"{}\"", action)), "{}", msg);
+        }
+    }
+
+    #[test]
+    fn failed_delivery_backs_off_only_the_failing_row() {
+        // bind and drop a listener to get a port nothing listens on
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let conn = queue_db(port);
+        for a in ["fs_copy", "fs_delete", "open_file"] {
+            queue_event(&conn, a);
+        }
+
+        assert_eq!(drain_queue(&conn).unwrap(), 0);
+        let attempts: Vec<i64> = conn
+            .prepare("SELECT attempts FROM audit_forward_queue ORDER BY id")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(attempts, vec![1, 0, 0]);
+        // the rows behind the backed-off head are held back, not sent out of order
+        assert_eq!(drain_queue(&conn).unwrap(), 0);
+    }
+}
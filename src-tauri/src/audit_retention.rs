@@ -0,0 +1,966 @@
+// src-tauri/src/audit_retention.rs
+//
+// Retention for the activity tables (audit_logs, login_history, failed_logins,
+// recent_access). Rows older than their policy are written to a gzip'd JSONL
+// archive under `<data dir>/archives`, signed with the installation key, and
+// only then deleted. Archives can be restored into a standalone sqlite file
+// that is opened read-only for investigations.
+use chrono::Utc;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::{params, Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle};
+
+use crate::security::sha256_hex;
+
+/// Tables that retention may prune, with their timestamp column.
+const RETENTION_TABLES: &[(&str, &str)] = &[
+    ("audit_logs", "created_at"),
+    ("login_history", "created_at"),
+    ("failed_logins", "attempted_at"),
+    ("recent_access", "accessed_at"),
+];
+
+/// Named action classes for `audit_logs` (LIKE patterns on `action`).
+/// Class "*" covers every action not claimed by a class that has its own policy.
+const ACTION_CLASSES: &[(&str, &[&str])] = &[
+    ("auth", &["login_%", "logout", "register"]),
+    ("admin", &["admin_%"]),
+    ("file", &["fs_%", "open_file", "open_path"]),
+    ("index", &["index_%"]),
+];
+
+pub const ALL_ACTIONS: &str = "*";
+/// Longest retention a policy may ask for (about a century; "keep forever").
+const MAX_RETAIN_DAYS: i64 = 36_500;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionPolicy {
+    pub id: i64,
+    pub table_name: String,
+    pub action_class: String,
+    pub retain_days: i64,
+    pub updated_by: Option<String>,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionOutcome {
+    pub table_name: String,
+    pub action_class: String,
+    pub cutoff_ts: i64,
+    pub rows: u64,
+    pub archive_id: Option<String>,
+    pub archive_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub archive_id: String,
+    pub table_name: String,
+    pub action_class: String,
+    pub row_count: u64,
+    pub first_id: Option<i64>,
+    pub last_id: Option<i64>,
+    pub cutoff_ts: i64,
+    pub sha256: String,
+    pub created_at: i64,
+    pub public_key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SignedArchiveManifest {
+    manifest: ArchiveManifest,
+    signature: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditArchive {
+    pub id: i64,
+    pub archive_id: String,
+    pub table_name: String,
+    pub action_class: String,
+    pub file_path: String,
+    pub row_count: i64,
+    pub first_id: Option<i64>,
+    pub last_id: Option<i64>,
+    pub cutoff_ts: i64,
+    pub sha256: String,
+    pub created_by: Option<String>,
+    pub created_at: i64,
+    pub restored_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoredArchive {
+    pub archive_id: String,
+    pub table_name: String,
+    pub db_path: String,
+    pub row_count: u64,
+}
+
+// -----------------------------------------------------------------------------
+// Helpers
+// -----------------------------------------------------------------------------
+
+fn ts_column(table: &str) -> Result<&'static str, String> {
+    RETENTION_TABLES
+        .iter()
+        .find(|(t, _)| *t == table)
+        .map(|(_, c)| *c)
+        .ok_or_else(|| format!("retention not supported for table '{}'", table))
+}
+
+fn class_patterns(class: &str) -> Option<&'static [&'static str]> {
+    ACTION_CLASSES
+        .iter()
+        .find(|(c, _)| *c == class)
+        .map(|(_, p)| *p)
+}
+
+fn validate_policy(table: &str, action_class: &str) -> Result<(), String> {
+    ts_column(table)?;
+    if action_class == ALL_ACTIONS {
+        return Ok(());
+    }
+    if table != "audit_logs" {
+        return Err("action classes only apply to audit_logs".into());
+    }
+    if class_patterns(action_class).is_none() {
+        let known = ACTION_CLASSES
+            .iter()
+            .map(|(c, _)| *c)
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(format!(
+            "unknown action class '{}' (known: {}, *)",
+            action_class, known
+        ));
+    }
+    Ok(())
+}
+
+fn like_any(patterns: &[&str]) -> String {
+    let parts = patterns
+        .iter()
+        .map(|p| format!("action LIKE '{}'", p.replace('\'', "''")))
+        .collect::<Vec<_>>();
+    format!("({})", parts.join(" OR "))
+}
+
+/// WHERE clause selecting rows of `table`/`class` older than the cutoff (bound as ?1).
+fn expired_where(table: &str, class: &str, policies: &[RetentionPolicy]) -> Result<String, String> {
+    let ts_col = ts_column(table)?;
+    let mut sql = format!("{} < ?1", ts_col);
+    if table == "audit_logs" {
+        if let Some(patterns) = class_patterns(class) {
+            sql.push_str(&format!(" AND {}", like_any(patterns)));
+        } else {
+            // "*" — exclude classes that carry their own policy
+            for p in policies
+                .iter()
+                .filter(|p| p.table_name == table && p.action_class != ALL_ACTIONS)
+            {
+                if let Some(patterns) = class_patterns(&p.action_class) {
+                    sql.push_str(&format!(" AND NOT {}", like_any(patterns)));
+                }
+            }
+        }
+    }
+    Ok(sql)
+}
+
+fn archives_dir() -> Result<PathBuf, String> {
+    let mut p = crate::db::data_dir()?;
+    p.push("archives");
+    fs::create_dir_all(&p).map_err(|e| format!("create {}: {}", p.display(), e))?;
+    Ok(p)
+}
+
+fn sidecar_path(archive: &Path) -> PathBuf {
+    let mut s = archive.as_os_str().to_owned();
+    s.push(".sig.json");
+    PathBuf::from(s)
+}
+
+/// Archives are written under a `.partial` name and only renamed into place once
+/// the transaction that deletes their rows has committed.
+fn partial_path(path: &Path) -> PathBuf {
+    let mut s = path.as_os_str().to_owned();
+    s.push(".partial");
+    PathBuf::from(s)
+}
+
+fn publish_archive(path: &Path) -> Result<(), String> {
+    let sidecar = sidecar_path(path);
+    fs::rename(partial_path(&sidecar), &sidecar)
+        .map_err(|e| format!("publish {}: {}", sidecar.display(), e))?;
+    fs::rename(partial_path(path), path).map_err(|e| format!("publish {}: {}", path.display(), e))
+}
+
+fn discard_archive(path: &Path) {
+    let _ = fs::remove_file(partial_path(path));
+    let _ = fs::remove_file(partial_path(&sidecar_path(path)));
+}
+
+fn value_to_json(v: rusqlite::types::ValueRef<'_>) -> serde_json::Value {
+    use rusqlite::types::ValueRef;
+    match v {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => serde_json::json!(i),
+        ValueRef::Real(f) => serde_json::json!(f),
+        ValueRef::Text(t) => serde_json::json!(String::from_utf8_lossy(t)),
+        ValueRef::Blob(b) => serde_json::json!(hex::encode(b)),
+    }
+}
+
+fn json_to_value(v: &serde_json::Value) -> rusqlite::types::Value {
+    use rusqlite::types::Value;
+    match v {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Integer(*b as i64),
+        serde_json::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::Integer(i)
+            } else {
+                Value::Real(n.as_f64().unwrap_or(0.0))
+            }
+        }
+        serde_json::Value::String(s) => Value::Text(s.clone()),
+        other => Value::Text(other.to_string()),
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Policies
+// -----------------------------------------------------------------------------
+
+pub fn list_policies(conn: &Connection) -> Result<Vec<RetentionPolicy>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, table_name, action_class, retain_days, updated_by, updated_at
+             FROM retention_policies ORDER BY table_name, action_class",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |r| {
+            Ok(RetentionPolicy {
+                id: r.get(0)?,
+                table_name: r.get(1)?,
+                action_class: r.get(2)?,
+                retain_days: r.get(3)?,
+                updated_by: r.get(4)?,
+                updated_at: r.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(out)
+}
+
+// -----------------------------------------------------------------------------
+// Archive + prune
+// -----------------------------------------------------------------------------
+
+fn archive_rows(
+    conn: &Connection,
+    table: &str,
+    class: &str,
+    where_sql: &str,
+    cutoff: i64,
+    actor: &str,
+) -> Result<Option<(String, PathBuf, u64)>, String> {
+    let sql = format!("SELECT * FROM {} WHERE {} ORDER BY id ASC", table, where_sql);
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let id_idx = columns.iter().position(|c| c == "id").unwrap_or(0);
+
+    let mut rows = stmt.query(params![cutoff]).map_err(|e| e.to_string())?;
+    let mut jsonl = String::new();
+    let mut count: u64 = 0;
+    let mut first_id: Option<i64> = None;
+    let mut last_id: Option<i64> = None;
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let mut obj = serde_json::Map::new();
+        for (i, c) in columns.iter().enumerate() {
+            let v = row.get_ref(i).map_err(|e| e.to_string())?;
+            obj.insert(c.clone(), value_to_json(v));
+        }
+        let id: i64 = row.get(id_idx).map_err(|e| e.to_string())?;
+        first_id.get_or_insert(id);
+        last_id = Some(id);
+        jsonl.push_str(&serde_json::Value::Object(obj).to_string());
+        jsonl.push('\n');
+        count += 1;
+    }
+
+    if count == 0 {
+        return Ok(None);
+    }
+
+    let mut enc = GzEncoder::new(Vec::new(), Compression::best());
+    enc.write_all(jsonl.as_bytes())
+        .map_err(|e| format!("compress archive: {}", e))?;
+    let gz = enc.finish().map_err(|e| format!("compress archive: {}", e))?;
+
+    let now = Utc::now();
+    let archive_id = uuid::Uuid::new_v4().to_string();
+    let class_tag = if class == ALL_ACTIONS { "all" } else { class };
+    let path = archives_dir()?.join(format!(
+        "{}-{}-{}-{}.jsonl.gz",
+        table,
+        class_tag,
+        now.format("%Y%m%d%H%M%S"),
+        &archive_id[..8]
+    ));
+    let res = write_archive(
+        conn, table, class, &path, &gz, &archive_id, now, count, first_id, last_id, cutoff, actor,
+    );
+    if let Err(e) = res {
+        discard_archive(&path);
+        return Err(e);
+    }
+
+    Ok(Some((archive_id, path, count)))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_archive(
+    conn: &Connection,
+    table: &str,
+    class: &str,
+    path: &Path,
+    gz: &[u8],
+    archive_id: &str,
+    now: chrono::DateTime<Utc>,
+    count: u64,
+    first_id: Option<i64>,
+    last_id: Option<i64>,
+    cutoff: i64,
+    actor: &str,
+) -> Result<(), String> {
+    let partial = partial_path(path);
+    fs::write(&partial, gz).map_err(|e| format!("write {}: {}", partial.display(), e))?;
+
+    let manifest = ArchiveManifest {
+        archive_id: archive_id.to_string(),
+        table_name: table.to_string(),
+        action_class: class.to_string(),
+        row_count: count,
+        first_id,
+        last_id,
+        cutoff_ts: cutoff,
+        sha256: sha256_hex(gz),
+        created_at: now.timestamp(),
+        public_key: crate::security::installation_public_key_hex()?,
+    };
+    let manifest_bytes = serde_json::to_vec(&manifest).map_err(|e| e.to_string())?;
+    let (signature, _) = crate::security::sign_detached(&manifest_bytes)?;
+    let signed = SignedArchiveManifest {
+        manifest: manifest.clone(),
+        signature: signature.clone(),
+    };
+    fs::write(
+        partial_path(&sidecar_path(path)),
+        serde_json::to_vec_pretty(&signed).map_err(|e| e.to_string())?,
+    )
+    .map_err(|e| format!("write archive signature: {}", e))?;
+
+    conn.execute(
+        "INSERT INTO audit_archives (archive_id, table_name, action_class, file_path, row_count, first_id, last_id, cutoff_ts, sha256, signature, created_by, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            archive_id,
+            table,
+            class,
+            path.to_string_lossy().to_string(),
+            count as i64,
+            first_id,
+            last_id,
+            cutoff,
+            manifest.sha256,
+            signature,
+            actor,
+            now.timestamp()
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Apply every retention policy: archive expired rows, then delete them.
+/// With `dry_run` only the row counts are reported.
+pub fn run_retention(
+    conn: &Connection,
+    actor: &str,
+    dry_run: bool,
+) -> Result<Vec<RetentionOutcome>, String> {
+    let policies = list_policies(conn)?;
+    let now = Utc::now().timestamp();
+    let mut out = Vec::new();
+
+    for p in &policies {
+        if p.retain_days < 0 {
+            continue;
+        }
+        // saturating: a policy stored before MAX_RETAIN_DAYS existed may hold
+        // a value whose cutoff would wrap into the future
+        let cutoff = now.saturating_sub(p.retain_days.saturating_mul(86_400));
+        let where_sql = expired_where(&p.table_name, &p.action_class, &policies)?;
+
+        if dry_run {
+            let n: i64 = conn
+                .query_row(
+                    &format!("SELECT COUNT(*) FROM {} WHERE {}", p.table_name, where_sql),
+                    params![cutoff],
+                    |r| r.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            out.push(RetentionOutcome {
+                table_name: p.table_name.clone(),
+                action_class: p.action_class.clone(),
+                cutoff_ts: cutoff,
+                rows: n as u64,
+                archive_id: None,
+                archive_path: None,
+            });
+            continue;
+        }
+
+        conn.execute_batch("BEGIN IMMEDIATE")
+            .map_err(|e| e.to_string())?;
+        let mut written: Option<PathBuf> = None;
+        let res = (|| -> Result<RetentionOutcome, String> {
+            let archived =
+                archive_rows(conn, &p.table_name, &p.action_class, &where_sql, cutoff, actor)?;
+            written = archived.as_ref().map(|(_, path, _)| path.clone());
+            let mut outcome = RetentionOutcome {
+                table_name: p.table_name.clone(),
+                action_class: p.action_class.clone(),
+                cutoff_ts: cutoff,
+                rows: 0,
+                archive_id: None,
+                archive_path: None,
+            };
+            if let Some((archive_id, path, count)) = archived {
+                conn.execute(
+                    &format!("DELETE FROM {} WHERE {}", p.table_name, where_sql),
+                    params![cutoff],
+                )
+                .map_err(|e| e.to_string())?;
+                outcome.rows = count;
+                outcome.archive_id = Some(archive_id);
+                outcome.archive_path = Some(path.to_string_lossy().to_string());
+            }
+            Ok(outcome)
+        })();
+        let res = res.and_then(|o| {
+            conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+            Ok(o)
+        });
+        match res {
+            Ok(o) => {
+                if let Some(path) = &written {
+                    publish_archive(path)?;
+                }
+                out.push(o);
+            }
+            Err(e) => {
+                let _ = conn.execute_batch("ROLLBACK");
+                if let Some(path) = &written {
+                    discard_archive(path);
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+// -----------------------------------------------------------------------------
+// Restore
+// -----------------------------------------------------------------------------
+
+fn get_archive(conn: &Connection, archive_id: &str) -> Result<AuditArchive, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, archive_id, table_name, action_class, file_path, row_count, first_id, last_id,
+                    cutoff_ts, sha256, created_by, created_at, restored_path
+             FROM audit_archives WHERE archive_id = ?1",
+        )
+        .map_err(|e| e.to_string())?;
+    let mut rows = stmt.query(params![archive_id]).map_err(|e| e.to_string())?;
+    if let Some(r) = rows.next().map_err(|e| e.to_string())? {
+        Ok(archive_from_row(r).map_err(|e| e.to_string())?)
+    } else {
+        Err("archive not found".into())
+    }
+}
+
+fn archive_from_row(r: &rusqlite::Row<'_>) -> rusqlite::Result<AuditArchive> {
+    Ok(AuditArchive {
+        id: r.get(0)?,
+        archive_id: r.get(1)?,
+        table_name: r.get(2)?,
+        action_class: r.get(3)?,
+        file_path: r.get(4)?,
+        row_count: r.get(5)?,
+        first_id: r.get(6)?,
+        last_id: r.get(7)?,
+        cutoff_ts: r.get(8)?,
+        sha256: r.get(9)?,
+        created_by: r.get(10)?,
+        created_at: r.get(11)?,
+        restored_path: r.get(12)?,
+    })
+}
+
+/// Read and verify an archive file against its signed sidecar. Returns the JSONL text.
+fn read_verified_archive(path: &Path) -> Result<(ArchiveManifest, String), String> {
+    read_verified_archive_with_key(path, &crate::security::installation_public_key_hex()?)
+}
+
+/// The sidecar names its own key; only `trusted_key` is accepted.
+fn read_verified_archive_with_key(
+    path: &Path,
+    trusted_key: &str,
+) -> Result<(ArchiveManifest, String), String> {
+    let signed_bytes =
+        fs::read(sidecar_path(path)).map_err(|e| format!("read archive signature: {}", e))?;
+    let signed: SignedArchiveManifest =
+        serde_json::from_slice(&signed_bytes).map_err(|e| format!("parse signature: {}", e))?;
+    if signed.manifest.public_key != trusted_key {
+        return Err("archive is not signed with the trusted key".into());
+    }
+    let manifest_bytes = serde_json::to_vec(&signed.manifest).map_err(|e| e.to_string())?;
+    if !crate::security::verify_detached(trusted_key, &signed.signature, &manifest_bytes)? {
+        return Err("archive signature does not verify".into());
+    }
+
+    let gz = fs::read(path).map_err(|e| format!("read {}: {}", path.display(), e))?;
+    if sha256_hex(&gz) != signed.manifest.sha256 {
+        return Err("archive contents do not match signed hash".into());
+    }
+
+    let mut text = String::new();
+    GzDecoder::new(gz.as_slice())
+        .read_to_string(&mut text)
+        .map_err(|e| format!("decompress archive: {}", e))?;
+    Ok((signed.manifest, text))
+}
+
+/// Restore an archive into `<archives>/restored/<archive_id>.sqlite` (one table named
+/// after the source table). The file is rebuilt from the verified archive every time.
+pub fn restore_archive(conn: &Connection, archive_id: &str) -> Result<RestoredArchive, String> {
+    let archive = get_archive(conn, archive_id)?;
+    let (manifest, text) = read_verified_archive(Path::new(&archive.file_path))?;
+
+    let mut dir = archives_dir()?;
+    dir.push("restored");
+    fs::create_dir_all(&dir).map_err(|e| format!("create {}: {}", dir.display(), e))?;
+    let db_path = dir.join(format!("{}.sqlite", archive_id));
+    if db_path.exists() {
+        fs::remove_file(&db_path).map_err(|e| format!("remove old restore: {}", e))?;
+    }
+
+    let mut rconn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let tx = rconn.transaction().map_err(|e| e.to_string())?;
+    let mut columns: Vec<String> = Vec::new();
+    let mut count: u64 = 0;
+    for line in text.lines().filter(|l| !l.is_empty()) {
+        let obj: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(line).map_err(|e| format!("archive row: {}", e))?;
+        if columns.is_empty() {
+            columns = obj.keys().cloned().collect();
+            let cols_sql = columns
+                .iter()
+                .map(|c| format!("\"{}\"", c.replace('"', "")))
+                .collect::<Vec<_>>()
+                .join(", ");
+            tx.execute_batch(&format!(
+                "CREATE TABLE \"{}\" ({});",
+                manifest.table_name, cols_sql
+            ))
+            .map_err(|e| e.to_string())?;
+        }
+        let marks = vec!["?"; columns.len()].join(", ");
+        let values: Vec<rusqlite::types::Value> = columns
+            .iter()
+            .map(|c| json_to_value(obj.get(c).unwrap_or(&serde_json::Value::Null)))
+            .collect();
+        tx.execute(
+            &format!("INSERT INTO \"{}\" VALUES ({})", manifest.table_name, marks),
+            rusqlite::params_from_iter(values),
+        )
+        .map_err(|e| e.to_string())?;
+        count += 1;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    drop(rconn);
+
+    conn.execute(
+        "UPDATE audit_archives SET restored_path = ?1 WHERE archive_id = ?2",
+        params![db_path.to_string_lossy().to_string(), archive_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(RestoredArchive {
+        archive_id: archive_id.to_string(),
+        table_name: manifest.table_name,
+        db_path: db_path.to_string_lossy().to_string(),
+        row_count: count,
+    })
+}
+
+/// Query a restored archive. The restore file is opened read-only.
+pub fn query_restored(
+    conn: &Connection,
+    archive_id: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<serde_json::Value>, String> {
+    let archive = get_archive(conn, archive_id)?;
+    let db_path = archive
+        .restored_path
+        .ok_or_else(|| "archive has not been restored".to_string())?;
+    let rconn = Connection::open_with_flags(&db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| e.to_string())?;
+
+    let sql = format!(
+        "SELECT * FROM \"{}\" ORDER BY id DESC LIMIT ?1 OFFSET ?2",
+        archive.table_name
+    );
+    let mut stmt = rconn.prepare(&sql).map_err(|e| e.to_string())?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let mut rows = stmt
+        .query(params![limit, offset])
+        .map_err(|e| e.to_string())?;
+    let mut out = Vec::new();
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let mut obj = serde_json::Map::new();
+        for (i, c) in columns.iter().enumerate() {
+            obj.insert(
+                c.clone(),
+                value_to_json(row.get_ref(i).map_err(|e| e.to_string())?),
+            );
+        }
+        out.push(serde_json::Value::Object(obj));
+    }
+    Ok(out)
+}
+
+// -----------------------------------------------------------------------------
+// Commands
+// -----------------------------------------------------------------------------
+
+#[command]
+pub fn admin_list_retention_policies(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<Vec<RetentionPolicy>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    list_policies(&conn)
+}
+
+/// Create or update the policy for (table_name, action_class). Use action_class "*"
+/// (or omit it) for a table-wide policy.
+#[command]
+pub fn admin_set_retention_policy(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    table_name: String,
+    action_class: Option<String>,
+    retain_days: i64,
+) -> Result<Vec<RetentionPolicy>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let class = action_class.unwrap_or_else(|| ALL_ACTIONS.to_string());
+    validate_policy(&table_name, &class)?;
+    if !(1..=MAX_RETAIN_DAYS).contains(&retain_days) {
+        return Err(format!(
+            "retain_days must be between 1 and {}",
+            MAX_RETAIN_DAYS
+        ));
+    }
+
+    conn.execute(
+        "INSERT INTO retention_policies (table_name, action_class, retain_days, updated_by, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(table_name, action_class)
+         DO UPDATE SET retain_days = excluded.retain_days,
+                       updated_by = excluded.updated_by,
+                       updated_at = excluded.updated_at",
+        params![
+            table_name,
+            class,
+            retain_days,
+            admin_username,
+            Utc::now().timestamp()
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_set_retention_policy",
+        None,
+        Some(&format!("{}/{} -> {} days", table_name, class, retain_days)),
+    );
+
+    list_policies(&conn)
+}
+
+#[command]
+pub fn admin_delete_retention_policy(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    id: i64,
+) -> Result<Vec<RetentionPolicy>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    conn.execute("DELETE FROM retention_policies WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_delete_retention_policy",
+        None,
+        Some(&format!("Deleted retention policy id={}", id)),
+    );
+
+    list_policies(&conn)
+}
+
+#[command]
+pub fn admin_run_retention(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<Vec<RetentionOutcome>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let dry = dry_run.unwrap_or(false);
+    let out = run_retention(&conn, &admin_username, dry)?;
+
+    let total: u64 = out.iter().map(|o| o.rows).sum();
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_run_retention",
+        None,
+        Some(&format!(
+            "{} {} rows across {} policies",
+            if dry { "Would archive" } else { "Archived" },
+            total,
+            out.len()
+        )),
+    );
+
+    Ok(out)
+}
+
+#[command]
+pub fn admin_list_audit_archives(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<AuditArchive>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, archive_id, table_name, action_class, file_path, row_count, first_id, last_id,
+                    cutoff_ts, sha256, created_by, created_at, restored_path
+             FROM audit_archives ORDER BY created_at DESC LIMIT ?1",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([limit.unwrap_or(200)], archive_from_row)
+        .map_err(|e| e.to_string())?;
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(out)
+}
+
+#[command]
+pub fn admin_restore_audit_archive(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    archive_id: String,
+) -> Result<RestoredArchive, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let res = restore_archive(&conn, &archive_id)?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_restore_audit_archive",
+        None,
+        Some(&format!(
+            "Restored archive {} ({} {} rows)",
+            archive_id, res.row_count, res.table_name
+        )),
+    );
+
+    Ok(res)
+}
+
+#[command]
+pub fn admin_query_audit_archive(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    archive_id: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let out = query_restored(
+        &conn,
+        &archive_id,
+        limit.unwrap_or(200),
+        offset.unwrap_or(0),
+    )?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_query_audit_archive",
+        None,
+        Some(&format!("Viewed {} rows of archive {}", out.len(), archive_id)),
+    );
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    /// Write a one-row archive and its sidecar signed with `key`, as archive_rows does.
+    fn write_archive_signed(key: &SigningKey) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("audit-retention-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("audit_logs-all.jsonl.gz");
+        let mut enc = GzEncoder::new(Vec::new(), Compression::best());
+        enc.write_all(b"{\"id\":1,\"action\":\"fs_delete\"}\n").unwrap();
+        let gz = enc.finish().unwrap();
+        fs::write(&path, &gz).unwrap();
+
+        let manifest = ArchiveManifest {
+            archive_id: "test-archive".into(),
+            table_name: "audit_logs".into(),
+            action_class: ALL_ACTIONS.into(),
+            row_count: 1,
+            first_id: Some(1),
+            last_id: Some(1),
+            cutoff_ts: 0,
+            sha256: sha256_hex(&gz),
+            created_at: 1_700_000_000,
+            public_key: hex::encode(key.verifying_key().to_bytes()),
+        };
+        let manifest_bytes = serde_json::to_vec(&manifest).unwrap();
+        let signed = SignedArchiveManifest {
+            manifest,
+            signature: hex::encode(key.sign(&manifest_bytes).to_bytes()),
+        };
+        fs::write(sidecar_path(&path), serde_json::to_vec(&signed).unwrap()).unwrap();
+        path
+    }
+
+    #[test]
+    fn archive_signed_with_trusted_key_reads_back() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let path = write_archive_signed(&key);
+        let trusted = hex::encode(key.verifying_key().to_bytes());
+        let (manifest, text) = read_verified_archive_with_key(&path, &trusted).unwrap();
+        assert_eq!(manifest.row_count, 1);
+        assert!(text.contains("fs_delete"));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn archive_signed_with_another_key_is_rejected() {
+        let genuine = SigningKey::from_bytes(&[7u8; 32]);
+        let forger = SigningKey::from_bytes(&[9u8; 32]);
+        let path = write_archive_signed(&forger);
+        let trusted = hex::encode(genuine.verifying_key().to_bytes());
+        assert!(read_verified_archive_with_key(&path, &trusted).is_err());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn huge_retain_days_selects_nothing() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::init_db_schema(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO retention_policies (table_name, action_class, retain_days, updated_at)
+             VALUES ('audit_logs', '*', 100000000000000, 0)",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO audit_logs (action, created_at) VALUES ('fs_delete', ?1)",
+            params![Utc::now().timestamp() - 86_400],
+        )
+        .unwrap();
+
+        let dry = run_retention(&conn, "test", true).unwrap();
+        assert_eq!(dry.len(), 1);
+        assert_eq!(dry[0].rows, 0);
+        assert!(dry[0].cutoff_ts <= 0);
+
+        let done = run_retention(&conn, "test", false).unwrap();
+        assert_eq!((done[0].rows, done[0].archive_id.as_deref()), (0, None));
+        let left: i64 = conn
+            .query_row("SELECT COUNT(*) FROM audit_logs", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(left, 1);
+    }
+}
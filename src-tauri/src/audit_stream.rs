@@ -0,0 +1,516 @@
+// src-tauri/src/audit_stream.rs
+//
+// Live push of audit_logs rows to frontend windows as typed `audit:new`
+// events, so dashboards no longer have to poll `admin_list_audit_logs`.
+//
+// `db::insert_audit_log` calls `publish`, which never blocks the writer: each
+// subscription owns a bounded queue and a dispatcher thread that emits to the
+// subscribing window only. The frontend acknowledges what it has rendered
+// (`audit_stream_ack`); at most `ACK_WINDOW` events are in flight per window.
+// When a consumer falls behind and its queue fills, further events are dropped
+// for that subscription and a single `audit:lagged` notice tells it where to
+// resume from (`audit_stream_resume`). Events carry the audit row id, so the
+// client dedupes any overlap between backlog and live delivery by id.
+//
+// `publish` runs inside the writer's transaction, so the dispatcher only emits
+// an event once its row is visible to another connection. A row that does not
+// show up in time (slow commit or rollback) sends the subscription through the
+// lagged/resume path, which re-reads committed rows from the table.
+use once_cell::sync::{Lazy, OnceCell};
+use rusqlite::{params_from_iter, Connection};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Emitter, WebviewWindow};
+
+pub const EVENT_NEW: &str = "audit:new";
+pub const EVENT_LAGGED: &str = "audit:lagged";
+pub const EVENT_CLOSED: &str = "audit:closed";
+
+/// Events buffered per subscription before it is marked as lagging.
+const QUEUE_CAPACITY: usize = 512;
+/// Emitted-but-unacknowledged events allowed per subscription.
+const ACK_WINDOW: usize = 64;
+const MAX_BACKLOG: i64 = 1000;
+const IDLE_TICK: Duration = Duration::from_secs(1);
+/// Sessions are re-checked this often so logout/expiry ends the stream.
+const REVALIDATE_EVERY: Duration = Duration::from_secs(60);
+/// How long the dispatcher waits for a published row's transaction to commit.
+const COMMIT_WAIT: Duration = Duration::from_secs(2);
+const COMMIT_POLL: Duration = Duration::from_millis(25);
+
+static APP: OnceCell<AppHandle> = OnceCell::new();
+static SUBSCRIBERS: Lazy<Mutex<HashMap<String, Subscriber>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditStreamEvent {
+    pub subscription_id: String,
+    pub id: i64,
+    pub actor_user_id: Option<i64>,
+    pub actor_username: Option<String>,
+    pub action: String,
+    pub target_user_id: Option<i64>,
+    pub details: Option<String>,
+    pub created_at: i64,
+    pub target_type: Option<String>,
+    pub target_path: Option<String>,
+    pub target_dest_path: Option<String>,
+    pub target_file_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditStreamLagged {
+    pub subscription_id: String,
+    /// Last id delivered live; fetch everything after it via `audit_stream_resume`.
+    pub resume_from: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditStreamSubscription {
+    pub subscription_id: String,
+    pub backlog: Vec<AuditStreamEvent>,
+    pub has_more: bool,
+    pub ack_window: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditStreamBacklog {
+    pub events: Vec<AuditStreamEvent>,
+    pub has_more: bool,
+}
+
+struct Flow {
+    in_flight: VecDeque<i64>,
+    last_sent: i64,
+}
+
+struct Shared {
+    flow: Mutex<Flow>,
+    acked: Condvar,
+    lagged: AtomicBool,
+    closed: AtomicBool,
+}
+
+struct Subscriber {
+    user_id: i64,
+    is_admin: bool,
+    tx: SyncSender<AuditStreamEvent>,
+    shared: Arc<Shared>,
+}
+
+impl Subscriber {
+    /// Admins see every event; other users only their own activity.
+    fn can_see(&self, actor_user_id: Option<i64>) -> bool {
+        self.is_admin || actor_user_id == Some(self.user_id)
+    }
+}
+
+/// Called once from `main` setup; until then `publish` is a no-op.
+pub fn init(app: AppHandle) {
+    let _ = APP.set(app);
+}
+
+/// Fan a freshly inserted audit row out to live subscribers. Never blocks; the
+/// row may still be uncommitted, which the dispatcher accounts for.
+pub fn publish(
+    id: i64,
+    actor_user_id: Option<i64>,
+    actor_username: Option<&str>,
+    action: &str,
+    target_user_id: Option<i64>,
+    details: Option<&str>,
+    created_at: i64,
+    target: &crate::db::AuditTarget,
+) {
+    let mut subs = match SUBSCRIBERS.lock() {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+    if subs.is_empty() {
+        return;
+    }
+    let mut gone = Vec::new();
+    for (sid, sub) in subs.iter() {
+        if !sub.can_see(actor_user_id) {
+            continue;
+        }
+        // Once lagging, everything is recovered through resume instead.
+        if sub.shared.lagged.load(Ordering::SeqCst) {
+            continue;
+        }
+        let ev = AuditStreamEvent {
+            subscription_id: sid.clone(),
+            id,
+            actor_user_id,
+            actor_username: actor_username.map(|s| s.to_string()),
+            action: action.to_string(),
+            target_user_id,
+            details: details.map(|s| s.to_string()),
+            created_at,
+            target_type: target.target_type.clone(),
+            target_path: target.path.clone(),
+            target_dest_path: target.dest_path.clone(),
+            target_file_id: target.file_id,
+        };
+        match sub.tx.try_send(ev) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => sub.shared.lagged.store(true, Ordering::SeqCst),
+            Err(TrySendError::Disconnected(_)) => gone.push(sid.clone()),
+        }
+    }
+    for sid in gone {
+        subs.remove(&sid);
+    }
+}
+
+fn dispatch_loop(
+    app: AppHandle,
+    label: String,
+    subscription_id: String,
+    session_token: String,
+    rx: Receiver<AuditStreamEvent>,
+    shared: Arc<Shared>,
+) {
+    let mut validated_at = Instant::now();
+    let mut db: Option<Connection> = None;
+    loop {
+        if shared.closed.load(Ordering::SeqCst) {
+            break;
+        }
+        if validated_at.elapsed() >= REVALIDATE_EVERY {
+            if !session_still_valid(&session_token) {
+                let _ = app.emit_to(
+                    label.as_str(),
+                    EVENT_CLOSED,
+                    serde_json::json!({ "subscription_id": subscription_id, "reason": "session ended" }),
+                );
+                break;
+            }
+            validated_at = Instant::now();
+        }
+
+        match rx.recv_timeout(IDLE_TICK) {
+            Ok(ev) => {
+                // once lagging, queued events are left to resume so none is
+                // delivered ahead of a row still waiting on its commit
+                if shared.lagged.load(Ordering::SeqCst) || !wait_committed(&mut db, &ev) {
+                    shared.lagged.store(true, Ordering::SeqCst);
+                    continue;
+                }
+                // back-pressure: wait for the window to acknowledge earlier events
+                let mut flow = match shared.flow.lock() {
+                    Ok(f) => f,
+                    Err(_) => break,
+                };
+                while flow.in_flight.len() >= ACK_WINDOW && !shared.closed.load(Ordering::SeqCst) {
+                    flow = match shared.acked.wait_timeout(flow, IDLE_TICK) {
+                        Ok((f, _)) => f,
+                        Err(_) => return,
+                    };
+                }
+                if shared.closed.load(Ordering::SeqCst) {
+                    break;
+                }
+                let id = ev.id;
+                if app.emit_to(label.as_str(), EVENT_NEW, ev).is_err() {
+                    break;
+                }
+                flow.in_flight.push_back(id);
+                flow.last_sent = id;
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                // queue drained: tell a lagging client where to resume from
+                if shared.lagged.load(Ordering::SeqCst) {
+                    let resume_from = shared.flow.lock().map(|f| f.last_sent).unwrap_or(0);
+                    let _ = app.emit_to(
+                        label.as_str(),
+                        EVENT_LAGGED,
+                        AuditStreamLagged {
+                            subscription_id: subscription_id.clone(),
+                            resume_from,
+                        },
+                    );
+                    shared.lagged.store(false, Ordering::SeqCst);
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    if let Ok(mut subs) = SUBSCRIBERS.lock() {
+        subs.remove(&subscription_id);
+    }
+}
+
+/// Wait until the audit row behind `ev` is visible outside the writer's
+/// transaction. A rolled-back id can be reused by the next insert, so the row
+/// has to match the event, not just its id.
+fn wait_committed(db: &mut Option<Connection>, ev: &AuditStreamEvent) -> bool {
+    if db.is_none() {
+        *db = crate::db::open_connection().ok();
+    }
+    let conn = match db.as_ref() {
+        Some(c) => c,
+        None => return false,
+    };
+    let deadline = Instant::now() + COMMIT_WAIT;
+    loop {
+        let row = conn.query_row(
+            "SELECT action, actor_user_id, created_at FROM audit_logs WHERE id = ?1",
+            [ev.id],
+            |r| Ok((r.get::<_, String>(0)?, r.get::<_, Option<i64>>(1)?, r.get::<_, i64>(2)?)),
+        );
+        if let Ok((action, actor, created_at)) = row {
+            return action == ev.action
+                && actor == ev.actor_user_id
+                && created_at == ev.created_at;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(COMMIT_POLL);
+    }
+}
+
+fn session_still_valid(token: &str) -> bool {
+    match crate::db::open_connection() {
+        Ok(conn) => matches!(crate::session::validate_session(&conn, token), Ok(Some(_))),
+        // a transient open failure should not tear down the stream
+        Err(_) => true,
+    }
+}
+
+fn resolve_session(
+    conn: &Connection,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<(String, i64, bool), String> {
+    let token = sessionToken
+        .or(session_token)
+        .ok_or_else(|| "missing session token".to_string())?;
+    let uid = crate::session::validate_session(conn, &token)?
+        .ok_or_else(|| "Invalid session".to_string())?;
+    let user = crate::db::get_user_by_id_row(conn, uid)?
+        .ok_or_else(|| "Invalid session".to_string())?;
+    Ok((token, user.id, user.role == "admin"))
+}
+
+fn load_backlog(
+    conn: &Connection,
+    subscription_id: &str,
+    user_id: i64,
+    is_admin: bool,
+    since_id: i64,
+    limit: i64,
+) -> Result<AuditStreamBacklog, String> {
+    let limit = limit.clamp(1, MAX_BACKLOG);
+    let mut sql = String::from(
+        "SELECT id, actor_user_id, actor_username, action, target_user_id, details, created_at,
+                target_type, target_path, target_dest_path, target_file_id
+         FROM audit_logs WHERE id > ?",
+    );
+    let mut vals: Vec<rusqlite::types::Value> = vec![since_id.into()];
+    if !is_admin {
+        sql.push_str(" AND actor_user_id = ?");
+        vals.push(user_id.into());
+    }
+    sql.push_str(" ORDER BY id ASC LIMIT ?");
+    vals.push((limit + 1).into());
+
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params_from_iter(vals), |r| {
+            Ok(AuditStreamEvent {
+                subscription_id: subscription_id.to_string(),
+                id: r.get(0)?,
+                actor_user_id: r.get(1)?,
+                actor_username: r.get(2)?,
+                action: r.get(3)?,
+                target_user_id: r.get(4)?,
+                details: r.get(5)?,
+                created_at: r.get(6)?,
+                target_type: r.get(7)?,
+                target_path: r.get(8)?,
+                target_dest_path: r.get(9)?,
+                target_file_id: r.get(10)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    let mut events = Vec::new();
+    for r in rows {
+        events.push(r.map_err(|e| e.to_string())?);
+    }
+    let has_more = events.len() as i64 > limit;
+    events.truncate(limit as usize);
+    Ok(AuditStreamBacklog { events, has_more })
+}
+
+fn subscriber_owner(subscription_id: &str) -> Result<(i64, Arc<Shared>), String> {
+    let subs = SUBSCRIBERS.lock().map_err(|_| "lock poisoned".to_string())?;
+    let sub = subs
+        .get(subscription_id)
+        .ok_or_else(|| "Unknown subscription".to_string())?;
+    Ok((sub.user_id, sub.shared.clone()))
+}
+
+/// Start streaming to the calling window. Any previous subscription of the
+/// same window is replaced. Pass the last id the client saw as `since_id`
+/// after a reconnect to receive what was missed as `backlog`.
+#[command]
+pub fn audit_stream_subscribe(
+    _app: AppHandle,
+    window: WebviewWindow,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    since_id: Option<i64>,
+) -> Result<AuditStreamSubscription, String> {
+    let app = APP
+        .get()
+        .cloned()
+        .ok_or_else(|| "audit stream not initialised".to_string())?;
+    let conn = crate::db::open_connection()?;
+    let (token, user_id, is_admin) = resolve_session(&conn, sessionToken, session_token)?;
+    let label = window.label().to_string();
+    let subscription_id = uuid::Uuid::new_v4().to_string();
+
+    let (tx, rx) = sync_channel(QUEUE_CAPACITY);
+    let shared = Arc::new(Shared {
+        flow: Mutex::new(Flow {
+            in_flight: VecDeque::new(),
+            last_sent: since_id.unwrap_or(0),
+        }),
+        acked: Condvar::new(),
+        lagged: AtomicBool::new(false),
+        closed: AtomicBool::new(false),
+    });
+
+    // register before reading the backlog so nothing falls between the two
+    {
+        let mut subs = SUBSCRIBERS.lock().map_err(|_| "lock poisoned".to_string())?;
+        subs.insert(
+            subscription_id.clone(),
+            Subscriber {
+                user_id,
+                is_admin,
+                tx,
+                shared: shared.clone(),
+            },
+        );
+    }
+    replace_window_subscription(&label, &subscription_id);
+
+    {
+        let sid = subscription_id.clone();
+        let label = label.clone();
+        let shared = shared.clone();
+        std::thread::spawn(move || dispatch_loop(app, label, sid, token, rx, shared));
+    }
+
+    let (backlog, has_more) = match since_id {
+        Some(since) => {
+            let b = load_backlog(&conn, &subscription_id, user_id, is_admin, since, MAX_BACKLOG)?;
+            (b.events, b.has_more)
+        }
+        None => (Vec::new(), false),
+    };
+
+    Ok(AuditStreamSubscription {
+        subscription_id,
+        backlog,
+        has_more,
+        ack_window: ACK_WINDOW,
+    })
+}
+
+static WINDOW_SUBSCRIPTIONS: Lazy<Mutex<HashMap<String, String>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn replace_window_subscription(label: &str, subscription_id: &str) {
+    let previous = match WINDOW_SUBSCRIPTIONS.lock() {
+        Ok(mut m) => m.insert(label.to_string(), subscription_id.to_string()),
+        Err(_) => None,
+    };
+    if let Some(prev) = previous {
+        close_subscription(&prev);
+    }
+}
+
+fn close_subscription(subscription_id: &str) {
+    let removed = SUBSCRIBERS
+        .lock()
+        .ok()
+        .and_then(|mut subs| subs.remove(subscription_id));
+    if let Some(sub) = removed {
+        sub.shared.closed.store(true, Ordering::SeqCst);
+        sub.shared.acked.notify_all();
+    }
+}
+
+/// Page through events after `since_id`, e.g. after an `audit:lagged` notice.
+#[command]
+pub fn audit_stream_resume(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    subscription_id: String,
+    since_id: i64,
+    limit: Option<i64>,
+) -> Result<AuditStreamBacklog, String> {
+    let conn = crate::db::open_connection()?;
+    let (_token, user_id, is_admin) = resolve_session(&conn, sessionToken, session_token)?;
+    let (owner, _) = subscriber_owner(&subscription_id)?;
+    if owner != user_id {
+        return Err("Unknown subscription".into());
+    }
+    load_backlog(
+        &conn,
+        &subscription_id,
+        user_id,
+        is_admin,
+        since_id,
+        limit.unwrap_or(MAX_BACKLOG),
+    )
+}
+
+/// Acknowledge every delivered event up to and including `last_id`.
+#[command]
+pub fn audit_stream_ack(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    subscription_id: String,
+    last_id: i64,
+) -> Result<(), String> {
+    let conn = crate::db::open_connection()?;
+    let (_token, user_id, _) = resolve_session(&conn, sessionToken, session_token)?;
+    let (owner, shared) = subscriber_owner(&subscription_id)?;
+    if owner != user_id {
+        return Err("Unknown subscription".into());
+    }
+    let mut flow = shared.flow.lock().map_err(|_| "lock poisoned".to_string())?;
+    while flow.in_flight.front().map_or(false, |id| *id <= last_id) {
+        flow.in_flight.pop_front();
+    }
+    shared.acked.notify_all();
+    Ok(())
+}
+
+#[command]
+pub fn audit_stream_unsubscribe(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    subscription_id: String,
+) -> Result<(), String> {
+    let conn = crate::db::open_connection()?;
+    let (_token, user_id, _) = resolve_session(&conn, sessionToken, session_token)?;
+    let (owner, _) = subscriber_owner(&subscription_id)?;
+    if owner != user_id {
+        return Err("Unknown subscription".into());
+    }
+    close_subscription(&subscription_id);
+    Ok(())
+}
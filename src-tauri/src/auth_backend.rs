@@ -209,6 +209,7 @@ pub fn auth_logout(_app: AppHandle, session_token: String) -> Result<bool, Strin
 
     if let Some((_t, user_id, _)) = get_session(&conn, &session_token)? {
         session::revoke_session_token(&conn, &session_token)?;
+        crate::thumbnails::revoke_session(&session_token);
         let _ = insert_audit_log(
             &conn,
             Some(user_id),
@@ -0,0 +1,889 @@
+// src-tauri/src/content_extract.rs
+//
+// Plain-text and embedded-metadata extraction for full-text search.
+// Extractors work on in-memory bytes (not paths) so archive members can reuse
+// them. Each document kind has its own input size limit and timeout;
+// extraction runs on a helper thread so a pathological file cannot stall the
+// indexer (a timed-out helper is left to finish on its own and its result is
+// discarded). Metadata comes from the PDF Info dictionary and XMP packet,
+// OOXML docProps parts, and the EXIF block of images.
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::Serialize;
+use std::io::{Cursor, Read};
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+/// Upper bound on stored text per document (characters).
+pub const MAX_TEXT_CHARS: usize = 1_000_000;
+/// Cap on any single decompressed part inside an OOXML package.
+const MAX_XML_PART_BYTES: u64 = 64 * 1024 * 1024;
+/// Minimum run length for the legacy binary string scan.
+const MIN_RUN: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocKind {
+    Pdf,
+    Docx,
+    Xlsx,
+    Pptx,
+    LegacyOffice,
+    Text,
+    /// No embedded text; only the OCR stage can produce any.
+    Image,
+}
+
+impl DocKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DocKind::Pdf => "pdf",
+            DocKind::Docx => "docx",
+            DocKind::Xlsx => "xlsx",
+            DocKind::Pptx => "pptx",
+            DocKind::LegacyOffice => "legacy_office",
+            DocKind::Text => "text",
+            DocKind::Image => "image",
+        }
+    }
+
+    /// Largest input accepted for this kind, in bytes.
+    pub fn max_input_bytes(self) -> u64 {
+        match self {
+            DocKind::Pdf => 50 * 1024 * 1024,
+            DocKind::Docx | DocKind::Xlsx | DocKind::Pptx => 50 * 1024 * 1024,
+            DocKind::LegacyOffice => 20 * 1024 * 1024,
+            DocKind::Text => 10 * 1024 * 1024,
+            DocKind::Image => 50 * 1024 * 1024,
+        }
+    }
+
+    pub fn timeout(self) -> Duration {
+        match self {
+            DocKind::Pdf => Duration::from_secs(30),
+            DocKind::Docx | DocKind::Pptx => Duration::from_secs(15),
+            DocKind::Xlsx => Duration::from_secs(20),
+            DocKind::LegacyOffice => Duration::from_secs(10),
+            DocKind::Text | DocKind::Image => Duration::from_secs(5),
+        }
+    }
+}
+
+/// (extension, kind) for every supported type.
+pub const SUPPORTED_EXTENSIONS: &[(&str, DocKind)] = &[
+    ("pdf", DocKind::Pdf),
+    ("docx", DocKind::Docx),
+    ("docm", DocKind::Docx),
+    ("xlsx", DocKind::Xlsx),
+    ("xlsm", DocKind::Xlsx),
+    ("pptx", DocKind::Pptx),
+    ("pptm", DocKind::Pptx),
+    ("doc", DocKind::LegacyOffice),
+    ("ppt", DocKind::LegacyOffice),
+    ("xls", DocKind::LegacyOffice),
+    ("txt", DocKind::Text),
+    ("log", DocKind::Text),
+    ("json", DocKind::Text),
+    ("md", DocKind::Text),
+    ("csv", DocKind::Text),
+    ("xml", DocKind::Text),
+    ("png", DocKind::Image),
+    ("jpg", DocKind::Image),
+    ("jpeg", DocKind::Image),
+    ("tif", DocKind::Image),
+    ("tiff", DocKind::Image),
+    ("bmp", DocKind::Image),
+    ("webp", DocKind::Image),
+];
+
+pub fn kind_for_name(name: &str) -> Option<DocKind> {
+    let ext = name.rsplit_once('.')?.1.to_ascii_lowercase();
+    SUPPORTED_EXTENSIONS
+        .iter()
+        .find(|(e, _)| *e == ext)
+        .map(|(_, k)| *k)
+}
+
+#[derive(Debug)]
+pub enum ExtractError {
+    TooLarge(u64),
+    Timeout,
+    Failed(String),
+}
+
+impl std::fmt::Display for ExtractError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExtractError::TooLarge(n) => write!(f, "input too large ({} bytes)", n),
+            ExtractError::Timeout => write!(f, "extraction timed out"),
+            ExtractError::Failed(m) => write!(f, "{}", m),
+        }
+    }
+}
+
+/// Embedded document properties. Dates are unix seconds; EXIF capture times
+/// without a zone are taken as UTC.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DocMetadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    /// Application that created the document.
+    pub creator_tool: Option<String>,
+    pub producer: Option<String>,
+    pub last_modified_by: Option<String>,
+    pub revision: Option<String>,
+    pub created_at: Option<i64>,
+    pub modified_at: Option<i64>,
+    pub page_count: Option<i64>,
+    pub slide_count: Option<i64>,
+    pub word_count: Option<i64>,
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+    pub taken_at: Option<i64>,
+    pub gps_lat: Option<f64>,
+    pub gps_lon: Option<f64>,
+}
+
+impl DocMetadata {
+    pub fn is_empty(&self) -> bool {
+        *self == DocMetadata::default()
+    }
+
+    /// Text fields joined for the search index.
+    pub fn search_text(&self) -> String {
+        [
+            &self.title,
+            &self.author,
+            &self.subject,
+            &self.keywords,
+            &self.last_modified_by,
+            &self.camera_make,
+            &self.camera_model,
+        ]
+        .iter()
+        .filter_map(|f| f.as_deref())
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
+}
+
+/// Text and metadata of one document.
+#[derive(Debug, Default)]
+pub struct Extracted {
+    pub text: String,
+    pub metadata: DocMetadata,
+}
+
+/// Text and metadata from `bytes` with the kind's size limit and timeout
+/// applied. Metadata is best-effort: a document whose properties cannot be
+/// read still yields its text.
+pub fn extract_document_with_limits(
+    kind: DocKind,
+    bytes: Vec<u8>,
+) -> Result<Extracted, ExtractError> {
+    let len = bytes.len() as u64;
+    if len > kind.max_input_bytes() {
+        return Err(ExtractError::TooLarge(len));
+    }
+    let (tx, rx) = channel();
+    std::thread::Builder::new()
+        .name(format!("extract-{}", kind.as_str()))
+        .spawn(move || {
+            let _ = tx.send(extract_document(kind, &bytes));
+        })
+        .map_err(|e| ExtractError::Failed(format!("spawn extractor: {}", e)))?;
+    match rx.recv_timeout(kind.timeout()) {
+        Ok(Ok(doc)) => Ok(doc),
+        Ok(Err(e)) => Err(ExtractError::Failed(e)),
+        Err(_) => Err(ExtractError::Timeout),
+    }
+}
+
+/// Text and metadata without limits (caller is responsible for bounding input).
+fn extract_document(kind: DocKind, bytes: &[u8]) -> Result<Extracted, String> {
+    let (text, metadata) = match kind {
+        DocKind::Pdf => {
+            let doc = lopdf::Document::load_mem(bytes).map_err(|e| format!("pdf parse: {}", e))?;
+            (extract_pdf(&doc), pdf_metadata(&doc))
+        }
+        DocKind::Docx => (extract_docx(bytes)?, ooxml_metadata(bytes)),
+        DocKind::Xlsx => (extract_xlsx(bytes)?, ooxml_metadata(bytes)),
+        DocKind::Pptx => (extract_pptx(bytes)?, ooxml_metadata(bytes)),
+        DocKind::LegacyOffice => (extract_binary_strings(bytes), DocMetadata::default()),
+        DocKind::Text => (decode_text(bytes), DocMetadata::default()),
+        DocKind::Image => (String::new(), exif_metadata(bytes)),
+    };
+    Ok(Extracted {
+        text: truncate_chars(normalize_whitespace(&text), MAX_TEXT_CHARS),
+        metadata,
+    })
+}
+
+pub(crate) fn truncate_chars(mut s: String, max: usize) -> String {
+    if let Some((idx, _)) = s.char_indices().nth(max) {
+        s.truncate(idx);
+    }
+    s
+}
+
+pub(crate) fn normalize_whitespace(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut blank_lines = 0;
+    for line in s.lines() {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            blank_lines += 1;
+            if blank_lines > 1 {
+                continue;
+            }
+        } else {
+            blank_lines = 0;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.trim().to_string()
+}
+
+// ---------- text ----------
+
+fn decode_text(bytes: &[u8]) -> String {
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        return decode_utf16(rest, false);
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        return decode_utf16(rest, true);
+    }
+    let rest = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    String::from_utf8_lossy(rest).into_owned()
+}
+
+fn decode_utf16(bytes: &[u8], big_endian: bool) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| {
+            if big_endian {
+                u16::from_be_bytes([c[0], c[1]])
+            } else {
+                u16::from_le_bytes([c[0], c[1]])
+            }
+        })
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+// ---------- pdf ----------
+
+fn extract_pdf(doc: &lopdf::Document) -> String {
+    let mut out = String::new();
+    for page in doc.get_pages().keys() {
+        // one bad page (unsupported font encoding etc.) should not lose the rest
+        if let Ok(text) = doc.extract_text(&[*page]) {
+            out.push_str(&text);
+            out.push('\n');
+        }
+        if out.len() > MAX_TEXT_CHARS * 4 {
+            break;
+        }
+    }
+    out
+}
+
+/// Pages (1-based) with no usable text layer, e.g. scanned pages. A page whose
+/// text cannot be decoded counts as textless. Returns (page count, pages).
+pub fn pdf_pages_without_text(bytes: &[u8]) -> Result<(u32, Vec<u32>), String> {
+    let doc = lopdf::Document::load_mem(bytes).map_err(|e| format!("pdf parse: {}", e))?;
+    let pages = doc.get_pages();
+    let textless = pages
+        .keys()
+        .filter(|p| {
+            doc.extract_text(&[**p])
+                .map(|t| t.chars().filter(|c| !c.is_whitespace()).count() < 8)
+                .unwrap_or(true)
+        })
+        .copied()
+        .collect();
+    Ok((pages.len() as u32, textless))
+}
+
+/// PDF text string: UTF-16BE with a byte order mark, otherwise
+/// PDFDocEncoding (treated as Latin-1).
+fn pdf_string(bytes: &[u8]) -> Option<String> {
+    let s = match bytes.strip_prefix(&[0xFE, 0xFF]) {
+        Some(rest) => decode_utf16(rest, true),
+        None => bytes.iter().map(|b| *b as char).collect(),
+    };
+    non_blank(&s)
+}
+
+fn non_blank(s: &str) -> Option<String> {
+    let s = s.trim_matches(|c: char| c.is_whitespace() || c == '\0');
+    (!s.is_empty()).then(|| s.to_string())
+}
+
+/// `D:YYYYMMDDHHmmSSOHH'mm'` with everything after the year optional.
+fn parse_pdf_date(s: &str) -> Option<i64> {
+    let s = s.trim();
+    let s = s.strip_prefix("D:").unwrap_or(s);
+    let digits: String = s.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let field = |from: usize, len: usize, default: u32| -> u32 {
+        digits
+            .get(from..from + len)
+            .and_then(|d| d.parse().ok())
+            .unwrap_or(default)
+    };
+    let year = digits.get(0..4)?.parse::<i32>().ok()?;
+    let date = NaiveDate::from_ymd_opt(year, field(4, 2, 1), field(6, 2, 1))?;
+    let naive = date.and_hms_opt(field(8, 2, 0), field(10, 2, 0), field(12, 2, 0))?;
+    let zone = &s[digits.len()..];
+    let offset_secs = match zone.chars().next() {
+        Some(sign @ ('+' | '-')) => {
+            let z: String = zone[1..].chars().filter(|c| c.is_ascii_digit()).collect();
+            let hours: i32 = z.get(0..2).and_then(|h| h.parse().ok()).unwrap_or(0);
+            let minutes: i32 = z.get(2..4).and_then(|m| m.parse().ok()).unwrap_or(0);
+            let secs = hours * 3600 + minutes * 60;
+            if sign == '-' {
+                -secs
+            } else {
+                secs
+            }
+        }
+        _ => 0,
+    };
+    let offset = FixedOffset::east_opt(offset_secs)?;
+    offset
+        .from_local_datetime(&naive)
+        .single()
+        .map(|t| t.timestamp())
+}
+
+/// Info dictionary, with XMP filling in whatever it leaves out.
+fn pdf_metadata(doc: &lopdf::Document) -> DocMetadata {
+    let mut m = DocMetadata {
+        page_count: Some(doc.get_pages().len() as i64),
+        ..Default::default()
+    };
+
+    let info = doc
+        .trailer
+        .get(b"Info")
+        .and_then(|o| doc.dereference(o))
+        .and_then(|(_, o)| o.as_dict());
+    if let Ok(info) = info {
+        let text = |key: &[u8]| {
+            info.get(key)
+                .and_then(|o| doc.dereference(o))
+                .and_then(|(_, o)| o.as_str())
+                .ok()
+                .and_then(pdf_string)
+        };
+        m.title = text(b"Title");
+        m.author = text(b"Author");
+        m.subject = text(b"Subject");
+        m.keywords = text(b"Keywords");
+        m.creator_tool = text(b"Creator");
+        m.producer = text(b"Producer");
+        m.created_at = text(b"CreationDate").as_deref().and_then(parse_pdf_date);
+        m.modified_at = text(b"ModDate").as_deref().and_then(parse_pdf_date);
+    }
+
+    let xmp = doc
+        .catalog()
+        .and_then(|c| c.get(b"Metadata"))
+        .and_then(|o| doc.dereference(o))
+        .and_then(|(_, o)| o.as_stream())
+        .map(|st| {
+            st.decompressed_content()
+                .unwrap_or_else(|_| st.content.clone())
+        });
+    if let Ok(xml) = xmp {
+        let x = xmp_metadata(&xml);
+        m.title = m.title.or(x.title);
+        m.author = m.author.or(x.author);
+        m.subject = m.subject.or(x.subject);
+        m.keywords = m.keywords.or(x.keywords);
+        m.creator_tool = m.creator_tool.or(x.creator_tool);
+        m.producer = m.producer.or(x.producer);
+        m.created_at = m.created_at.or(x.created_at);
+        m.modified_at = m.modified_at.or(x.modified_at);
+    }
+    m
+}
+
+/// Properties from an XMP packet (Dublin Core, xmp: and pdf: schemas).
+fn xmp_metadata(xml: &[u8]) -> DocMetadata {
+    let fields = xml_fields(
+        xml,
+        &[
+            "title",
+            "creator",
+            "description",
+            "subject",
+            "Keywords",
+            "CreatorTool",
+            "Producer",
+            "CreateDate",
+            "ModifyDate",
+        ],
+    );
+    let get = |name: &str| field_value(&fields, name);
+    DocMetadata {
+        title: get("title"),
+        author: get("creator"),
+        subject: get("description"),
+        keywords: get("Keywords").or_else(|| get("subject")),
+        creator_tool: get("CreatorTool"),
+        producer: get("Producer"),
+        created_at: get("CreateDate").as_deref().and_then(parse_iso_date),
+        modified_at: get("ModifyDate").as_deref().and_then(parse_iso_date),
+        ..Default::default()
+    }
+}
+
+fn field_value(fields: &[(String, String)], name: &str) -> Option<String> {
+    fields
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.clone())
+}
+
+/// ISO 8601 / W3C-DTF date, with or without time and zone.
+fn parse_iso_date(s: &str) -> Option<i64> {
+    let s = s.trim();
+    if let Ok(t) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Some(t.timestamp());
+    }
+    let naive = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M"))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(s.get(0..10)?, "%Y-%m-%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)
+        })?;
+    Some(Utc.from_utc_datetime(&naive).timestamp())
+}
+
+/// Text of the elements (or attributes) named in `names`, by local name.
+/// Text inside nested list items (`rdf:li`) belongs to the nearest named
+/// ancestor; several items of one field are joined with "; ".
+fn xml_fields(xml: &[u8], names: &[&str]) -> Vec<(String, String)> {
+    use quick_xml::events::Event;
+    let mut reader = quick_xml::Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    let mut stack: Vec<Option<String>> = Vec::new();
+    let mut out: Vec<(String, String)> = Vec::new();
+    let mut push = |out: &mut Vec<(String, String)>, name: &str, value: &str| {
+        let value = match non_blank(value) {
+            Some(v) => v,
+            None => return,
+        };
+        match out.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) if !v.split("; ").any(|p| p == value) => {
+                v.push_str("; ");
+                v.push_str(&value);
+            }
+            Some(_) => {}
+            None => out.push((name.to_string(), value)),
+        }
+    };
+    let known = |local: &[u8]| {
+        names
+            .iter()
+            .find(|n| n.as_bytes() == local)
+            .map(|n| n.to_string())
+    };
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                for a in e.attributes().flatten() {
+                    let name = known(a.key.local_name().as_ref());
+                    if let (Some(name), Ok(v)) = (name, a.unescape_value()) {
+                        push(&mut out, &name, &v);
+                    }
+                }
+                stack.push(known(e.local_name().as_ref()));
+            }
+            Ok(Event::Empty(e)) => {
+                for a in e.attributes().flatten() {
+                    let name = known(a.key.local_name().as_ref());
+                    if let (Some(name), Ok(v)) = (name, a.unescape_value()) {
+                        push(&mut out, &name, &v);
+                    }
+                }
+            }
+            Ok(Event::End(_)) => {
+                stack.pop();
+            }
+            Ok(Event::Text(t)) => {
+                if let Some(name) = stack.iter().rev().flatten().next() {
+                    if let Ok(v) = t.unescape() {
+                        push(&mut out, name, &v);
+                    }
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    out
+}
+
+// ---------- OOXML ----------
+
+pub(crate) type Package<'a> = zip::ZipArchive<Cursor<&'a [u8]>>;
+
+pub(crate) fn open_package(bytes: &[u8]) -> Result<Package<'_>, String> {
+    zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("zip: {}", e))
+}
+
+pub(crate) fn read_part(pkg: &mut Package<'_>, name: &str) -> Result<Option<Vec<u8>>, String> {
+    let file = match pkg.by_name(name) {
+        Ok(f) => f,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("zip part {}: {}", name, e)),
+    };
+    let mut buf = Vec::new();
+    file.take(MAX_XML_PART_BYTES)
+        .read_to_end(&mut buf)
+        .map_err(|e| format!("read {}: {}", name, e))?;
+    Ok(Some(buf))
+}
+
+/// Part names matching `prefix` + number + ".xml", in numeric order.
+fn numbered_parts(pkg: &Package<'_>, prefix: &str) -> Vec<String> {
+    let mut parts: Vec<(u32, String)> = pkg
+        .file_names()
+        .filter_map(|n| {
+            let num = n.strip_prefix(prefix)?.strip_suffix(".xml")?;
+            num.parse::<u32>().ok().map(|i| (i, n.to_string()))
+        })
+        .collect();
+    parts.sort();
+    parts.into_iter().map(|(_, n)| n).collect()
+}
+
+/// Concatenate the text of `text_tag` elements; `break_tag` ends a line and
+/// `tab_tag` (empty element) inserts a tab.
+fn xml_text(xml: &[u8], text_tag: &[u8], break_tag: &[u8], tab_tag: &[u8]) -> Result<String, String> {
+    use quick_xml::events::Event;
+    let mut reader = quick_xml::Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    let mut out = String::new();
+    let mut in_text = false;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) if e.local_name().as_ref() == text_tag => in_text = true,
+            Ok(Event::Empty(e)) => {
+                let n = e.local_name();
+                if n.as_ref() == tab_tag {
+                    out.push('\t');
+                } else if n.as_ref() == break_tag || n.as_ref() == b"br" {
+                    out.push('\n');
+                }
+            }
+            Ok(Event::End(e)) => {
+                let n = e.local_name();
+                if n.as_ref() == text_tag {
+                    in_text = false;
+                } else if n.as_ref() == break_tag {
+                    out.push('\n');
+                }
+            }
+            Ok(Event::Text(t)) if in_text => {
+                let s = t.unescape().map_err(|e| format!("xml text: {}", e))?;
+                out.push_str(&s);
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("xml: {}", e)),
+            _ => {}
+        }
+        buf.clear();
+        if out.len() > MAX_TEXT_CHARS * 4 {
+            break;
+        }
+    }
+    Ok(out)
+}
+
+fn extract_docx(bytes: &[u8]) -> Result<String, String> {
+    let mut pkg = open_package(bytes)?;
+    let mut out = String::new();
+    let mut parts = vec!["word/document.xml".to_string()];
+    parts.extend(numbered_parts(&pkg, "word/header"));
+    parts.extend(numbered_parts(&pkg, "word/footer"));
+    parts.push("word/footnotes.xml".into());
+    for part in parts {
+        if let Some(xml) = read_part(&mut pkg, &part)? {
+            out.push_str(&xml_text(&xml, b"t", b"p", b"tab")?);
+            out.push('\n');
+        }
+    }
+    Ok(out)
+}
+
+fn extract_pptx(bytes: &[u8]) -> Result<String, String> {
+    let mut pkg = open_package(bytes)?;
+    let mut out = String::new();
+    let mut parts = numbered_parts(&pkg, "ppt/slides/slide");
+    parts.extend(numbered_parts(&pkg, "ppt/notesSlides/notesSlide"));
+    for part in parts {
+        if let Some(xml) = read_part(&mut pkg, &part)? {
+            out.push_str(&xml_text(&xml, b"t", b"p", b"tab")?);
+            out.push('\n');
+        }
+    }
+    Ok(out)
+}
+
+fn extract_xlsx(bytes: &[u8]) -> Result<String, String> {
+    use quick_xml::events::Event;
+    let mut pkg = open_package(bytes)?;
+
+    // shared string table: one entry per <si>, which may hold several runs
+    let mut shared: Vec<String> = Vec::new();
+    if let Some(xml) = read_part(&mut pkg, "xl/sharedStrings.xml")? {
+        let mut reader = quick_xml::Reader::from_reader(xml.as_slice());
+        let mut buf = Vec::new();
+        let mut cur = String::new();
+        let mut in_t = false;
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) if e.local_name().as_ref() == b"t" => in_t = true,
+                Ok(Event::End(e)) => match e.local_name().as_ref() {
+                    b"t" => in_t = false,
+                    b"si" => shared.push(std::mem::take(&mut cur)),
+                    _ => {}
+                },
+                Ok(Event::Text(t)) if in_t => {
+                    cur.push_str(&t.unescape().map_err(|e| format!("xml text: {}", e))?)
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(format!("sharedStrings: {}", e)),
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+
+    let mut out = String::new();
+    for part in numbered_parts(&pkg, "xl/worksheets/sheet") {
+        let xml = match read_part(&mut pkg, &part)? {
+            Some(x) => x,
+            None => continue,
+        };
+        let mut reader = quick_xml::Reader::from_reader(xml.as_slice());
+        let mut buf = Vec::new();
+        let mut cell_type: Option<Vec<u8>> = None;
+        let mut in_value = false;
+        let mut row_cells: Vec<String> = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                    b"c" => {
+                        cell_type = e
+                            .attributes()
+                            .flatten()
+                            .find(|a| a.key.local_name().as_ref() == b"t")
+                            .map(|a| a.value.to_vec());
+                    }
+                    b"v" | b"t" => in_value = true,
+                    _ => {}
+                },
+                Ok(Event::End(e)) => match e.local_name().as_ref() {
+                    b"v" | b"t" => in_value = false,
+                    b"row" if !row_cells.is_empty() => {
+                        out.push_str(&row_cells.join("\t"));
+                        out.push('\n');
+                        row_cells.clear();
+                    }
+                    _ => {}
+                },
+                Ok(Event::Text(t)) if in_value => {
+                    let raw = t.unescape().map_err(|e| format!("xml text: {}", e))?;
+                    let value = if cell_type.as_deref() == Some(b"s") {
+                        raw.trim()
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|i| shared.get(i).cloned())
+                            .unwrap_or_default()
+                    } else {
+                        raw.into_owned()
+                    };
+                    if !value.is_empty() {
+                        row_cells.push(value);
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(format!("{}: {}", part, e)),
+                _ => {}
+            }
+            buf.clear();
+            if out.len() > MAX_TEXT_CHARS * 4 {
+                break;
+            }
+        }
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Core properties (docProps/core.xml) and document statistics
+/// (docProps/app.xml) of an OOXML package.
+fn ooxml_metadata(bytes: &[u8]) -> DocMetadata {
+    let mut pkg = match open_package(bytes) {
+        Ok(p) => p,
+        Err(_) => return DocMetadata::default(),
+    };
+    let mut m = DocMetadata::default();
+    if let Ok(Some(xml)) = read_part(&mut pkg, "docProps/core.xml") {
+        let fields = xml_fields(
+            &xml,
+            &[
+                "title",
+                "subject",
+                "creator",
+                "keywords",
+                "description",
+                "lastModifiedBy",
+                "revision",
+                "created",
+                "modified",
+            ],
+        );
+        let get = |name: &str| field_value(&fields, name);
+        m.title = get("title");
+        m.subject = get("subject").or_else(|| get("description"));
+        m.author = get("creator");
+        m.keywords = get("keywords");
+        m.last_modified_by = get("lastModifiedBy");
+        m.revision = get("revision");
+        m.created_at = get("created").as_deref().and_then(parse_iso_date);
+        m.modified_at = get("modified").as_deref().and_then(parse_iso_date);
+    }
+    if let Ok(Some(xml)) = read_part(&mut pkg, "docProps/app.xml") {
+        let fields = xml_fields(&xml, &["Application", "Pages", "Slides", "Words"]);
+        let get = |name: &str| field_value(&fields, name);
+        let count = |name: &str| get(name).and_then(|v| v.parse::<i64>().ok());
+        m.creator_tool = get("Application");
+        m.page_count = count("Pages");
+        m.slide_count = count("Slides");
+        m.word_count = count("Words");
+    }
+    if m.slide_count.is_none() {
+        let slides = numbered_parts(&pkg, "ppt/slides/slide").len();
+        if slides > 0 {
+            m.slide_count = Some(slides as i64);
+        }
+    }
+    m
+}
+
+// ---------- legacy binary Office ----------
+
+/// Best-effort text from OLE2 .doc/.ppt/.xls: printable runs of 8-bit text
+/// and of UTF-16LE text (the encoding Office uses for most body text).
+fn extract_binary_strings(bytes: &[u8]) -> String {
+    let mut out = String::new();
+
+    // UTF-16LE runs
+    let mut run: Vec<u16> = Vec::new();
+    for pair in bytes.chunks_exact(2) {
+        let u = u16::from_le_bytes([pair[0], pair[1]]);
+        let printable = matches!(u, 0x20..=0x7E | 0x0900..=0x097F | 0x00A0..=0x024F)
+            || u == 0x09
+            || u == 0x0D;
+        if printable {
+            run.push(if u == 0x0D { 0x0A } else { u });
+        } else {
+            flush_utf16_run(&mut run, &mut out);
+        }
+    }
+    flush_utf16_run(&mut run, &mut out);
+
+    // 8-bit runs
+    let mut run8: Vec<u8> = Vec::new();
+    for b in bytes {
+        if (0x20..=0x7E).contains(b) || *b == b'\t' {
+            run8.push(*b);
+        } else {
+            if run8.len() >= MIN_RUN * 2 && run8.iter().any(|c| c.is_ascii_alphabetic()) {
+                out.push_str(&String::from_utf8_lossy(&run8));
+                out.push('\n');
+            }
+            run8.clear();
+        }
+    }
+    out
+}
+
+fn flush_utf16_run(run: &mut Vec<u16>, out: &mut String) {
+    if run.len() >= MIN_RUN {
+        let s = String::from_utf16_lossy(run);
+        if s.chars().filter(|c| c.is_alphabetic()).count() >= MIN_RUN {
+            out.push_str(&s);
+            out.push('\n');
+        }
+    }
+    run.clear();
+}
+
+// ---------- image EXIF ----------
+
+fn exif_ascii(exif: &exif::Exif, tag: exif::Tag) -> Option<String> {
+    match &exif.get_field(tag, exif::In::PRIMARY)?.value {
+        exif::Value::Ascii(parts) => parts
+            .iter()
+            .find_map(|p| non_blank(&String::from_utf8_lossy(p))),
+        _ => None,
+    }
+}
+
+/// Degrees from a GPS (degrees, minutes, seconds) triple and its N/S/E/W ref.
+fn exif_coordinate(exif: &exif::Exif, tag: exif::Tag, ref_tag: exif::Tag) -> Option<f64> {
+    let dms = match &exif.get_field(tag, exif::In::PRIMARY)?.value {
+        exif::Value::Rational(v) if !v.is_empty() => v.clone(),
+        _ => return None,
+    };
+    let part = |i: usize| dms.get(i).map(|r| r.to_f64()).unwrap_or(0.0);
+    let deg = part(0) + part(1) / 60.0 + part(2) / 3600.0;
+    if !deg.is_finite() {
+        return None;
+    }
+    let negative = matches!(exif_ascii(exif, ref_tag).as_deref(), Some("S") | Some("W"));
+    Some(if negative { -deg } else { deg })
+}
+
+/// Camera, capture time and position from the EXIF block of a JPEG, TIFF,
+/// PNG, HEIF or WebP image.
+fn exif_metadata(bytes: &[u8]) -> DocMetadata {
+    let exif = match exif::Reader::new().read_from_container(&mut Cursor::new(bytes)) {
+        Ok(e) => e,
+        Err(_) => return DocMetadata::default(),
+    };
+    let taken_at = [exif::Tag::DateTimeOriginal, exif::Tag::DateTime]
+        .iter()
+        .find_map(|tag| {
+            let field = exif.get_field(*tag, exif::In::PRIMARY)?;
+            let raw = match &field.value {
+                exif::Value::Ascii(parts) => parts.first()?.clone(),
+                _ => return None,
+            };
+            let t = exif::DateTime::from_ascii(&raw).ok()?;
+            let naive = NaiveDate::from_ymd_opt(t.year as i32, t.month as u32, t.day as u32)?
+                .and_hms_opt(t.hour as u32, t.minute as u32, t.second as u32)?;
+            Some(Utc.from_utc_datetime(&naive).timestamp())
+        });
+    DocMetadata {
+        camera_make: exif_ascii(&exif, exif::Tag::Make),
+        camera_model: exif_ascii(&exif, exif::Tag::Model),
+        creator_tool: exif_ascii(&exif, exif::Tag::Software),
+        author: exif_ascii(&exif, exif::Tag::Artist),
+        title: exif_ascii(&exif, exif::Tag::ImageDescription),
+        taken_at,
+        gps_lat: exif_coordinate(&exif, exif::Tag::GPSLatitude, exif::Tag::GPSLatitudeRef),
+        gps_lon: exif_coordinate(&exif, exif::Tag::GPSLongitude, exif::Tag::GPSLongitudeRef),
+        ..Default::default()
+    }
+}
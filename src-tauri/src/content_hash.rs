@@ -0,0 +1,630 @@
+// src-tauri/src/content_hash.rs
+//
+// Content hashes of indexed files (BLAKE3 by default, SHA-256 optionally),
+// the basis for duplicate detection, integrity checks and recognising a file
+// after it moved. Off until an admin enables it.
+//
+// Hashes are computed lazily by a background pass after walks and watcher
+// events, one file at a time, and kept in `file_hashes` together with the
+// size and mtime they were computed from; a file is re-hashed only when
+// those change (or the algorithm is switched). The row belongs to the
+// files_index id, so it follows the file through renames and moves.
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{command, AppHandle};
+
+const CONFIG_KEY: &str = "hash.config";
+const BATCH: i64 = 200;
+const READ_BUF: usize = 1 << 20;
+/// Pause between batches so a large backlog does not monopolise the disk.
+const YIELD_BETWEEN_BATCHES: Duration = Duration::from_millis(100);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static RERUN: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Blake3,
+    Sha256,
+}
+
+impl HashAlgorithm {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HashAlgorithm::Blake3 => "blake3",
+            HashAlgorithm::Sha256 => "sha256",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashConfig {
+    pub enabled: bool,
+    #[serde(default = "default_algorithm")]
+    pub algorithm: HashAlgorithm,
+    /// Files larger than this are not hashed; None = no limit.
+    #[serde(default)]
+    pub max_file_size: Option<i64>,
+}
+
+fn default_algorithm() -> HashAlgorithm {
+    HashAlgorithm::Blake3
+}
+
+impl Default for HashConfig {
+    fn default() -> Self {
+        HashConfig {
+            enabled: false,
+            algorithm: default_algorithm(),
+            max_file_size: None,
+        }
+    }
+}
+
+pub fn load_config(conn: &Connection) -> Result<HashConfig, String> {
+    match crate::db::get_setting(conn, CONFIG_KEY)? {
+        Some(raw) => serde_json::from_str(&raw).map_err(|e| format!("hash config: {}", e)),
+        None => Ok(HashConfig::default()),
+    }
+}
+
+pub(crate) fn ensure_tables(conn: &Connection) -> Result<(), String> {
+    crate::fs_ops::ensure_files_index_tables(conn)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS file_hashes (
+            file_id INTEGER PRIMARY KEY,
+            algorithm TEXT NOT NULL,
+            hash TEXT,
+            status TEXT NOT NULL,
+            error TEXT,
+            source_size INTEGER,
+            source_mtime INTEGER,
+            hashed_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_file_hashes_hash ON file_hashes(hash);
+        CREATE TRIGGER IF NOT EXISTS files_index_hash_ad AFTER DELETE ON files_index BEGIN
+            DELETE FROM file_hashes WHERE file_id = old.id;
+        END;",
+    )
+    .map_err(|e| format!("ensure file_hashes: {}", e))
+}
+
+/// Stream `path` through the hasher; returns the lowercase hex digest.
+pub fn hash_file(path: &str, algorithm: HashAlgorithm) -> Result<String, String> {
+    let mut f = std::fs::File::open(path).map_err(|e| format!("open {}: {}", path, e))?;
+    let mut buf = vec![0u8; READ_BUF];
+    match algorithm {
+        HashAlgorithm::Blake3 => {
+            let mut h = blake3::Hasher::new();
+            loop {
+                let n = f.read(&mut buf).map_err(|e| format!("read {}: {}", path, e))?;
+                if n == 0 {
+                    break;
+                }
+                h.update(&buf[..n]);
+            }
+            Ok(h.finalize().to_hex().to_string())
+        }
+        HashAlgorithm::Sha256 => {
+            let mut h = Sha256::new();
+            loop {
+                let n = f.read(&mut buf).map_err(|e| format!("read {}: {}", path, e))?;
+                if n == 0 {
+                    break;
+                }
+                h.update(&buf[..n]);
+            }
+            Ok(hex::encode(h.finalize()))
+        }
+    }
+}
+
+fn stat(path: &str) -> Option<(i64, Option<i64>)> {
+    let meta = std::fs::metadata(path).ok()?;
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
+    Some((meta.len() as i64, mtime))
+}
+
+/// Hash one indexed file and store the result. Returns the hash when the
+/// file was stable while it was read.
+pub(crate) fn hash_and_store(
+    conn: &Connection,
+    file_id: i64,
+    path: &str,
+    algorithm: HashAlgorithm,
+) -> Result<Option<String>, String> {
+    let before = stat(path);
+    let (status, hash, error) = match hash_file(path, algorithm) {
+        // written to while we read it: leave it pending for the next pass
+        Ok(_) if stat(path) != before => return Ok(None),
+        Ok(h) => ("ok", Some(h), None),
+        Err(e) => ("error", None, Some(e)),
+    };
+    // the row may predate the last change (no walk or event since); refresh
+    // it so the hash is not considered stale straight away
+    let indexed: Option<(Option<i64>, Option<i64>)> = conn
+        .query_row(
+            "SELECT size, mtime FROM files_index WHERE id = ?1",
+            params![file_id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    let (size, mtime) = match before {
+        Some((s, m)) => (Some(s), m),
+        // not even stat-able: record the error against the indexed size and
+        // mtime, so it is retried once the index sees a change, not every pass
+        None => indexed.unwrap_or((None, None)),
+    };
+    if before.is_some() && indexed.is_some_and(|row| row != (size, mtime)) {
+        crate::indexer::upsert_path(conn, path, chrono::Utc::now().timestamp())?;
+    }
+    conn.execute(
+        "INSERT INTO file_hashes (file_id, algorithm, hash, status, error, source_size, source_mtime, hashed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT(file_id) DO UPDATE SET
+           algorithm = excluded.algorithm,
+           hash = excluded.hash,
+           status = excluded.status,
+           error = excluded.error,
+           source_size = excluded.source_size,
+           source_mtime = excluded.source_mtime,
+           hashed_at = excluded.hashed_at",
+        params![
+            file_id,
+            algorithm.as_str(),
+            hash,
+            status,
+            error,
+            size,
+            mtime,
+            chrono::Utc::now().timestamp()
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(hash)
+}
+
+/// Hash of the file's current content: the stored one when it was taken under
+/// `algorithm` from a file of the size and mtime on disk now (the index row
+/// may lag behind the file), otherwise computed now (None if the file kept
+/// changing or could not be read).
+pub(crate) fn current_hash(
+    conn: &Connection,
+    file_id: i64,
+    path: &str,
+    algorithm: HashAlgorithm,
+) -> Result<Option<String>, String> {
+    let (size, mtime) = match stat(path) {
+        Some(s) => s,
+        None => return hash_and_store(conn, file_id, path, algorithm),
+    };
+    let stored: Option<String> = conn
+        .query_row(
+            "SELECT hash FROM file_hashes
+             WHERE file_id = ?1 AND algorithm = ?2 AND status = 'ok'
+               AND source_size = ?3 AND source_mtime IS ?4",
+            params![file_id, algorithm.as_str(), size, mtime],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    match stored {
+        Some(h) => Ok(Some(h)),
+        None => hash_and_store(conn, file_id, path, algorithm),
+    }
+}
+
+/// Rows whose stored hash is missing or stale (joined as `fi`/`fh`).
+const STALE_FILTER: &str = "(fh.file_id IS NULL OR fh.algorithm IS NOT ?1
+      OR fh.source_size IS NOT fi.size OR fh.source_mtime IS NOT fi.mtime)";
+
+/// Whether a hashing pass is running (the scheduler keeps rescans apart from it).
+pub(crate) fn pass_running() -> bool {
+    RUNNING.load(Ordering::SeqCst)
+}
+
+/// Request a hashing pass (no-op while hashing is disabled). At most one
+/// pass runs at a time; a request made during a pass triggers one more.
+pub fn schedule_pass() {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        RERUN.store(true, Ordering::SeqCst);
+        return;
+    }
+    let spawned = std::thread::Builder::new()
+        .name("content-hash".into())
+        .spawn(|| loop {
+            RERUN.store(false, Ordering::SeqCst);
+            if let Err(e) = run_pass() {
+                eprintln!("[content_hash] pass failed: {}", e);
+            }
+            if !RERUN.load(Ordering::SeqCst) {
+                RUNNING.store(false, Ordering::SeqCst);
+                if RERUN.load(Ordering::SeqCst) && !RUNNING.swap(true, Ordering::SeqCst) {
+                    continue;
+                }
+                break;
+            }
+        });
+    if spawned.is_err() {
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+fn run_pass() -> Result<usize, String> {
+    let conn = crate::db::open_connection()?;
+    ensure_tables(&conn)?;
+    let config = load_config(&conn)?;
+    if !config.enabled {
+        return Ok(0);
+    }
+    let sql = format!(
+        "SELECT fi.id, fi.path
+         FROM files_index fi
+         LEFT JOIN file_hashes fh ON fh.file_id = fi.id
+         WHERE fi.id > ?2
+           AND fi.file_type = 'file'
+           AND (?3 IS NULL OR fi.size <= ?3)
+           AND {}
+         ORDER BY fi.id
+         LIMIT ?4",
+        STALE_FILTER
+    );
+    let mut cursor = 0i64;
+    let mut done = 0usize;
+    loop {
+        let batch: Vec<(i64, String)> = {
+            let mut stmt = conn.prepare_cached(&sql).map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map(
+                    params![config.algorithm.as_str(), cursor, config.max_file_size, BATCH],
+                    |r| Ok((r.get(0)?, r.get(1)?)),
+                )
+                .map_err(|e| e.to_string())?;
+            rows.filter_map(|r| r.ok()).collect()
+        };
+        if batch.is_empty() {
+            break;
+        }
+        for (id, path) in batch {
+            cursor = id;
+            if let Err(e) = hash_and_store(&conn, id, &path, config.algorithm) {
+                eprintln!("[content_hash] store {}: {}", path, e);
+            }
+            done += 1;
+        }
+        std::thread::sleep(YIELD_BETWEEN_BATCHES);
+    }
+    Ok(done)
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct VerifyReport {
+    /// Files re-read whose digest still matched.
+    pub verified: usize,
+    /// Same size and mtime, different content.
+    pub mismatched: Vec<String>,
+    /// Changed on disk since they were hashed; re-hashed instead.
+    pub rehashed: usize,
+    /// Gone or unreadable; recorded as errors.
+    pub failed: usize,
+}
+
+/// Re-read files whose stored hash is fresh by size and mtime and compare the
+/// digest. A different digest without a size or mtime change means the
+/// content changed behind the index (bit rot, or a write that reset the
+/// mtime): the row is marked `mismatch` and the finding audited. Oldest
+/// verifications go first, so `max_files` walks the library over several runs.
+pub(crate) fn verify_hashes(
+    conn: &Connection,
+    max_files: Option<i64>,
+) -> Result<VerifyReport, String> {
+    ensure_tables(conn)?;
+    let ids: Vec<i64> = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT file_id FROM file_hashes WHERE status = 'ok'
+                 ORDER BY hashed_at, file_id LIMIT ?1",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![max_files.unwrap_or(-1)], |r| r.get(0))
+            .map_err(|e| e.to_string())?;
+        rows.filter_map(|r| r.ok()).collect()
+    };
+
+    let mut report = VerifyReport::default();
+    for (i, id) in ids.into_iter().enumerate() {
+        if i > 0 && i as i64 % BATCH == 0 {
+            std::thread::sleep(YIELD_BETWEEN_BATCHES);
+        }
+        let row = conn
+            .query_row(
+                "SELECT fi.path, fh.algorithm, fh.hash, fh.source_size, fh.source_mtime
+                 FROM file_hashes fh JOIN files_index fi ON fi.id = fh.file_id
+                 WHERE fh.file_id = ?1 AND fh.status = 'ok'",
+                params![id],
+                |r| {
+                    Ok((
+                        r.get::<_, String>(0)?,
+                        r.get::<_, String>(1)?,
+                        r.get::<_, String>(2)?,
+                        r.get::<_, Option<i64>>(3)?,
+                        r.get::<_, Option<i64>>(4)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+        let Some((path, algorithm, stored, size, mtime)) = row else {
+            continue;
+        };
+        let algorithm = match algorithm.as_str() {
+            "sha256" => HashAlgorithm::Sha256,
+            _ => HashAlgorithm::Blake3,
+        };
+        let before = stat(&path);
+        if before != size.map(|s| (s, mtime)) {
+            match hash_and_store(conn, id, &path, algorithm)? {
+                Some(_) => report.rehashed += 1,
+                None if before.is_none() => report.failed += 1,
+                None => {}
+            }
+            continue;
+        }
+        let now = chrono::Utc::now().timestamp();
+        match hash_file(&path, algorithm) {
+            // modified while we read it; the next hashing pass picks it up
+            Ok(_) if stat(&path) != before => {}
+            Ok(h) if h == stored => {
+                conn.execute(
+                    "UPDATE file_hashes SET hashed_at = ?2 WHERE file_id = ?1",
+                    params![id, now],
+                )
+                .map_err(|e| e.to_string())?;
+                report.verified += 1;
+            }
+            Ok(h) => {
+                conn.execute(
+                    "UPDATE file_hashes SET status = 'mismatch', error = ?2, hashed_at = ?3
+                     WHERE file_id = ?1",
+                    params![id, format!("content now hashes to {}", h), now],
+                )
+                .map_err(|e| e.to_string())?;
+                let _ = crate::db::insert_audit_log(
+                    conn,
+                    None,
+                    None,
+                    "hash_mismatch",
+                    None,
+                    Some(&format!(
+                        "{} (expected {} {}, found {})",
+                        path,
+                        algorithm.as_str(),
+                        stored,
+                        h
+                    )),
+                );
+                report.mismatched.push(path);
+            }
+            Err(e) => {
+                conn.execute(
+                    "UPDATE file_hashes SET status = 'error', error = ?2, hashed_at = ?3
+                     WHERE file_id = ?1",
+                    params![id, e, now],
+                )
+                .map_err(|e| e.to_string())?;
+                report.failed += 1;
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[derive(Debug, Serialize)]
+pub struct HashedFile {
+    pub id: i64,
+    pub path: String,
+    pub name: String,
+    pub size: Option<i64>,
+    pub mtime: Option<i64>,
+    pub algorithm: String,
+    pub hash: String,
+    pub hashed_at: i64,
+}
+
+fn hashed_file(r: &rusqlite::Row) -> rusqlite::Result<HashedFile> {
+    Ok(HashedFile {
+        id: r.get(0)?,
+        path: r.get(1)?,
+        name: r.get(2)?,
+        size: r.get(3)?,
+        mtime: r.get(4)?,
+        algorithm: r.get(5)?,
+        hash: r.get(6)?,
+        hashed_at: r.get(7)?,
+    })
+}
+
+const HASHED_FILE_COLUMNS: &str =
+    "fi.id, fi.path, fi.name, fi.size, fi.mtime, fh.algorithm, fh.hash, fh.hashed_at";
+
+/// Indexed files whose current content has `hash` under `algorithm`. BLAKE3
+/// and SHA-256 digests are both 64 hex characters, so the algorithm has to
+/// match as well.
+pub fn files_with_hash(
+    conn: &Connection,
+    hash: &str,
+    algorithm: HashAlgorithm,
+) -> Result<Vec<HashedFile>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM file_hashes fh JOIN files_index fi ON fi.id = fh.file_id
+             WHERE fh.hash = ?1 AND fh.algorithm = ?2 AND fh.status = 'ok'
+               AND fh.source_size IS fi.size AND fh.source_mtime IS fi.mtime
+             ORDER BY fi.path",
+            HASHED_FILE_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![hash.trim().to_lowercase(), algorithm.as_str()], hashed_file)
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Find indexed files by content hash.
+#[tauri::command]
+pub fn find_files_by_hash(session_token: String, hash: String) -> Result<Vec<HashedFile>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("db open: {}", e))?;
+    let uid = crate::session::validate_session(&conn, &session_token)
+        .map_err(|e| format!("session validation: {}", e))?
+        .ok_or_else(|| "invalid session".to_string())?;
+    ensure_tables(&conn)?;
+    if hash.trim().is_empty() || !hash.trim().chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("hash must be hex".into());
+    }
+    let mut found = files_with_hash(&conn, &hash, load_config(&conn)?.algorithm)?;
+    // rows of restricted locations are admin-only, as in search results
+    found.retain(|f| matches!(crate::fs_ops::visible_file_id(&conn, uid, &f.path), Ok(Some(_))));
+    Ok(found)
+}
+
+/// Content hash of one indexed file, computed now if it is missing or stale
+/// (works while background hashing is disabled).
+#[tauri::command]
+pub fn get_file_hash(session_token: String, path: String) -> Result<HashedFile, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("db open: {}", e))?;
+    let uid = crate::session::validate_session(&conn, &session_token)
+        .map_err(|e| format!("session validation: {}", e))?
+        .ok_or_else(|| "invalid session".to_string())?;
+    ensure_tables(&conn)?;
+    crate::fs_ops::visible_file_id(&conn, uid, &path)?
+        .ok_or_else(|| "file is not indexed or not accessible".to_string())?;
+    let config = load_config(&conn)?;
+
+    let lookup = format!(
+        "SELECT {} FROM files_index fi JOIN file_hashes fh ON fh.file_id = fi.id
+         WHERE fi.path = ?2 AND fh.status = 'ok' AND NOT {}",
+        HASHED_FILE_COLUMNS, STALE_FILTER
+    );
+    let fresh = |conn: &Connection| {
+        conn.query_row(&lookup, params![config.algorithm.as_str(), path], hashed_file)
+            .optional()
+            .map_err(|e| e.to_string())
+    };
+    if let Some(found) = fresh(&conn)? {
+        return Ok(found);
+    }
+
+    let file_id: i64 = conn
+        .query_row(
+            "SELECT id FROM files_index WHERE path = ?1 AND file_type = 'file'",
+            params![path],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "file is not indexed".to_string())?;
+    hash_and_store(&conn, file_id, &path, config.algorithm)?
+        .ok_or_else(|| "file changed while hashing; try again".to_string())?;
+    fresh(&conn)?.ok_or_else(|| "hashing failed".to_string())
+}
+
+#[derive(Debug, Serialize)]
+pub struct HashStatus {
+    pub config: HashConfig,
+    pub running: bool,
+    /// Files still to hash under the current config.
+    pub pending: i64,
+    /// (status, count) over file_hashes.
+    pub by_status: Vec<(String, i64)>,
+}
+
+fn status(conn: &Connection) -> Result<HashStatus, String> {
+    let config = load_config(conn)?;
+    let pending: i64 = conn
+        .query_row(
+            &format!(
+                "SELECT COUNT(*) FROM files_index fi
+                 LEFT JOIN file_hashes fh ON fh.file_id = fi.id
+                 WHERE fi.file_type = 'file' AND (?2 IS NULL OR fi.size <= ?2) AND {}",
+                STALE_FILTER
+            ),
+            params![config.algorithm.as_str(), config.max_file_size],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare("SELECT status, COUNT(*) FROM file_hashes GROUP BY status ORDER BY status")
+        .map_err(|e| e.to_string())?;
+    let by_status = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(HashStatus {
+        config,
+        running: RUNNING.load(Ordering::SeqCst),
+        pending,
+        by_status,
+    })
+}
+
+#[command]
+pub fn admin_get_hash_settings(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<HashStatus, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    ensure_tables(&conn)?;
+    status(&conn)
+}
+
+#[command]
+pub fn admin_set_hash_settings(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    config: HashConfig,
+) -> Result<HashStatus, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    ensure_tables(&conn)?;
+
+    if config.max_file_size.is_some_and(|n| n < 0) {
+        return Err("max_file_size must not be negative".into());
+    }
+    let raw = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    crate::db::set_setting(&conn, CONFIG_KEY, &raw)?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_set_hash_settings",
+        None,
+        Some(&format!(
+            "enabled={} algorithm={} max_file_size={:?}",
+            config.enabled,
+            config.algorithm.as_str(),
+            config.max_file_size
+        )),
+    );
+
+    schedule_pass();
+    status(&conn)
+}
@@ -0,0 +1,336 @@
+// src-tauri/src/content_index.rs
+//
+// Background document-text indexer. After a walk (or a watcher event) adds
+// rows to files_index, a pass picks up supported documents that have no
+// extracted text yet (or whose size or mtime changed), runs the extractors from
+// content_extract and stores the text in the `body` column of
+// files_index_fts and the embedded properties in file_metadata. Per-file
+// outcome is kept in file_content so failed or oversized files are not
+// retried on every pass. Scanned PDFs and images are handed on to the OCR
+// stage (ocr.rs) after each pass.
+use crate::content_extract::{self, DocKind, DocMetadata, ExtractError, SUPPORTED_EXTENSIONS};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const BATCH: i64 = 200;
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+/// Set when a pass is requested while one is already running.
+static RERUN: AtomicBool = AtomicBool::new(false);
+
+/// Whether a content pass is running (the scheduler keeps rescans apart from it).
+pub(crate) fn pass_running() -> bool {
+    RUNNING.load(Ordering::SeqCst)
+}
+
+/// Request a content pass. Returns immediately; at most one pass runs at a
+/// time and a request made during a pass triggers one more pass after it.
+pub fn schedule_pass() {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        RERUN.store(true, Ordering::SeqCst);
+        return;
+    }
+    let spawned = std::thread::Builder::new()
+        .name("content-index".into())
+        .spawn(|| loop {
+            RERUN.store(false, Ordering::SeqCst);
+            if let Err(e) = run_pass() {
+                eprintln!("[content_index] pass failed: {}", e);
+            }
+            if !RERUN.load(Ordering::SeqCst) {
+                RUNNING.store(false, Ordering::SeqCst);
+                // a request may have slipped in between the check and the store
+                if RERUN.load(Ordering::SeqCst) && !RUNNING.swap(true, Ordering::SeqCst) {
+                    continue;
+                }
+                break;
+            }
+        });
+    if spawned.is_err() {
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// SQL predicate selecting rows whose name has a supported extension.
+fn extension_filter() -> String {
+    SUPPORTED_EXTENSIONS
+        .iter()
+        .map(|(ext, _)| format!("lower(fi.name) LIKE '%.{}'", ext))
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
+
+struct Outcome {
+    status: &'static str,
+    text: String,
+    error: Option<String>,
+    metadata: DocMetadata,
+}
+
+impl Outcome {
+    fn failed(status: &'static str, error: String) -> Self {
+        Outcome { status, text: String::new(), error: Some(error), metadata: DocMetadata::default() }
+    }
+}
+
+fn extract_file(path: &str, kind: DocKind) -> (Outcome, Option<i64>, Option<i64>) {
+    let meta = match std::fs::metadata(path) {
+        Ok(m) => m,
+        Err(e) => {
+            return (Outcome::failed("error", e.to_string()), None, None)
+        }
+    };
+    let size = meta.len() as i64;
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
+
+    if meta.len() > kind.max_input_bytes() {
+        let error = format!("{} bytes exceeds {} limit", size, kind.as_str());
+        return (Outcome::failed("too_large", error), Some(size), mtime);
+    }
+
+    let outcome = match std::fs::read(path) {
+        Err(e) => Outcome::failed("error", e.to_string()),
+        Ok(bytes) => extract_bytes(kind, bytes),
+    };
+    (outcome, Some(size), mtime)
+}
+
+/// Run the extractor over a document's bytes. Images carry no text of their
+/// own (that is left to the OCR stage), only EXIF metadata.
+fn extract_bytes(kind: DocKind, bytes: Vec<u8>) -> Outcome {
+    match content_extract::extract_document_with_limits(kind, bytes) {
+        Ok(doc) if doc.text.trim().is_empty() => {
+            Outcome { status: "empty", text: String::new(), error: None, metadata: doc.metadata }
+        }
+        Ok(doc) => Outcome { status: "ok", text: doc.text, error: None, metadata: doc.metadata },
+        Err(e @ ExtractError::Timeout) => Outcome::failed("timeout", e.to_string()),
+        Err(e @ ExtractError::TooLarge(_)) => Outcome::failed("too_large", e.to_string()),
+        Err(e) => Outcome::failed("error", e.to_string()),
+    }
+}
+
+fn store(
+    conn: &Connection,
+    file_id: i64,
+    kind: DocKind,
+    outcome: &Outcome,
+    size: Option<i64>,
+    mtime: Option<i64>,
+) -> Result<(), String> {
+    let now = chrono::Utc::now().timestamp();
+    conn.execute(
+        "UPDATE files_index_fts SET body = ?1 WHERE rowid = ?2",
+        params![outcome.text, file_id],
+    )
+    .map_err(|e| e.to_string())?;
+    crate::file_metadata::store(conn, file_id, &outcome.metadata)?;
+    conn.execute(
+        "INSERT INTO file_content (file_id, extractor, status, chars, error, source_size, source_mtime, extracted_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT(file_id) DO UPDATE SET
+           extractor = excluded.extractor,
+           status = excluded.status,
+           chars = excluded.chars,
+           error = excluded.error,
+           source_size = excluded.source_size,
+           source_mtime = excluded.source_mtime,
+           extracted_at = excluded.extracted_at,
+           ocr_status = NULL,
+           ocr_sha256 = NULL",
+        params![
+            file_id,
+            kind.as_str(),
+            outcome.status,
+            outcome.text.chars().count() as i64,
+            outcome.error,
+            size,
+            mtime,
+            now
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Whether `file_id` was last extracted from a source of this size and mtime.
+pub(crate) fn is_current(
+    conn: &Connection,
+    file_id: i64,
+    size: Option<i64>,
+    mtime: Option<i64>,
+) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM file_content
+         WHERE file_id = ?1 AND source_size IS ?2 AND source_mtime IS ?3",
+        params![file_id, size, mtime],
+        |r| r.get::<_, i64>(0),
+    )
+    .map(|n| n > 0)
+    .map_err(|e| e.to_string())
+}
+
+/// Extract and store the text of a document read from somewhere other than
+/// its indexed path (archive members, see archive_index.rs). `None` stands
+/// for a document over the extractor's input limit.
+pub(crate) fn index_bytes(
+    conn: &Connection,
+    file_id: i64,
+    kind: DocKind,
+    bytes: Option<Vec<u8>>,
+    size: Option<i64>,
+    mtime: Option<i64>,
+) -> Result<(), String> {
+    let outcome = match bytes {
+        Some(bytes) => extract_bytes(kind, bytes),
+        None => Outcome::failed(
+            "too_large",
+            format!("{} bytes exceeds {} limit", size.unwrap_or(0), kind.as_str()),
+        ),
+    };
+    store(conn, file_id, kind, &outcome, size, mtime)
+}
+
+fn run_pass() -> Result<usize, String> {
+    let conn = crate::db::open_connection()?;
+    crate::fs_ops::ensure_files_index_tables(&conn)?;
+    let sql = format!(
+        "SELECT fi.id, fi.path, fi.name
+         FROM files_index fi
+         LEFT JOIN file_content fc ON fc.file_id = fi.id
+         WHERE fi.id > ?1
+           AND fi.file_type = 'file'
+           AND (fc.file_id IS NULL OR fc.source_size IS NOT fi.size
+                OR (fi.mtime IS NOT NULL AND fc.source_mtime IS NOT fi.mtime))
+           AND ({})
+         ORDER BY fi.id
+         LIMIT ?2",
+        extension_filter()
+    );
+    let mut cursor = 0i64;
+    let mut done = 0usize;
+    loop {
+        let batch: Vec<(i64, String, String)> = {
+            let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map(params![cursor, BATCH], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+                .map_err(|e| e.to_string())?;
+            rows.filter_map(|r| r.ok()).collect()
+        };
+        if batch.is_empty() {
+            break;
+        }
+        for (id, path, name) in batch {
+            cursor = id;
+            let kind = match content_extract::kind_for_name(&name) {
+                Some(k) => k,
+                None => continue,
+            };
+            let (outcome, size, mtime) = extract_file(&path, kind);
+            if let Err(e) = store(&conn, id, kind, &outcome, size, mtime) {
+                eprintln!("[content_index] store {}: {}", path, e);
+            }
+            done += 1;
+        }
+    }
+    // OCR is slower and lower priority; it runs once the text pass is done
+    crate::ocr::schedule_pass();
+    Ok(done)
+}
+
+#[derive(Serialize)]
+pub struct ContentIndexStatus {
+    pub running: bool,
+    /// Supported documents not yet extracted (or changed since extraction).
+    pub pending: i64,
+    /// (status, count) over file_content.
+    pub by_status: Vec<(String, i64)>,
+}
+
+/// Progress of document-text extraction.
+#[tauri::command]
+pub fn content_index_status(session_token: String) -> Result<ContentIndexStatus, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("db open: {}", e))?;
+    crate::session::validate_session(&conn, &session_token)
+        .map_err(|e| format!("session validation: {}", e))?
+        .ok_or_else(|| "invalid session".to_string())?;
+    crate::fs_ops::ensure_files_index_tables(&conn)?;
+
+    let pending: i64 = conn
+        .query_row(
+            &format!(
+                "SELECT COUNT(*) FROM files_index fi
+                 LEFT JOIN file_content fc ON fc.file_id = fi.id
+                 WHERE fi.file_type = 'file'
+                   AND (fc.file_id IS NULL OR fc.source_size IS NOT fi.size
+                        OR (fi.mtime IS NOT NULL AND fc.source_mtime IS NOT fi.mtime))
+                   AND ({})",
+                extension_filter()
+            ),
+            [],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare("SELECT status, COUNT(*) FROM file_content GROUP BY status ORDER BY status")
+        .map_err(|e| e.to_string())?;
+    let by_status = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(ContentIndexStatus {
+        running: RUNNING.load(Ordering::SeqCst),
+        pending,
+        by_status,
+    })
+}
+
+/// Admin: forget extracted text (optionally only failed/timed-out entries)
+/// and re-extract in the background.
+#[tauri::command]
+pub fn content_index_rebuild(
+    _app: tauri::AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    failed_only: Option<bool>,
+) -> Result<usize, String> {
+    let conn = crate::db::open_connection()?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    crate::fs_ops::ensure_files_index_tables(&conn)?;
+
+    let failed_only = failed_only.unwrap_or(false);
+    let reset = if failed_only {
+        conn.execute(
+            "DELETE FROM file_content WHERE status IN ('error','timeout')",
+            [],
+        )
+    } else {
+        conn.execute("DELETE FROM file_content", [])
+    }
+    .map_err(|e| e.to_string())?;
+    // archive members are extracted by the archive pass; rescanning skips
+    // the ones whose text is still stored
+    conn.execute("DELETE FROM archive_scans", [])
+        .map_err(|e| e.to_string())?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "content_index_rebuild",
+        None,
+        Some(&format!("failed_only:{} reset:{}", failed_only, reset)),
+    );
+
+    schedule_pass();
+    crate::archive_index::schedule_pass();
+    Ok(reset)
+}
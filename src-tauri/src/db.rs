@@ -4,6 +4,7 @@ use rusqlite::{params, Connection, OpenFlags};
 use std::fs;
 use std::path::PathBuf;
 use std::result::Result;
+use std::time::Duration;
 use uuid::Uuid;
 
 // Normalize path helper
@@ -12,17 +13,23 @@ fn normalize_path(p: &str) -> String {
 }
 // use dirs-next to locate OS-specific app data directory
 fn app_data_dir() -> Result<PathBuf, String> {
+    let mut p = data_dir()?;
+    p.push("vaultguard.sqlite");
+    Ok(p)
+}
+
+/// Directory that holds the sqlite DB and the other per-installation files
+/// (installation key, exports, archives). Created on first use.
+pub fn data_dir() -> Result<PathBuf, String> {
     // prefer local data dir (Windows: %LOCALAPPDATA%, Linux: $XDG_DATA_HOME, macOS: ~/Library/Application Support)
     if let Some(mut p) = dirs_next::data_local_dir() {
         p.push("vaultguard");
         fs::create_dir_all(&p).map_err(|e| format!("Failed to create data dir {:?}: {}", p, e))?;
-        p.push("vaultguard.sqlite");
         Ok(p)
     } else if let Some(mut p) = dirs_next::data_dir() {
         // fallback to generic data_dir
         p.push("vaultguard");
         fs::create_dir_all(&p).map_err(|e| format!("Failed to create data dir {:?}: {}", p, e))?;
-        p.push("vaultguard.sqlite");
         Ok(p)
     } else {
         Err(
@@ -42,7 +49,16 @@ pub fn open_connection() -> Result<Connection, String> {
     let flags = OpenFlags::SQLITE_OPEN_READ_WRITE
         | OpenFlags::SQLITE_OPEN_CREATE
         | OpenFlags::SQLITE_OPEN_FULL_MUTEX;
-    Connection::open_with_flags(p, flags).map_err(|e| e.to_string())
+    let conn = Connection::open_with_flags(p, flags).map_err(|e| e.to_string())?;
+    // background passes, the scheduler and commands each hold their own
+    // connection; wait for a competing writer instead of failing with SQLITE_BUSY
+    conn.busy_timeout(Duration::from_secs(5))
+        .map_err(|e| format!("set busy timeout: {}", e))?;
+    // WAL lets the indexer's walker connections read while its writer commits;
+    // the mode is stored in the file, so later opens only confirm it.
+    conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")
+        .map_err(|e| format!("set journal mode: {}", e))?;
+    Ok(conn)
 }
 
 // -----------------------------------------------------------------------------
@@ -103,6 +119,11 @@ pub fn init_db_schema(conn: &Connection) -> Result<(), String> {
       target_user_id INTEGER,
       details TEXT,
       created_at INTEGER NOT NULL,
+      target_type TEXT,
+      target_path TEXT,
+      target_dest_path TEXT,
+      target_file_id INTEGER,
+      target_dest_file_id INTEGER,
       FOREIGN KEY(actor_user_id) REFERENCES users(id) ON DELETE SET NULL,
       FOREIGN KEY(target_user_id) REFERENCES users(id) ON DELETE SET NULL
     );
@@ -141,12 +162,174 @@ CREATE TABLE IF NOT EXISTS recent_access (
   FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE
 );
 
+CREATE TABLE IF NOT EXISTS audit_exports (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  export_id TEXT NOT NULL UNIQUE,
+  export_dir TEXT NOT NULL,
+  from_ts INTEGER,
+  to_ts INTEGER,
+  audit_rows INTEGER NOT NULL,
+  login_rows INTEGER NOT NULL,
+  anchor TEXT NOT NULL,
+  signature TEXT NOT NULL,
+  created_by TEXT,
+  created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS retention_policies (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  table_name TEXT NOT NULL,
+  action_class TEXT NOT NULL DEFAULT '*',
+  retain_days INTEGER NOT NULL,
+  updated_by TEXT,
+  updated_at INTEGER NOT NULL,
+  UNIQUE(table_name, action_class)
+);
+
+CREATE TABLE IF NOT EXISTS audit_archives (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  archive_id TEXT NOT NULL UNIQUE,
+  table_name TEXT NOT NULL,
+  action_class TEXT NOT NULL,
+  file_path TEXT NOT NULL,
+  row_count INTEGER NOT NULL,
+  first_id INTEGER,
+  last_id INTEGER,
+  cutoff_ts INTEGER NOT NULL,
+  sha256 TEXT NOT NULL,
+  signature TEXT NOT NULL,
+  created_by TEXT,
+  created_at INTEGER NOT NULL,
+  restored_path TEXT
+);
+
+CREATE TABLE IF NOT EXISTS app_settings (
+  key TEXT PRIMARY KEY,
+  value TEXT NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS alert_rules (
+  rule_key TEXT PRIMARY KEY,
+  enabled INTEGER NOT NULL DEFAULT 1,
+  severity TEXT NOT NULL,
+  threshold INTEGER NOT NULL DEFAULT 0,
+  window_minutes INTEGER NOT NULL DEFAULT 0,
+  params TEXT,
+  updated_by TEXT,
+  updated_at INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS alerts (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  rule_key TEXT NOT NULL,
+  severity TEXT NOT NULL,
+  actor_user_id INTEGER,
+  actor_username TEXT,
+  summary TEXT NOT NULL,
+  details TEXT,
+  first_event_id INTEGER,
+  last_event_id INTEGER,
+  created_at INTEGER NOT NULL,
+  acknowledged INTEGER NOT NULL DEFAULT 0,
+  acknowledged_by TEXT,
+  acknowledged_at INTEGER,
+  ack_note TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_alerts_rule_created ON alerts(rule_key, created_at);
+CREATE INDEX IF NOT EXISTS idx_alerts_ack_created ON alerts(acknowledged, created_at);
+
+CREATE TABLE IF NOT EXISTS alert_seen_drives (
+  drive TEXT PRIMARY KEY,
+  first_seen_at INTEGER NOT NULL,
+  first_event_id INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS audit_forward_queue (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  audit_id INTEGER NOT NULL,
+  payload TEXT NOT NULL,
+  enqueued_at INTEGER NOT NULL,
+  attempts INTEGER NOT NULL DEFAULT 0,
+  next_attempt_at INTEGER NOT NULL,
+  last_error TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_audit_forward_queue_next ON audit_forward_queue(next_attempt_at);
+
+CREATE TABLE IF NOT EXISTS file_identities (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  current_path TEXT NOT NULL,
+  current_key TEXT NOT NULL,
+  kind TEXT NOT NULL DEFAULT 'file',
+  volume_dev INTEGER,
+  inode INTEGER,
+  origin_file_id INTEGER,
+  first_seen_at INTEGER NOT NULL,
+  last_seen_at INTEGER NOT NULL,
+  deleted_at INTEGER
+);
+CREATE INDEX IF NOT EXISTS idx_file_identities_key ON file_identities(current_key, deleted_at);
+CREATE INDEX IF NOT EXISTS idx_file_identities_inode ON file_identities(volume_dev, inode);
+CREATE INDEX IF NOT EXISTS idx_file_identities_origin ON file_identities(origin_file_id);
+
+CREATE TABLE IF NOT EXISTS file_path_history (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  file_id INTEGER NOT NULL,
+  path TEXT NOT NULL,
+  path_key TEXT NOT NULL,
+  valid_from INTEGER NOT NULL,
+  valid_to INTEGER,
+  audit_id INTEGER,
+  FOREIGN KEY(file_id) REFERENCES file_identities(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_file_path_history_file ON file_path_history(file_id, valid_from);
+CREATE INDEX IF NOT EXISTS idx_file_path_history_key ON file_path_history(path_key);
+
     "#;
 
     conn.execute_batch(sql).map_err(|e| e.to_string())?;
+
+    // columns added after the first release; CREATE TABLE IF NOT EXISTS leaves old tables alone
+    ensure_column(conn, "audit_logs", "target_type", "TEXT")?;
+    ensure_column(conn, "audit_logs", "target_path", "TEXT")?;
+    ensure_column(conn, "audit_logs", "target_dest_path", "TEXT")?;
+    ensure_column(conn, "audit_logs", "target_file_id", "INTEGER")?;
+    ensure_column(conn, "audit_logs", "target_dest_file_id", "INTEGER")?;
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_audit_logs_target_file ON audit_logs(target_file_id);
+         CREATE INDEX IF NOT EXISTS idx_audit_logs_target_dest_file ON audit_logs(target_dest_file_id);",
+    )
+    .map_err(|e| e.to_string())?;
     Ok(())
 }
 
+/// Add `column` to `table` if an older database does not have it yet.
+/// Returns true when the column was added.
+pub(crate) fn ensure_column(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    decl: &str,
+) -> Result<bool, String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .map_err(|e| e.to_string())?;
+    let names = stmt
+        .query_map([], |r| r.get::<_, String>(1))
+        .map_err(|e| e.to_string())?;
+    for n in names {
+        if n.map_err(|e| e.to_string())?.eq_ignore_ascii_case(column) {
+            return Ok(false);
+        }
+    }
+    conn.execute(
+        &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl),
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
 pub fn init_db() -> Result<Connection, String> {
     let conn = open_connection()?;
     init_db_schema(&conn)?;
@@ -324,6 +507,14 @@ pub fn revoke_session(conn: &Connection, token: &str) -> Result<(), String> {
     Ok(())
 }
 
+pub fn delete_expired_sessions(conn: &Connection, now: i64) -> Result<usize, String> {
+    conn.execute(
+        "DELETE FROM sessions WHERE expires_at IS NOT NULL AND expires_at < ?1",
+        params![now],
+    )
+    .map_err(|e| e.to_string())
+}
+
 pub fn insert_audit_log(
     conn: &Connection,
     actor_user_id: Option<i64>,
@@ -331,10 +522,130 @@ pub fn insert_audit_log(
     action: &str,
     target_user_id: Option<i64>,
     details: Option<&str>,
+) -> Result<i64, String> {
+    insert_audit_row(
+        conn,
+        actor_user_id,
+        actor_username,
+        action,
+        target_user_id,
+        details,
+        &AuditTarget::default(),
+    )
+}
+
+/// Structured target of a file action (see `file_history`).
+#[derive(Debug, Clone, Default)]
+pub struct AuditTarget {
+    pub target_type: Option<String>,
+    pub path: Option<String>,
+    pub dest_path: Option<String>,
+    pub file_id: Option<i64>,
+    pub dest_file_id: Option<i64>,
+}
+
+/// Audit a file action with structured targets. `path` is the file acted on;
+/// `dest_path` is the destination of a move/rename/copy. The file's stable
+/// identity is resolved (and moved along on rename/move) before the row is
+/// written, so per-file history survives path changes.
+pub fn insert_file_audit_log(
+    conn: &Connection,
+    actor_user_id: Option<i64>,
+    actor_username: Option<&str>,
+    action: &str,
+    path: &str,
+    dest_path: Option<&str>,
+    details: Option<&str>,
+) -> Result<i64, String> {
+    let (target, history_ids) = match crate::file_history::track(conn, action, path, dest_path) {
+        Ok(t) => t,
+        Err(e) => {
+            // identity tracking is best effort; the audit row itself must be written
+            eprintln!("[file_history] tracking failed for {}: {}", path, e);
+            (
+                AuditTarget {
+                    target_type: Some("file".into()),
+                    path: Some(path.to_string()),
+                    dest_path: dest_path.map(|d| d.to_string()),
+                    ..Default::default()
+                },
+                Vec::new(),
+            )
+        }
+    };
+    let id = insert_audit_row(
+        conn,
+        actor_user_id,
+        actor_username,
+        action,
+        None,
+        details,
+        &target,
+    )?;
+    crate::file_history::link_history_to_audit(conn, &history_ids, id);
+    Ok(id)
+}
+
+fn insert_audit_row(
+    conn: &Connection,
+    actor_user_id: Option<i64>,
+    actor_username: Option<&str>,
+    action: &str,
+    target_user_id: Option<i64>,
+    details: Option<&str>,
+    target: &AuditTarget,
 ) -> Result<i64, String> {
     let now = Utc::now().timestamp();
-    conn.execute("INSERT INTO audit_logs (actor_user_id, actor_username, action, target_user_id, details, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)", params![actor_user_id, actor_username, action, target_user_id, details, now]).map_err(|e| e.to_string())?;
-    Ok(conn.last_insert_rowid())
+    conn.execute(
+        "INSERT INTO audit_logs (actor_user_id, actor_username, action, target_user_id, details, created_at,
+                                 target_type, target_path, target_dest_path, target_file_id, target_dest_file_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            actor_user_id,
+            actor_username,
+            action,
+            target_user_id,
+            details,
+            now,
+            target.target_type,
+            target.path,
+            target.dest_path,
+            target.file_id,
+            target.dest_file_id
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    let id = conn.last_insert_rowid();
+    crate::audit_stream::publish(id, actor_user_id, actor_username, action, target_user_id, details, now, target);
+    if let Err(e) = crate::audit_forward::enqueue(conn, id) {
+        eprintln!("[audit_forward] enqueue failed: {}", e);
+    }
+    // anomaly rules run over the audit stream off the caller's write path
+    crate::alerts::schedule_pass();
+    Ok(id)
+}
+
+pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT value FROM app_settings WHERE key = ?1")
+        .map_err(|e| e.to_string())?;
+    let mut rows = stmt.query(params![key]).map_err(|e| e.to_string())?;
+    if let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        Ok(Some(row.get(0).map_err(|e| e.to_string())?))
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn set_setting(conn: &Connection, key: &str, value: &str) -> Result<(), String> {
+    let now = Utc::now().timestamp();
+    conn.execute(
+        "INSERT INTO app_settings (key, value, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        params![key, value, now],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
 }
 
 pub fn list_audit_logs(
@@ -476,32 +787,38 @@ pub fn get_indexing_by_drive_and_type(
     Ok(rows)
 }
 
-pub fn get_indexing_by_category_global(
-    conn: &Connection,
-) -> Result<Vec<(String, i64)>, rusqlite::Error> {
-    let sql = r#"
-    SELECT category, SUM(cnt) as total
-    FROM (
-      SELECT
-        CASE
+/// Dashboard category of a files_index row.
+pub(crate) const CATEGORY_SQL: &str = "CASE
           WHEN COALESCE(doc_type, '') = 'dir' THEN 'Dir'
           WHEN lower(COALESCE(doc_type, file_type, '')) IN ('pdf') THEN 'PDF'
           WHEN lower(COALESCE(doc_type, file_type, '')) IN ('doc','docx','odt') THEN 'Docs'
           WHEN lower(COALESCE(doc_type, file_type, '')) IN ('ppt','pptx') THEN 'PPT'
-          WHEN lower(COALESCE(doc_type, file_type, '')) IN ('xls','xlsx','csv') THEN 'Sheets'
+          WHEN lower(COALESCE(doc_type, file_type, '')) IN ('xls','xlsx','csv','sheet') THEN 'Sheets'
           WHEN lower(COALESCE(doc_type, file_type, '')) IN ('mp4','mkv','mov','avi','m4v','wmv') THEN 'Videos'
           WHEN lower(COALESCE(doc_type, file_type, '')) IN ('jpg','jpeg','png','gif','bmp','webp','heic','svg','image') THEN 'Images'
-          WHEN lower(COALESCE(doc_type, file_type, '')) IN ('txt','log','md','json','xml','yml','yaml') THEN 'Text'
+          WHEN lower(COALESCE(doc_type, file_type, '')) IN ('txt','log','md','json','xml','yml','yaml','text') THEN 'Text'
           WHEN lower(COALESCE(doc_type, file_type, '')) IN ('zip','rar','7z','tar','gz','exe','dll','bin','binary','archive') THEN 'Binary/Archive'
           ELSE 'Other'
-        END AS category,
+        END";
+
+pub fn get_indexing_by_category_global(
+    conn: &Connection,
+) -> Result<Vec<(String, i64)>, rusqlite::Error> {
+    let sql = format!(
+        r#"
+    SELECT category, SUM(cnt) as total
+    FROM (
+      SELECT
+        {} AS category,
         1 AS cnt
       FROM files_index
     ) sub
     GROUP BY category
     ORDER BY total DESC;
-    "#;
-    let mut stmt = conn.prepare(sql)?;
+    "#,
+        CATEGORY_SQL
+    );
+    let mut stmt = conn.prepare(&sql)?;
     let rows = stmt
         .query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?)))?
         .collect::<Result<Vec<_>, _>>()?;
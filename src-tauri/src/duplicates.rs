@@ -0,0 +1,463 @@
+// src-tauri/src/duplicates.rs
+//
+// Duplicate finder over files_index. Candidates are grouped by size first
+// (cheap, from the index) and only files that share a size are hashed
+// (content_hash; stored hashes are reused while fresh), so a report does
+// not read the whole library even when background hashing is off.
+//
+// An admin can resolve a set by keeping one copy: every other copy is
+// re-hashed from disk right before deletion (a copy edited since the report
+// is skipped, as is a path that reaches the kept file itself through a hard
+// link, bind mount or differently spelt path), deleted, audited as `fs_delete` (which closes its file
+// identity) and recorded in `duplicate_removals` with the copy that was kept.
+use crate::content_hash;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{command, AppHandle};
+
+const DEFAULT_MIN_SIZE: i64 = 1;
+const DEFAULT_SET_LIMIT: usize = 200;
+
+pub(crate) fn ensure_tables(conn: &Connection) -> Result<(), String> {
+    content_hash::ensure_tables(conn)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS duplicate_removals (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            removed_path TEXT NOT NULL,
+            kept_path TEXT NOT NULL,
+            algorithm TEXT NOT NULL,
+            hash TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            removed_by INTEGER,
+            removed_by_username TEXT,
+            removed_at INTEGER NOT NULL,
+            audit_id INTEGER
+        );
+        CREATE INDEX IF NOT EXISTS idx_duplicate_removals_at ON duplicate_removals(removed_at);
+        CREATE INDEX IF NOT EXISTS idx_duplicate_removals_hash ON duplicate_removals(hash);",
+    )
+    .map_err(|e| format!("ensure duplicate_removals: {}", e))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateCopy {
+    pub file_id: i64,
+    pub path: String,
+    pub drive: Option<String>,
+    pub mtime: Option<i64>,
+    pub ctime: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateSet {
+    pub algorithm: String,
+    pub hash: String,
+    pub size: i64,
+    pub copies: Vec<DuplicateCopy>,
+    /// Bytes freed by keeping a single copy.
+    pub wasted_bytes: i64,
+    /// Copy with the earliest modification time (the likely original).
+    pub oldest: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateReport {
+    pub sets: Vec<DuplicateSet>,
+    /// Over all sets found, including those cut off by `limit`.
+    pub set_count: usize,
+    pub total_wasted_bytes: i64,
+    /// Files that shared a size with another file.
+    pub candidates: usize,
+    /// Candidates hashed during this request (no fresh stored hash).
+    pub hashed_now: usize,
+}
+
+/// Group indexed files (optionally under `root`) of at least `min_size`
+/// bytes into sets with identical content, largest waste first.
+pub fn find_duplicates(
+    conn: &Connection,
+    root: Option<&str>,
+    min_size: i64,
+    limit: usize,
+) -> Result<DuplicateReport, String> {
+    ensure_tables(conn)?;
+    let algorithm = content_hash::load_config(conn)?.algorithm;
+    let (root, lo, hi) = match root {
+        Some(r) => {
+            let (lo, hi) = crate::indexer::descendant_range(r);
+            (Some(r.to_string()), lo, hi)
+        }
+        None => (None, String::new(), String::new()),
+    };
+    let scope = "fi.file_type = 'file' AND fi.size >= ?1
+        AND (?2 IS NULL OR fi.path = ?2 OR (fi.path >= ?3 AND fi.path < ?4))";
+
+    // sizes shared by more than one file
+    let sizes: Vec<i64> = {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT fi.size FROM files_index fi WHERE {}
+                 GROUP BY fi.size HAVING COUNT(*) > 1 ORDER BY fi.size DESC",
+                scope
+            ))
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![min_size, root, lo, hi], |r| r.get(0))
+            .map_err(|e| e.to_string())?;
+        rows.filter_map(|r| r.ok()).collect()
+    };
+
+    let mut sets = Vec::new();
+    let mut candidates = 0usize;
+    let mut hashed_now = 0usize;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT fi.id, fi.path, fi.drive, fi.mtime, fi.ctime,
+                    (SELECT fh.hash FROM file_hashes fh
+                      WHERE fh.file_id = fi.id AND fh.status = 'ok' AND fh.algorithm = ?5
+                        AND fh.source_size IS fi.size AND fh.source_mtime IS fi.mtime)
+             FROM files_index fi WHERE {} AND fi.size = ?6 ORDER BY fi.id",
+            scope
+        ))
+        .map_err(|e| e.to_string())?;
+
+    for size in sizes {
+        let rows: Vec<(DuplicateCopy, Option<String>)> = stmt
+            .query_map(
+                params![min_size, root, lo, hi, algorithm.as_str(), size],
+                |r| {
+                    Ok((
+                        DuplicateCopy {
+                            file_id: r.get(0)?,
+                            path: r.get(1)?,
+                            drive: r.get(2)?,
+                            mtime: r.get(3)?,
+                            ctime: r.get(4)?,
+                        },
+                        r.get(5)?,
+                    ))
+                },
+            )
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        candidates += rows.len();
+
+        let mut by_hash: HashMap<String, Vec<DuplicateCopy>> = HashMap::new();
+        for (copy, stored) in rows {
+            let hash = match stored {
+                Some(h) => h,
+                None => {
+                    hashed_now += 1;
+                    match content_hash::hash_and_store(conn, copy.file_id, &copy.path, algorithm)? {
+                        Some(h) => h,
+                        None => continue,
+                    }
+                }
+            };
+            by_hash.entry(hash).or_default().push(copy);
+        }
+
+        for (hash, mut copies) in by_hash {
+            if copies.len() < 2 {
+                continue;
+            }
+            copies.sort_by(|a, b| {
+                (a.mtime.unwrap_or(i64::MAX), &a.path).cmp(&(b.mtime.unwrap_or(i64::MAX), &b.path))
+            });
+            sets.push(DuplicateSet {
+                algorithm: algorithm.as_str().to_string(),
+                hash,
+                size,
+                wasted_bytes: size * (copies.len() as i64 - 1),
+                oldest: copies[0].path.clone(),
+                copies,
+            });
+        }
+    }
+
+    sets.sort_by(|a, b| b.wasted_bytes.cmp(&a.wasted_bytes).then_with(|| a.hash.cmp(&b.hash)));
+    let set_count = sets.len();
+    let total_wasted_bytes = sets.iter().map(|s| s.wasted_bytes).sum();
+    sets.truncate(limit);
+    Ok(DuplicateReport {
+        sets,
+        set_count,
+        total_wasted_bytes,
+        candidates,
+        hashed_now,
+    })
+}
+
+/// Admin: duplicate sets among indexed files (optionally under `root`).
+#[command]
+pub fn admin_find_duplicates(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    root: Option<String>,
+    min_size: Option<i64>,
+    limit: Option<usize>,
+) -> Result<DuplicateReport, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    let root = root.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+    find_duplicates(
+        &conn,
+        root.as_deref(),
+        min_size.unwrap_or(DEFAULT_MIN_SIZE).max(0),
+        limit.unwrap_or(DEFAULT_SET_LIMIT).clamp(1, 10_000),
+    )
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResolveOutcome {
+    pub kept: String,
+    pub removed: Vec<String>,
+    /// (path, reason) for copies left in place.
+    pub skipped: Vec<(String, String)>,
+    pub bytes_freed: i64,
+}
+
+/// Why `copy` must not be deleted, if anything. Deleting a path that is the
+/// kept file under another name would delete the only copy.
+fn removal_blocker(keep: &str, copy: &str, exe: Option<&std::path::Path>) -> Option<String> {
+    if exe == Some(std::path::Path::new(copy)) {
+        return Some("running application binary".into());
+    }
+    match same_file::is_same_file(keep, copy) {
+        Ok(false) => None,
+        Ok(true) => Some("same file as the kept copy".into()),
+        Err(e) => Some(format!("compare with kept copy: {}", e)),
+    }
+}
+
+/// Admin: keep `keep_path` and delete the other indexed copies with the same
+/// content hash. Each copy is verified against the kept file's content before
+/// it is removed.
+#[command]
+pub fn admin_resolve_duplicates(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    hash: String,
+    keep_path: String,
+) -> Result<ResolveOutcome, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    ensure_tables(&conn)?;
+
+    let hash = hash.trim().to_lowercase();
+    if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("hash must be a 64-character hex digest".into());
+    }
+    let algorithm = content_hash::load_config(&conn)?.algorithm;
+    let copies = content_hash::files_with_hash(&conn, &hash, algorithm)?;
+    let keep = copies
+        .iter()
+        .find(|c| c.path == keep_path)
+        .ok_or_else(|| "keep_path is not a copy in this duplicate set".to_string())?;
+    if copies.len() < 2 {
+        return Err("no other copies to remove".into());
+    }
+    if content_hash::hash_file(&keep.path, algorithm)? != hash {
+        return Err("the copy to keep no longer matches; refresh the report".into());
+    }
+
+    let exe = std::env::current_exe().ok();
+    let now = chrono::Utc::now().timestamp();
+    let mut outcome = ResolveOutcome {
+        kept: keep.path.clone(),
+        removed: Vec::new(),
+        skipped: Vec::new(),
+        bytes_freed: 0,
+    };
+    for copy in copies.iter().filter(|c| c.path != keep.path) {
+        if let Some(reason) = removal_blocker(&keep.path, &copy.path, exe.as_deref()) {
+            outcome.skipped.push((copy.path.clone(), reason));
+            continue;
+        }
+        match content_hash::hash_file(&copy.path, algorithm) {
+            Ok(h) if h == hash => {}
+            Ok(_) => {
+                outcome
+                    .skipped
+                    .push((copy.path.clone(), "content changed since it was hashed".into()));
+                continue;
+            }
+            Err(e) => {
+                outcome.skipped.push((copy.path.clone(), e));
+                continue;
+            }
+        }
+        if let Err(e) = std::fs::remove_file(&copy.path) {
+            outcome
+                .skipped
+                .push((copy.path.clone(), format!("remove_file: {}", e)));
+            continue;
+        }
+        let _ = crate::indexer::remove_entry(&conn, &copy.path);
+
+        let audit = crate::db::insert_file_audit_log(
+            &conn,
+            Some(admin_id),
+            Some(&admin_username),
+            "fs_delete",
+            &copy.path,
+            None,
+            Some(&format!(
+                "duplicate of {} ({}:{})",
+                keep.path,
+                algorithm.as_str(),
+                hash
+            )),
+        );
+        let audit_id = match audit {
+            Ok(id) => Some(id),
+            Err(e) => {
+                eprintln!("[duplicates] audit of removed {} failed: {}", copy.path, e);
+                None
+            }
+        };
+        conn.execute(
+            "INSERT INTO duplicate_removals
+               (removed_path, kept_path, algorithm, hash, size, removed_by, removed_by_username, removed_at, audit_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                copy.path,
+                keep.path,
+                algorithm.as_str(),
+                hash,
+                copy.size.unwrap_or(0),
+                admin_id,
+                admin_username,
+                now,
+                audit_id
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+        outcome.bytes_freed += copy.size.unwrap_or(0);
+        outcome.removed.push(copy.path.clone());
+    }
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_resolve_duplicates",
+        None,
+        Some(&format!(
+            "kept={} removed={} skipped={} bytes_freed={} {}:{}",
+            outcome.kept,
+            outcome.removed.len(),
+            outcome.skipped.len(),
+            outcome.bytes_freed,
+            algorithm.as_str(),
+            hash
+        )),
+    );
+    Ok(outcome)
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateRemoval {
+    pub id: i64,
+    pub removed_path: String,
+    pub kept_path: String,
+    pub algorithm: String,
+    pub hash: String,
+    pub size: i64,
+    pub removed_by_username: Option<String>,
+    pub removed_at: i64,
+    pub audit_id: Option<i64>,
+}
+
+/// Admin: copies removed as duplicates, newest first.
+#[command]
+pub fn admin_list_duplicate_removals(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<DuplicateRemoval>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    ensure_tables(&conn)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, removed_path, kept_path, algorithm, hash, size, removed_by_username, removed_at, audit_id
+             FROM duplicate_removals ORDER BY removed_at DESC, id DESC LIMIT ?1",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![limit.unwrap_or(100).clamp(1, 5000)], |r| {
+            Ok(DuplicateRemoval {
+                id: r.get(0)?,
+                removed_path: r.get(1)?,
+                kept_path: r.get(2)?,
+                algorithm: r.get(3)?,
+                hash: r.get(4)?,
+                size: r.get(5)?,
+                removed_by_username: r.get(6)?,
+                removed_at: r.get(7)?,
+                audit_id: r.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir() -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("duplicates-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn paths_to_the_kept_file_are_never_removed() {
+        let dir = scratch_dir();
+        let keep = dir.join("keep.txt");
+        std::fs::write(&keep, "same bytes").unwrap();
+        let linked = dir.join("sub").join("linked.txt");
+        std::fs::hard_link(&keep, &linked).unwrap();
+        let respelt = dir.join("sub").join("..").join("keep.txt");
+        let keep = keep.to_string_lossy().to_string();
+
+        for copy in [&linked, &respelt] {
+            let reason = removal_blocker(&keep, &copy.to_string_lossy(), None);
+            assert_eq!(reason.as_deref(), Some("same file as the kept copy"), "{:?}", copy);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn separate_copies_and_the_app_binary() {
+        let dir = scratch_dir();
+        let keep = dir.join("keep.txt");
+        let copy = dir.join("sub").join("copy.txt");
+        std::fs::write(&keep, "same bytes").unwrap();
+        std::fs::write(&copy, "same bytes").unwrap();
+        let (keep, copy) = (keep.to_string_lossy(), copy.to_string_lossy());
+
+        assert_eq!(removal_blocker(&keep, &copy, None), None);
+        let exe = std::path::Path::new(copy.as_ref());
+        assert_eq!(
+            removal_blocker(&keep, &copy, Some(exe)).as_deref(),
+            Some("running application binary")
+        );
+        // a copy that vanished cannot be compared and is left alone
+        std::fs::remove_file(copy.as_ref()).unwrap();
+        assert!(removal_blocker(&keep, &copy, None).is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
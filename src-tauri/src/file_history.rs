@@ -0,0 +1,659 @@
+// src-tauri/src/file_history.rs
+//
+// Stable file identity for the audit trail ("who touched this document").
+// Every file action audited through `db::insert_file_audit_log` is attached to
+// a row in `file_identities`; moves and renames carry that identity to the new
+// path (directories carry their known descendants along), copies create a new
+// identity that remembers its origin, and deletes close it. Identities are
+// matched by normalized path first and by (st_dev, st_ino) on unix, so a file
+// moved outside the app is picked up again when it is next touched. The
+// watcher reports external renames/removes here as well.
+//
+// Rows written before structured targets existed only carry free-text
+// `details` and are not linked to an identity.
+use crate::audit::ensure_admin_session;
+use crate::db::AuditTarget;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use tauri::{command, AppHandle};
+
+#[derive(Debug, Serialize)]
+pub struct FileIdentity {
+    pub id: i64,
+    pub current_path: String,
+    pub kind: String,
+    pub origin_file_id: Option<i64>,
+    pub first_seen_at: i64,
+    pub last_seen_at: i64,
+    pub deleted_at: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PathSpan {
+    pub path: String,
+    pub valid_from: i64,
+    pub valid_to: Option<i64>,
+    pub audit_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileHistoryEvent {
+    pub audit_id: i64,
+    pub action: String,
+    pub actor_user_id: Option<i64>,
+    pub actor_username: Option<String>,
+    pub path: Option<String>,
+    pub dest_path: Option<String>,
+    pub details: Option<String>,
+    pub created_at: i64,
+    /// "subject" when this file was acted on, "copy_destination" when it was
+    /// created by copying another file, "container" when a parent folder was
+    /// moved or renamed.
+    pub role: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileHistory {
+    pub file: FileIdentity,
+    pub origin: Option<FileIdentity>,
+    pub copies: Vec<FileIdentity>,
+    pub paths: Vec<PathSpan>,
+    pub events: Vec<FileHistoryEvent>,
+}
+
+/// Display form of a path: Windows verbatim prefixes removed.
+fn display_path(p: &str) -> String {
+    let mut s = p.to_string();
+    if let Some(rest) = s.strip_prefix("\\\\?\\UNC\\") {
+        s = format!("\\\\{}", rest);
+    } else if let Some(rest) = s.strip_prefix("\\\\?\\") {
+        s = rest.to_string();
+    }
+    if s.len() > 1 {
+        let trimmed = s.trim_end_matches(['/', '\\']);
+        // keep "C:\" and "/" intact
+        if !trimmed.is_empty() && !trimmed.ends_with(':') {
+            s = trimmed.to_string();
+        }
+    }
+    s
+}
+
+/// Lookup key for a display path. ASCII-only case folding on Windows keeps
+/// byte offsets identical between key and path (used for prefix rewrites).
+pub(crate) fn path_key(p: &str) -> String {
+    let s = display_path(p).replace('\\', "/");
+    if cfg!(target_os = "windows") {
+        s.to_ascii_lowercase()
+    } else {
+        s
+    }
+}
+
+#[cfg(unix)]
+fn disk_id(path: &str) -> (Option<i64>, Option<i64>) {
+    use std::os::unix::fs::MetadataExt;
+    match std::fs::symlink_metadata(path) {
+        Ok(md) => (Some(md.dev() as i64), Some(md.ino() as i64)),
+        Err(_) => (None, None),
+    }
+}
+
+#[cfg(not(unix))]
+fn disk_id(_path: &str) -> (Option<i64>, Option<i64>) {
+    (None, None)
+}
+
+fn kind_of(path: &str) -> Option<&'static str> {
+    std::fs::symlink_metadata(path)
+        .ok()
+        .map(|md| if md.is_dir() { "dir" } else { "file" })
+}
+
+fn find_live_by_key(conn: &Connection, key: &str) -> Result<Option<i64>, String> {
+    conn.query_row(
+        "SELECT id FROM file_identities WHERE current_key = ?1 AND deleted_at IS NULL
+         ORDER BY id DESC LIMIT 1",
+        params![key],
+        |r| r.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+fn find_live_by_disk_id(conn: &Connection, path: &str) -> Result<Option<i64>, String> {
+    let (dev, ino) = match disk_id(path) {
+        (Some(d), Some(i)) => (d, i),
+        _ => return Ok(None),
+    };
+    conn.query_row(
+        "SELECT id FROM file_identities WHERE volume_dev = ?1 AND inode = ?2 AND deleted_at IS NULL
+         ORDER BY last_seen_at DESC LIMIT 1",
+        params![dev, ino],
+        |r| r.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+fn open_span(conn: &Connection, file_id: i64, path: &str, now: i64) -> Result<i64, String> {
+    conn.execute(
+        "INSERT INTO file_path_history (file_id, path, path_key, valid_from) VALUES (?1, ?2, ?3, ?4)",
+        params![file_id, display_path(path), path_key(path), now],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(conn.last_insert_rowid())
+}
+
+fn close_span(conn: &Connection, file_id: i64, now: i64) -> Result<(), String> {
+    conn.execute(
+        "UPDATE file_path_history SET valid_to = ?2 WHERE file_id = ?1 AND valid_to IS NULL",
+        params![file_id, now],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn create_identity(
+    conn: &Connection,
+    path: &str,
+    kind: &str,
+    origin: Option<i64>,
+    now: i64,
+    history: &mut Vec<i64>,
+) -> Result<i64, String> {
+    let (dev, ino) = disk_id(path);
+    conn.execute(
+        "INSERT INTO file_identities (current_path, current_key, kind, volume_dev, inode, origin_file_id, first_seen_at, last_seen_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+        params![display_path(path), path_key(path), kind, dev, ino, origin, now],
+    )
+    .map_err(|e| e.to_string())?;
+    let id = conn.last_insert_rowid();
+    history.push(open_span(conn, id, path, now)?);
+    Ok(id)
+}
+
+/// Identity of the file currently at `path`, creating one on first sight.
+/// When the path is new but the inode is known, the file was moved outside
+/// the app: the identity is carried to the new path.
+fn resolve_or_create(
+    conn: &Connection,
+    path: &str,
+    now: i64,
+    history: &mut Vec<i64>,
+) -> Result<i64, String> {
+    if let Some(id) = find_live_by_key(conn, &path_key(path))? {
+        touch(conn, id, now)?;
+        return Ok(id);
+    }
+    if let Some(id) = find_live_by_disk_id(conn, path)? {
+        relocate(conn, id, path, now, history)?;
+        return Ok(id);
+    }
+    create_identity(conn, path, kind_of(path).unwrap_or("file"), None, now, history)
+}
+
+fn touch(conn: &Connection, id: i64, now: i64) -> Result<(), String> {
+    conn.execute(
+        "UPDATE file_identities SET last_seen_at = ?2 WHERE id = ?1",
+        params![id, now],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Point identity `id` (and any tracked descendants) at `new_path`.
+fn relocate(
+    conn: &Connection,
+    id: i64,
+    new_path: &str,
+    now: i64,
+    history: &mut Vec<i64>,
+) -> Result<(), String> {
+    let (old_key, kind): (String, String) = conn
+        .query_row(
+            "SELECT current_key, kind FROM file_identities WHERE id = ?1",
+            params![id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    let new_key = path_key(new_path);
+    if old_key == new_key {
+        return touch(conn, id, now);
+    }
+
+    let (dev, ino) = disk_id(new_path);
+    conn.execute(
+        "UPDATE file_identities
+         SET current_path = ?2, current_key = ?3, last_seen_at = ?4,
+             volume_dev = COALESCE(?5, volume_dev), inode = COALESCE(?6, inode)
+         WHERE id = ?1",
+        params![id, display_path(new_path), new_key, now, dev, ino],
+    )
+    .map_err(|e| e.to_string())?;
+    close_span(conn, id, now)?;
+    history.push(open_span(conn, id, new_path, now)?);
+
+    if kind == "dir" {
+        let prefix = format!("{}/", old_key);
+        let new_display = display_path(new_path);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, current_key, current_path FROM file_identities
+                 WHERE deleted_at IS NULL AND substr(current_key, 1, length(?1)) = ?1",
+            )
+            .map_err(|e| e.to_string())?;
+        let children: Vec<(i64, String, String)> = stmt
+            .query_map(params![prefix], |r| {
+                Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+            })
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        let sep = if new_display.contains('\\') { "\\" } else { "/" };
+        for (child_id, child_key, child_path) in children {
+            // key and display path have equal byte lengths (see path_key)
+            let suffix = child_path
+                .get(prefix.len()..)
+                .unwrap_or(&child_key[prefix.len()..])
+                .replace(['/', '\\'], sep);
+            let child_path = format!("{}{}{}", new_display, sep, suffix);
+            conn.execute(
+                "UPDATE file_identities SET current_path = ?2, current_key = ?3, last_seen_at = ?4 WHERE id = ?1",
+                params![child_id, child_path, format!("{}{}", new_key, &child_key[old_key.len()..]), now],
+            )
+            .map_err(|e| e.to_string())?;
+            close_span(conn, child_id, now)?;
+            history.push(open_span(conn, child_id, &child_path, now)?);
+        }
+    }
+    Ok(())
+}
+
+fn mark_deleted(conn: &Connection, id: i64, now: i64) -> Result<(), String> {
+    let key: String = conn
+        .query_row(
+            "SELECT current_key FROM file_identities WHERE id = ?1",
+            params![id],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    let prefix = format!("{}/", key);
+    conn.execute(
+        "UPDATE file_identities SET deleted_at = ?3, last_seen_at = ?3
+         WHERE deleted_at IS NULL AND (id = ?1 OR substr(current_key, 1, length(?2)) = ?2)",
+        params![id, prefix, now],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE file_path_history SET valid_to = ?1
+         WHERE valid_to IS NULL AND file_id IN (SELECT id FROM file_identities WHERE deleted_at = ?1)",
+        params![now],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Resolve identities for an audited file action and apply its effect
+/// (relocate / copy / delete). Returns the structured audit target and the
+/// path-history rows that should point at the audit row.
+pub fn track(
+    conn: &Connection,
+    action: &str,
+    path: &str,
+    dest_path: Option<&str>,
+) -> Result<(AuditTarget, Vec<i64>), String> {
+    let now = Utc::now().timestamp();
+    let mut history = Vec::new();
+    let mut target = AuditTarget {
+        target_type: None,
+        path: Some(display_path(path)),
+        dest_path: dest_path.map(display_path),
+        file_id: None,
+        dest_file_id: None,
+    };
+
+    match (action, dest_path) {
+        ("fs_move" | "fs_rename", Some(dest)) => {
+            // the source path is gone by now; fall back to the destination
+            // (watcher may have relocated it first) and then to the inode
+            let id = match find_live_by_key(conn, &path_key(path))? {
+                Some(id) => id,
+                None => match find_live_by_key(conn, &path_key(dest))? {
+                    Some(id) => id,
+                    None => match find_live_by_disk_id(conn, dest)? {
+                        Some(id) => id,
+                        None => create_identity(
+                            conn,
+                            path,
+                            kind_of(dest).unwrap_or("file"),
+                            None,
+                            now,
+                            &mut history,
+                        )?,
+                    },
+                },
+            };
+            relocate(conn, id, dest, now, &mut history)?;
+            target.file_id = Some(id);
+            target.target_type = kind_of(dest).map(|k| k.to_string());
+        }
+        ("fs_copy", Some(dest)) => {
+            let src = resolve_or_create(conn, path, now, &mut history)?;
+            let kind = kind_of(dest).unwrap_or("file");
+            let copy = match find_live_by_key(conn, &path_key(dest))? {
+                Some(id) => id,
+                None => create_identity(conn, dest, kind, Some(src), now, &mut history)?,
+            };
+            target.file_id = Some(src);
+            target.dest_file_id = Some(copy);
+            target.target_type = Some(kind.to_string());
+        }
+        ("fs_delete", _) => {
+            let id = match find_live_by_key(conn, &path_key(path))? {
+                Some(id) => id,
+                None => create_identity(conn, path, "file", None, now, &mut history)?,
+            };
+            let kind: String = conn
+                .query_row(
+                    "SELECT kind FROM file_identities WHERE id = ?1",
+                    params![id],
+                    |r| r.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            mark_deleted(conn, id, now)?;
+            target.file_id = Some(id);
+            target.target_type = Some(kind);
+        }
+        _ => {
+            let id = resolve_or_create(conn, path, now, &mut history)?;
+            target.file_id = Some(id);
+            target.target_type = kind_of(path).map(|k| k.to_string());
+        }
+    }
+    if target.target_type.is_none() {
+        target.target_type = Some("file".into());
+    }
+    Ok((target, history))
+}
+
+pub fn link_history_to_audit(conn: &Connection, history_ids: &[i64], audit_id: i64) {
+    for h in history_ids {
+        let _ = conn.execute(
+            "UPDATE file_path_history SET audit_id = ?2 WHERE id = ?1",
+            params![h, audit_id],
+        );
+    }
+}
+
+/// External rename seen by the watcher (no audit row).
+pub fn watcher_renamed(conn: &Connection, old_path: &str, new_path: &str) -> Result<(), String> {
+    if let Some(id) = find_live_by_key(conn, &path_key(old_path))? {
+        let mut history = Vec::new();
+        relocate(conn, id, new_path, Utc::now().timestamp(), &mut history)?;
+    }
+    Ok(())
+}
+
+/// A volume re-appeared at `new_root` (see volumes.rs): point the live
+/// identities tracked under `old_root` at the same paths below the new root.
+pub fn volume_rebased(conn: &Connection, old_root: &str, new_root: &str) -> Result<usize, String> {
+    let old_key = path_key(old_root);
+    let prefix = if old_key.ends_with('/') {
+        old_key.clone()
+    } else {
+        format!("{}/", old_key)
+    };
+    let new_display = display_path(new_root);
+    let sep = if new_display.contains('\\') { "\\" } else { "/" };
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, current_key, current_path FROM file_identities
+             WHERE deleted_at IS NULL
+               AND (current_key = ?1 OR substr(current_key, 1, length(?2)) = ?2)",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<(i64, String, String)> = stmt
+        .query_map(params![old_key, prefix], |r| {
+            Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    let now = Utc::now().timestamp();
+    for (id, key, path) in &rows {
+        let new_path = if key == &old_key {
+            new_display.clone()
+        } else {
+            // key and display path have equal byte lengths (see path_key)
+            let suffix = path
+                .get(prefix.len()..)
+                .unwrap_or(&key[prefix.len()..])
+                .replace(['/', '\\'], sep);
+            if new_display.ends_with(sep) {
+                format!("{}{}", new_display, suffix)
+            } else {
+                format!("{}{}{}", new_display, sep, suffix)
+            }
+        };
+        conn.execute(
+            "UPDATE file_identities SET current_path = ?2, current_key = ?3, last_seen_at = ?4 WHERE id = ?1",
+            params![id, new_path, path_key(&new_path), now],
+        )
+        .map_err(|e| e.to_string())?;
+        close_span(conn, *id, now)?;
+        open_span(conn, *id, &new_path, now)?;
+    }
+    Ok(rows.len())
+}
+
+/// External delete seen by the watcher (no audit row).
+pub fn watcher_removed(conn: &Connection, path: &str) -> Result<(), String> {
+    if let Some(id) = find_live_by_key(conn, &path_key(path))? {
+        mark_deleted(conn, id, Utc::now().timestamp())?;
+    }
+    Ok(())
+}
+
+fn load_identity(conn: &Connection, id: i64) -> Result<Option<FileIdentity>, String> {
+    conn.query_row(
+        "SELECT id, current_path, kind, origin_file_id, first_seen_at, last_seen_at, deleted_at
+         FROM file_identities WHERE id = ?1",
+        params![id],
+        |r| {
+            Ok(FileIdentity {
+                id: r.get(0)?,
+                current_path: r.get(1)?,
+                kind: r.get(2)?,
+                origin_file_id: r.get(3)?,
+                first_seen_at: r.get(4)?,
+                last_seen_at: r.get(5)?,
+                deleted_at: r.get(6)?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Identity for a path as the user knows it: the live file at that path, else
+/// whichever identity most recently lived there (renamed away or deleted).
+fn identity_for_path(conn: &Connection, path: &str) -> Result<Option<i64>, String> {
+    let key = path_key(path);
+    if let Some(id) = find_live_by_key(conn, &key)? {
+        return Ok(Some(id));
+    }
+    conn.query_row(
+        "SELECT file_id FROM file_path_history WHERE path_key = ?1 ORDER BY valid_from DESC, id DESC LIMIT 1",
+        params![key],
+        |r| r.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+pub fn file_history(conn: &Connection, file_id: i64) -> Result<FileHistory, String> {
+    let file = load_identity(conn, file_id)?.ok_or("Unknown file".to_string())?;
+    let origin = match file.origin_file_id {
+        Some(o) => load_identity(conn, o)?,
+        None => None,
+    };
+
+    let mut stmt = conn
+        .prepare("SELECT id FROM file_identities WHERE origin_file_id = ?1 ORDER BY id ASC")
+        .map_err(|e| e.to_string())?;
+    let copy_ids: Vec<i64> = stmt
+        .query_map(params![file_id], |r| r.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    let mut copies = Vec::new();
+    for c in copy_ids {
+        if let Some(i) = load_identity(conn, c)? {
+            copies.push(i);
+        }
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT path, valid_from, valid_to, audit_id FROM file_path_history
+             WHERE file_id = ?1 ORDER BY valid_from ASC, id ASC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![file_id], |r| {
+            Ok(PathSpan {
+                path: r.get(0)?,
+                valid_from: r.get(1)?,
+                valid_to: r.get(2)?,
+                audit_id: r.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    let mut paths = Vec::new();
+    for r in rows {
+        paths.push(r.map_err(|e| e.to_string())?);
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, action, actor_user_id, actor_username, target_path, target_dest_path, details, created_at,
+                    CASE WHEN target_file_id = ?1 THEN 'subject'
+                         WHEN target_dest_file_id = ?1 THEN 'copy_destination'
+                         ELSE 'container' END
+             FROM audit_logs
+             WHERE target_file_id = ?1 OR target_dest_file_id = ?1
+                OR id IN (SELECT audit_id FROM file_path_history WHERE file_id = ?1 AND audit_id IS NOT NULL)
+             ORDER BY id ASC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![file_id], |r| {
+            Ok(FileHistoryEvent {
+                audit_id: r.get(0)?,
+                action: r.get(1)?,
+                actor_user_id: r.get(2)?,
+                actor_username: r.get(3)?,
+                path: r.get(4)?,
+                dest_path: r.get(5)?,
+                details: r.get(6)?,
+                created_at: r.get(7)?,
+                role: r.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    let mut events = Vec::new();
+    for r in rows {
+        events.push(r.map_err(|e| e.to_string())?);
+    }
+
+    Ok(FileHistory {
+        file,
+        origin,
+        copies,
+        paths,
+        events,
+    })
+}
+
+/// Every audited action on a file across its renames and moves. Look up by
+/// `file_id`, or by any `path` the file has had.
+#[command]
+pub fn admin_file_history(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    path: Option<String>,
+    file_id: Option<i64>,
+) -> Result<FileHistory, String> {
+    let conn = crate::db::open_connection()?;
+    crate::db::init_db_schema(&conn)?;
+    let (admin_id, admin_username) = ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let id = match (file_id, path.as_deref()) {
+        (Some(id), _) => id,
+        (None, Some(p)) => {
+            identity_for_path(&conn, p)?.ok_or("No audited history for this path".to_string())?
+        }
+        (None, None) => return Err("path or file_id required".into()),
+    };
+    let history = file_history(&conn, id)?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_file_history",
+        None,
+        Some(&format!(
+            "File history for #{} {} ({} events)",
+            id,
+            history.file.current_path,
+            history.events.len()
+        )),
+    );
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn current_path(conn: &Connection, id: i64) -> String {
+        conn.query_row(
+            "SELECT current_path FROM file_identities WHERE id = ?1",
+            params![id],
+            |r| r.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn non_ascii_directories_carry_their_children() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::init_db_schema(&conn).unwrap();
+        let base = std::env::temp_dir().join(format!("file-history-{}", uuid::Uuid::new_v4()));
+        let old_dir = base.join("दस्तावेज़");
+        let new_dir = base.join("अभिलेख");
+        std::fs::create_dir_all(&old_dir).unwrap();
+        std::fs::write(old_dir.join("नोट.txt"), b"x").unwrap();
+        let old_dir = old_dir.to_string_lossy().to_string();
+        let new_dir = new_dir.to_string_lossy().to_string();
+
+        track(&conn, "open_path", &old_dir, None).unwrap();
+        let (child, _) = track(&conn, "open_file", &format!("{}/नोट.txt", old_dir), None).unwrap();
+        let child = child.file_id.unwrap();
+
+        std::fs::rename(&old_dir, &new_dir).unwrap();
+        track(&conn, "fs_rename", &old_dir, Some(&new_dir)).unwrap();
+        assert_eq!(current_path(&conn, child), format!("{}/नोट.txt", new_dir));
+
+        let moved = base.join("नया").to_string_lossy().to_string();
+        volume_rebased(&conn, &new_dir, &moved).unwrap();
+        assert_eq!(current_path(&conn, child), format!("{}/नोट.txt", moved));
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
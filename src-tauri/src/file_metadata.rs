@@ -0,0 +1,226 @@
+// src-tauri/src/file_metadata.rs
+//
+// Embedded document properties of indexed files (title, author, dates, page
+// and slide counts, EXIF camera/capture time/GPS), one row per files_index
+// row. Filled by the content pass (content_index.rs) from what
+// content_extract reads alongside the text; the text fields are also copied
+// into the `meta` column of files_index_fts so plain searches match them.
+use crate::content_extract::DocMetadata;
+use rusqlite::types::Value;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Deserialize;
+
+pub(crate) fn ensure_tables(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS file_metadata (
+            file_id INTEGER PRIMARY KEY,
+            title TEXT,
+            author TEXT,
+            subject TEXT,
+            keywords TEXT,
+            creator_tool TEXT,
+            producer TEXT,
+            last_modified_by TEXT,
+            revision TEXT,
+            created_at INTEGER,
+            modified_at INTEGER,
+            page_count INTEGER,
+            slide_count INTEGER,
+            word_count INTEGER,
+            camera_make TEXT,
+            camera_model TEXT,
+            taken_at INTEGER,
+            gps_lat REAL,
+            gps_lon REAL,
+            extracted_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_file_metadata_created ON file_metadata(created_at);
+        CREATE INDEX IF NOT EXISTS idx_file_metadata_taken ON file_metadata(taken_at);",
+    )
+    .map_err(|e| format!("ensure file_metadata: {}", e))
+}
+
+/// Replace the metadata of `file_id` (dropping the row when nothing was found).
+pub(crate) fn store(conn: &Connection, file_id: i64, m: &DocMetadata) -> Result<(), String> {
+    conn.execute(
+        "UPDATE files_index_fts SET meta = ?1 WHERE rowid = ?2",
+        params![m.search_text(), file_id],
+    )
+    .map_err(|e| e.to_string())?;
+    if m.is_empty() {
+        conn.execute(
+            "DELETE FROM file_metadata WHERE file_id = ?1",
+            params![file_id],
+        )
+        .map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO file_metadata (
+            file_id, title, author, subject, keywords, creator_tool, producer,
+            last_modified_by, revision, created_at, modified_at, page_count,
+            slide_count, word_count, camera_make, camera_model, taken_at,
+            gps_lat, gps_lon, extracted_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+                 ?18, ?19, ?20)",
+        params![
+            file_id,
+            m.title,
+            m.author,
+            m.subject,
+            m.keywords,
+            m.creator_tool,
+            m.producer,
+            m.last_modified_by,
+            m.revision,
+            m.created_at,
+            m.modified_at,
+            m.page_count,
+            m.slide_count,
+            m.word_count,
+            m.camera_make,
+            m.camera_model,
+            m.taken_at,
+            m.gps_lat,
+            m.gps_lon,
+            chrono::Utc::now().timestamp()
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Metadata of one indexed file, if any was extracted.
+pub(crate) fn load(conn: &Connection, file_id: i64) -> Result<Option<DocMetadata>, String> {
+    conn.query_row(
+        "SELECT title, author, subject, keywords, creator_tool, producer,
+                last_modified_by, revision, created_at, modified_at, page_count,
+                slide_count, word_count, camera_make, camera_model, taken_at,
+                gps_lat, gps_lon
+         FROM file_metadata WHERE file_id = ?1",
+        params![file_id],
+        |r| {
+            Ok(DocMetadata {
+                title: r.get(0)?,
+                author: r.get(1)?,
+                subject: r.get(2)?,
+                keywords: r.get(3)?,
+                creator_tool: r.get(4)?,
+                producer: r.get(5)?,
+                last_modified_by: r.get(6)?,
+                revision: r.get(7)?,
+                created_at: r.get(8)?,
+                modified_at: r.get(9)?,
+                page_count: r.get(10)?,
+                slide_count: r.get(11)?,
+                word_count: r.get(12)?,
+                camera_make: r.get(13)?,
+                camera_model: r.get(14)?,
+                taken_at: r.get(15)?,
+                gps_lat: r.get(16)?,
+                gps_lon: r.get(17)?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Metadata conditions for `search_files`. Text fields match substrings
+/// (case-insensitive); dates are unix seconds; pages count slides for
+/// presentations.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct MetadataFilter {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub keywords: Option<String>,
+    /// Camera make or model.
+    pub camera: Option<String>,
+    pub created_after: Option<i64>,
+    pub created_before: Option<i64>,
+    pub taken_after: Option<i64>,
+    pub taken_before: Option<i64>,
+    pub min_pages: Option<i64>,
+    pub max_pages: Option<i64>,
+    pub has_gps: Option<bool>,
+}
+
+fn like_pattern(s: &str) -> String {
+    let escaped = s
+        .trim()
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+impl MetadataFilter {
+    /// SQL conditions on `file_metadata fm`, binding values by number after
+    /// the ones already in `values`. Empty when no field is set.
+    pub(crate) fn clauses(&self, values: &mut Vec<Value>) -> Vec<String> {
+        let mut out = Vec::new();
+        let mut bind = |values: &mut Vec<Value>, v: Value| {
+            values.push(v);
+            format!("?{}", values.len())
+        };
+        let text_fields: [(&Option<String>, &[&str]); 4] = [
+            (&self.title, &["fm.title"]),
+            (&self.author, &["fm.author", "fm.last_modified_by"]),
+            (&self.keywords, &["fm.keywords", "fm.subject"]),
+            (&self.camera, &["fm.camera_make", "fm.camera_model"]),
+        ];
+        for (value, columns) in text_fields {
+            let value = match value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+                Some(v) => v,
+                None => continue,
+            };
+            let p = bind(values, Value::Text(like_pattern(value)));
+            let any = columns
+                .iter()
+                .map(|c| format!("{} LIKE {} ESCAPE '\\'", c, p))
+                .collect::<Vec<_>>()
+                .join(" OR ");
+            out.push(format!("({})", any));
+        }
+        let ranges = [
+            ("fm.created_at >=", self.created_after),
+            ("fm.created_at <", self.created_before),
+            ("fm.taken_at >=", self.taken_after),
+            ("fm.taken_at <", self.taken_before),
+            ("COALESCE(fm.page_count, fm.slide_count) >=", self.min_pages),
+            ("COALESCE(fm.page_count, fm.slide_count) <=", self.max_pages),
+        ];
+        for (cond, value) in ranges {
+            if let Some(v) = value {
+                let p = bind(values, Value::Integer(v));
+                out.push(format!("{} {}", cond, p));
+            }
+        }
+        match self.has_gps {
+            Some(true) => out.push("fm.gps_lat IS NOT NULL".to_string()),
+            Some(false) => out.push("fm.gps_lat IS NULL".to_string()),
+            None => {}
+        }
+        out
+    }
+}
+
+/// Extracted metadata of one indexed file (None when it has none, is not
+/// indexed or is not accessible to the caller).
+#[tauri::command]
+pub fn get_file_metadata(
+    session_token: String,
+    path: String,
+) -> Result<Option<DocMetadata>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("db open: {}", e))?;
+    let uid = crate::session::validate_session(&conn, &session_token)
+        .map_err(|e| format!("session validation: {}", e))?
+        .ok_or_else(|| "invalid session".to_string())?;
+    crate::fs_ops::ensure_files_index_tables(&conn)?;
+
+    // rows of restricted locations are admin-only, as in search results
+    match crate::fs_ops::visible_file_id(&conn, uid, &path)? {
+        Some(id) => load(&conn, id),
+        None => Ok(None),
+    }
+}
@@ -1,14 +1,12 @@
 // src-tauri/src/fs_ops.rs
 use chrono::Utc;
-use once_cell::sync::Lazy;
 use rusqlite::params; // params! macro
 use rusqlite::Connection;
+use rusqlite::OptionalExtension;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::fs::OpenOptions;
 use std::os::windows::prelude::OpenOptionsExt;
 use std::process::Command;
-use std::sync::Mutex;
 use std::time::UNIX_EPOCH;
 use std::{
     fs, io,
@@ -18,7 +16,6 @@ use std::{
 use sysinfo::{DiskExt, System, SystemExt};
 use tauri::command;
 use tauri::AppHandle;
-use uuid::Uuid;
 
 #[derive(Serialize)]
 pub struct DriveInfo {
@@ -38,6 +35,20 @@ pub struct FileEntry {
     pub size: Option<i64>,
     pub modified: Option<i64>, // epoch seconds
     pub path: String,
+    /// Matching excerpt of the document text (content search only); hits are
+    /// wrapped in U+E000 … U+E001.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+    /// Registered storage location holding the file (search results only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Embedded document title and author (search results only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Indexed on a location or volume that is not reachable right now.
+    pub unavailable: bool,
 }
 #[derive(Debug, Serialize)]
 pub struct FsItem {
@@ -55,7 +66,11 @@ fn format_system_time(st: SystemTime) -> Option<String> {
 }
 
 /// Normalize drive string for storing in `files_index.drive`.
+/// Paths on a known volume use its stable key (see volumes.rs).
 pub(crate) fn normalize_drive_for_storage(path: &str) -> String {
+    if let Some(loc) = crate::volumes::locate(path) {
+        return loc.key;
+    }
     #[cfg(target_os = "windows")]
     {
         // Ensure we handle Windows long-path: prefix is "\\?\"
@@ -101,7 +116,7 @@ pub(crate) fn normalize_drive_for_storage(path: &str) -> String {
     }
 }
 
-fn is_excluded_path(path: &str) -> bool {
+pub(crate) fn is_excluded_path(path: &str) -> bool {
     // Normalize to lowercase for comparisons
     let lower = path.to_lowercase();
 
@@ -248,174 +263,9 @@ pub fn index_all_drives_start(
         return Err("no drives or home directory found to index".into());
     }
 
-    // create job id and set initial state
-    let job_id = Uuid::new_v4().to_string();
-    {
-        let mut m = INDEX_JOBS
-            .lock()
-            .map_err(|_| "job mutex poisoned".to_string())?;
-        m.insert(
-            job_id.clone(),
-            IndexJobState::Running {
-                processed: 0,
-                last_path: None,
-            },
-        );
-    }
-
-    // clone values for thread
-    let job_clone = job_id.clone();
-    let roots_for_indexer = roots.clone();
-    let roots_for_watcher = roots.clone();
-
-    fn fail_job(job_id: &str, message: String) {
-        if let Ok(mut m) = INDEX_JOBS.lock() {
-            m.insert(job_id.to_string(), IndexJobState::Failed { message });
-        }
-    }
-
-    // Spawn background thread (uses its own DB connection)
-    // Spawn background thread (uses its own DB connection)
-    std::thread::spawn(move || {
-        let conn2 = match crate::db::open_connection() {
-            Ok(c) => c,
-            Err(e) => {
-                fail_job(&job_clone, format!("worker db open failed: {}", e));
-                return;
-            }
-        };
-
-        if let Err(e) = ensure_files_index_tables(&conn2) {
-            fail_job(&job_clone, format!("ensure tables failed: {}", e));
-            return;
-        }
-
-        let mut processed: usize = 0;
-        let now = Utc::now().timestamp();
-        let mut stack: Vec<PathBuf> = roots_for_indexer;
-
-        while let Some(p) = stack.pop() {
-            // update last_path
-            {
-                if let Ok(mut m) = INDEX_JOBS.lock() {
-                    m.insert(
-                        job_clone.clone(),
-                        IndexJobState::Running {
-                            processed,
-                            last_path: Some(p.to_string_lossy().to_string()),
-                        },
-                    );
-                }
-            }
-
-            let read_dir = match std::fs::read_dir(&p) {
-                Ok(r) => r,
-                Err(e) => {
-                    // Non-fatal but logged
-                    eprintln!("[index] read_dir failed at {}: {}", p.display(), e);
-                    continue;
-                }
-            };
-
-            for entry_res in read_dir {
-                let entry = match entry_res {
-                    Ok(e) => e,
-                    Err(e) => {
-                        eprintln!("[index] dir entry read error in {}: {}", p.display(), e);
-                        continue;
-                    }
-                };
-
-                let path_buf = entry.path();
-
-                let meta = match entry.metadata() {
-                    Ok(m) => m,
-                    Err(e) => {
-                        eprintln!("[index] metadata failed for {}: {}", path_buf.display(), e);
-                        continue;
-                    }
-                };
-
-                let is_dir = meta.is_dir();
-                let size_opt = if is_dir {
-                    None
-                } else {
-                    Some(meta.len() as i64)
-                };
-
-                // ⚠️ Canonicalize with error visibility
-                let canonical_buf = match std::fs::canonicalize(&path_buf) {
-                    Ok(c) => c,
-                    Err(e) => {
-                        eprintln!(
-                            "[index] canonicalize failed for {}: {}",
-                            path_buf.display(),
-                            e
-                        );
-                        path_buf.clone()
-                    }
-                };
-
-                let path_str = canonical_buf.to_string_lossy().to_string();
-                let name = entry.file_name().to_string_lossy().to_string();
-                let file_type = if is_dir { "dir" } else { "file" }.to_string();
-                let doc_type = doc_type_for_path(&path_str, is_dir);
-                let drive_val = normalize_drive_for_storage(&path_str);
-
-                if let Err(e) = conn2.execute(
-                "INSERT INTO files_index (path, name, file_type, doc_type, size, indexed_at, drive)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-                 ON CONFLICT(path)
-                 DO UPDATE SET
-                   name=excluded.name,
-                   file_type=excluded.file_type,
-                   doc_type=excluded.doc_type,
-                   size=excluded.size,
-                   indexed_at=excluded.indexed_at,
-                   drive=excluded.drive",
-                params![path_str, name, file_type, doc_type, size_opt, now, drive_val],
-            ) {
-                // ⚠️ DB error = fatal (surface it)
-                fail_job(
-                    &job_clone,
-                    format!("db insert failed at {}: {}", canonical_buf.display(), e),
-                );
-                return;
-            }
-
-                processed += 1;
-
-                if is_dir && !is_excluded_path(&path_str) {
-                    stack.push(canonical_buf);
-                }
-            }
-        }
-
-        // mark finished
-        if let Ok(mut m) = INDEX_JOBS.lock() {
-            m.insert(job_clone.clone(), IndexJobState::Finished { processed });
-        }
-
-        // START FS WATCHER AFTER INDEXING
-        let db_path = match crate::db::get_db_path() {
-            Ok(p) => p,
-            Err(e) => {
-                eprintln!("[watcher] db path error: {}", e);
-                return;
-            }
-        };
-
-        let app_for_watcher = app.clone();
-        let roots_for_watcher = roots_for_watcher.clone();
-
-        std::thread::spawn(move || {
-            if let Err(e) =
-                crate::fs_watch::start_fs_watcher(roots_for_watcher, db_path, app_for_watcher)
-            {
-                eprintln!("[fs_watch] error: {}", e);
-            }
-        });
-    });
+    // persistent job; the runner checkpoints so a restart can resume it
+    let job_id = crate::index_jobs::create(&conn, "all_drives", &roots, Some(uid), &user.username)?;
+    crate::index_jobs::spawn_runner(job_id.clone(), Some(app));
 
     // audit (outer conn)
     let _ = crate::db::insert_audit_log(
@@ -430,10 +280,18 @@ pub fn index_all_drives_start(
     Ok(job_id)
 }
 
-/// Remove indexed files that no longer exist on disk
+/// Remove indexed files that no longer exist on disk. Rows on volumes that
+/// are not mounted, or on a storage location that cannot be reached, are
+/// kept until it comes back.
 pub fn reconcile_missing_files(conn: &rusqlite::Connection) -> Result<u64, String> {
+    crate::volumes::refresh(conn)?;
+    crate::locations::probe(conn)?;
     let mut stmt = conn
-        .prepare("SELECT path FROM files_index")
+        .prepare(&format!(
+            "SELECT path FROM files_index
+             WHERE volume_id IS NULL OR volume_id NOT IN ({})",
+            crate::locations::UNAVAILABLE_VOLUME_IDS
+        ))
         .map_err(|e| e.to_string())?;
 
     let paths = stmt
@@ -444,6 +302,10 @@ pub fn reconcile_missing_files(conn: &rusqlite::Connection) -> Result<u64, Strin
 
     for p in paths {
         let path = p.map_err(|e| e.to_string())?;
+        // archive members go with their archive (archive_index.rs)
+        if crate::archive_index::split_member_path(&path).is_some() {
+            continue;
+        }
         if !std::path::Path::new(&path).exists() {
             conn.execute(
                 "DELETE FROM files_index WHERE path = ?1",
@@ -589,11 +451,12 @@ pub fn open_path_by_session(
     }
 
     // Audit
-    let _ = crate::db::insert_audit_log(
+    let _ = crate::db::insert_file_audit_log(
         &conn,
         Some(uid),
         actor_username.as_deref(),
         "open_path",
+        &canonical.to_string_lossy(),
         None,
         Some(&path),
     );
@@ -721,6 +584,13 @@ pub fn open_file_by_session(session_token: String, path: String) -> Result<(), S
         .map_err(|e| format!("session validation error: {}", e))?;
     let uid = maybe_uid.ok_or_else(|| "invalid or expired session".to_string())?;
 
+    // Search results inside archives (archive_index.rs) have no file of their own
+    if !std::path::Path::new(&path).exists()
+        && crate::archive_index::split_member_path(&path).is_some()
+    {
+        return crate::archive_index::open_member(&conn, uid, &path);
+    }
+
     // Canonicalize path
     let canonical = std::fs::canonicalize(&path)
         .map_err(|e| format!("cannot canonicalize path '{}' : {}", path, e))?;
@@ -739,41 +609,52 @@ pub fn open_file_by_session(session_token: String, path: String) -> Result<(), S
         return Err("path is not a file".into());
     }
 
+    // files of restricted locations are admin-only, as in search results
+    ensure_files_index_tables(&conn)?;
+    if location_hidden(&conn, uid, &canonical.to_string_lossy())? {
+        return Err("path is not accessible".into());
+    }
+
     // Resolve actor username for audit (non-fatal)
     let actor_username_opt = match crate::db::get_user_by_id_row(&conn, uid) {
         Ok(Some(urow)) => Some(urow.username),
         _ => None,
     };
 
-    // Insert audit log using your db.rs signature:
-    // insert_audit_log(conn, actor_user_id, actor_username, action, target_user_id, details)
-    let _ = crate::db::insert_audit_log(
+    // Insert audit log with the file as structured target:
+    // insert_file_audit_log(conn, actor_user_id, actor_username, action, path, dest_path, details)
+    let _ = crate::db::insert_file_audit_log(
         &conn,
         Some(uid),
         actor_username_opt.as_deref(),
         "open_file",
-        None, // target_user_id (not relevant here)
+        &canonical.to_string_lossy(),
+        None,
         Some(&canonical.to_string_lossy()),
     );
 
-    // Spawn platform-specific opener in a background thread (non-blocking)
-    let canonical_for_thread = canonical.to_string_lossy().to_string();
+    open_with_default_app(&canonical.to_string_lossy());
+
+    Ok(())
+}
+
+/// Spawn the platform-specific opener in a background thread (non-blocking).
+pub(crate) fn open_with_default_app(path: &str) {
+    let path_for_thread = path.to_string();
     std::thread::spawn(move || {
         #[cfg(target_os = "windows")]
         {
-            let _ = Command::new("explorer").arg(&canonical_for_thread).spawn();
+            let _ = Command::new("explorer").arg(&path_for_thread).spawn();
         }
         #[cfg(target_os = "macos")]
         {
-            let _ = Command::new("open").arg(&canonical_for_thread).spawn();
+            let _ = Command::new("open").arg(&path_for_thread).spawn();
         }
         #[cfg(all(unix, not(target_os = "macos")))]
         {
-            let _ = Command::new("xdg-open").arg(&canonical_for_thread).spawn();
+            let _ = Command::new("xdg-open").arg(&path_for_thread).spawn();
         }
     });
-
-    Ok(())
 }
 
 /// Read directory entries for `path`. Returns normalized list of FileEntry.
@@ -925,6 +806,11 @@ pub fn read_dir(session_token: Option<String>, path: String) -> Result<Vec<FileE
             size: size_opt,
             modified: modified_opt,
             path: path_full,
+            snippet: None,
+            location: None,
+            title: None,
+            author: None,
+            unavailable: false,
         });
     }
 
@@ -962,79 +848,20 @@ pub fn index_path(session_token: String, root_path: String) -> Result<usize, Str
         .map_err(|e| format!("cannot canonicalize root: {}", e))?;
 
     // Prepare DB: create tables if needed (idempotent)
-    conn.execute_batch(
-        "CREATE TABLE IF NOT EXISTS files_index (
-        id INTEGER PRIMARY KEY AUTOINCREMENT,
-        path TEXT NOT NULL UNIQUE,
-        name TEXT NOT NULL,
-        file_type TEXT,
-        doc_type TEXT,
-        size INTEGER,
-        indexed_at INTEGER NOT NULL,
-        drive TEXT
-    );",
-    )
-    .map_err(|e| e.to_string())?;
-
-    let now = Utc::now().timestamp();
-
-    // Walk synchronously and insert into files_index
-    let mut inserted: usize = 0;
-    let mut stack = vec![root];
+    ensure_files_index_tables(&conn)?;
 
-    while let Some(p) = stack.pop() {
-        let read = match std::fs::read_dir(&p) {
-            Ok(r) => r,
-            Err(_) => continue,
-        };
-
-        for entry in read.flatten() {
-            let path_buf = entry.path();
-            let meta = match entry.metadata() {
-                Ok(m) => m,
-                Err(_) => continue,
-            };
-            let is_dir = meta.is_dir();
-            let size_opt = if is_dir {
-                None
-            } else {
-                Some(meta.len() as i64)
-            };
-
-            // canonicalize best-effort (fallback to original)
-            let canonical_buf = std::fs::canonicalize(&path_buf).unwrap_or(path_buf.clone());
-            let path_str = canonical_buf.to_string_lossy().to_string();
-
-            // name from entry
-            let name = entry.file_name().to_string_lossy().to_string();
-
-            // compute types using canonical path string
-            let file_type = if is_dir { "dir" } else { "file" }.to_string();
-            let doc_type = doc_type_for_path(&path_str, is_dir);
-            let drive_val = normalize_drive_for_storage(&path_str);
-
-            // Use the synchronous connection `conn` here
-            let _ = conn.execute(
-                "INSERT INTO files_index (path, name, file_type, doc_type, size, indexed_at, drive)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-         ON CONFLICT(path)
-         DO UPDATE SET
-           name=excluded.name,
-           file_type=excluded.file_type,
-           doc_type=excluded.doc_type,
-           size=excluded.size,
-           indexed_at=excluded.indexed_at,
-           drive=excluded.drive",
-                params![path_str, name, file_type, doc_type, size_opt, now, drive_val],
-            );
+    // Walk synchronously, reconciling files_index with the tree
+    // pick up volumes mounted or moved since the last walk
+    crate::volumes::refresh(&conn)?;
+    let rules = crate::index_rules::IndexRules::load(&conn, std::slice::from_ref(&root))?;
+    let stats =
+        crate::indexer::walk_roots(&conn, vec![root], Default::default(), &rules, |_| {
+            std::ops::ControlFlow::Continue(())
+        })?;
 
-            inserted += 1;
-            if is_dir && !is_excluded_path(&path_str) {
-                // push canonical buf so later visits are normalized
-                stack.push(canonical_buf);
-            }
-        }
-    }
+    crate::content_index::schedule_pass();
+    crate::content_hash::schedule_pass();
+    crate::archive_index::schedule_pass();
 
     // audit (use outer conn local here)
     let _ = crate::db::insert_audit_log(
@@ -1043,13 +870,16 @@ pub fn index_path(session_token: String, root_path: String) -> Result<usize, Str
         Some(&user.username),
         "index_path",
         None,
-        Some(&root_path),
+        Some(&format!(
+            "{} (added:{} updated:{} removed:{})",
+            root_path, stats.added, stats.updated, stats.removed
+        )),
     );
 
-    Ok(inserted)
+    Ok(stats.visited)
 }
 
-// Simple in-memory job tracker for indexing jobs (job_id -> status)
+// Indexing job status as reported to the frontend (persisted in index_jobs)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum IndexJobState {
     Running {
@@ -1058,17 +888,24 @@ pub enum IndexJobState {
     },
     Finished {
         processed: usize,
+        #[serde(default)]
+        stats: crate::indexer::WalkStats,
     },
     Failed {
         message: String,
     },
+    Paused {
+        processed: usize,
+        last_path: Option<String>,
+    },
+    /// Stopped by an admin; counts cover the part that was walked.
+    Cancelled {
+        processed: usize,
+        stats: crate::indexer::WalkStats,
+    },
 }
 
-// in-memory job tracker (same as before)
-static INDEX_JOBS: Lazy<Mutex<HashMap<String, IndexJobState>>> =
-    Lazy::new(|| Mutex::new(HashMap::new()));
-
-fn ensure_files_index_tables(conn: &rusqlite::Connection) -> Result<(), String> {
+pub(crate) fn ensure_files_index_tables(conn: &rusqlite::Connection) -> Result<(), String> {
     conn.execute_batch(
         "CREATE TABLE IF NOT EXISTS files_index (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -1078,20 +915,97 @@ fn ensure_files_index_tables(conn: &rusqlite::Connection) -> Result<(), String>
             doc_type TEXT,
             size INTEGER,
             indexed_at INTEGER NOT NULL,
-            drive TEXT
+            drive TEXT,
+            parent TEXT,
+            mtime INTEGER,
+            ctime INTEGER,
+            inode INTEGER
         );
-        CREATE VIRTUAL TABLE IF NOT EXISTS files_index_fts USING fts5(name, path, content='files_index', content_rowid='id');
-        CREATE TRIGGER IF NOT EXISTS files_index_ai AFTER INSERT ON files_index BEGIN
-          INSERT INTO files_index_fts(rowid, name, path) VALUES (new.id, new.name, new.path);
+        CREATE TABLE IF NOT EXISTS file_content (
+            file_id INTEGER PRIMARY KEY,
+            extractor TEXT NOT NULL,
+            status TEXT NOT NULL,
+            chars INTEGER NOT NULL DEFAULT 0,
+            error TEXT,
+            source_size INTEGER,
+            source_mtime INTEGER,
+            extracted_at INTEGER NOT NULL,
+            ocr_status TEXT,
+            ocr_sha256 TEXT
+        );",
+    )
+    .map_err(|e| format!("ensure tables error: {}", e))?;
+    // change-detection columns (incremental re-indexing)
+    let added_parent = crate::db::ensure_column(conn, "files_index", "parent", "TEXT")?;
+    crate::db::ensure_column(conn, "files_index", "mtime", "INTEGER")?;
+    crate::db::ensure_column(conn, "files_index", "ctime", "INTEGER")?;
+    crate::db::ensure_column(conn, "files_index", "inode", "INTEGER")?;
+    // volume identity (volumes.rs); rel_path is relative to the volume root
+    crate::volumes::ensure_tables(conn)?;
+    crate::db::ensure_column(conn, "files_index", "volume_id", "INTEGER")?;
+    crate::db::ensure_column(conn, "files_index", "rel_path", "TEXT")?;
+    crate::locations::ensure_tables(conn)?;
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_files_index_parent ON files_index(parent);
+         CREATE INDEX IF NOT EXISTS idx_files_index_volume ON files_index(volume_id);
+         CREATE INDEX IF NOT EXISTS idx_files_index_size ON files_index(size);",
+    )
+    .map_err(|e| format!("ensure tables error: {}", e))?;
+    if added_parent {
+        crate::indexer::backfill_parents(conn)?;
+    }
+    crate::db::ensure_column(conn, "file_content", "ocr_status", "TEXT")?;
+    crate::db::ensure_column(conn, "file_content", "ocr_sha256", "TEXT")?;
+    crate::file_metadata::ensure_tables(conn)?;
+    crate::archive_index::ensure_tables(conn)?;
+
+    // The FTS table used to be external-content over files_index (name, path
+    // only). Document text has no column in files_index, so it is now a
+    // regular FTS table with `body` (document text) and `meta` (embedded
+    // properties) columns; replace an older layout once. Clearing
+    // file_content makes the content pass fill both in again.
+    let fts_sql: Option<String> = conn
+        .query_row(
+            "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'files_index_fts'",
+            [],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("ensure tables error: {}", e))?;
+    let needs_rebuild = match fts_sql {
+        Some(sql) => !sql.contains("meta"),
+        None => true,
+    };
+    if needs_rebuild {
+        conn.execute_batch(
+            "DROP TRIGGER IF EXISTS files_index_ai;
+             DROP TRIGGER IF EXISTS files_index_ad;
+             DROP TRIGGER IF EXISTS files_index_au;
+             DROP TABLE IF EXISTS files_index_fts;
+             CREATE VIRTUAL TABLE files_index_fts USING fts5(name, path, body, meta);
+             INSERT INTO files_index_fts(rowid, name, path, body, meta)
+               SELECT id, name, path, '', '' FROM files_index;
+             DELETE FROM file_content;",
+        )
+        .map_err(|e| format!("ensure tables error: {}", e))?;
+    }
+
+    conn.execute_batch(
+        "CREATE TRIGGER IF NOT EXISTS files_index_ai AFTER INSERT ON files_index BEGIN
+          INSERT INTO files_index_fts(rowid, name, path, body, meta)
+            VALUES (new.id, new.name, new.path, '', '');
         END;
         CREATE TRIGGER IF NOT EXISTS files_index_ad AFTER DELETE ON files_index BEGIN
-          INSERT INTO files_index_fts(files_index_fts, rowid, name, path) VALUES('delete', old.id, old.name, old.path);
+          DELETE FROM files_index_fts WHERE rowid = old.id;
+          DELETE FROM file_content WHERE file_id = old.id;
+          DELETE FROM file_metadata WHERE file_id = old.id;
         END;
-        CREATE TRIGGER IF NOT EXISTS files_index_au AFTER UPDATE ON files_index BEGIN
-          INSERT INTO files_index_fts(files_index_fts, rowid, name, path) VALUES('delete', old.id, old.name, old.path);
-          INSERT INTO files_index_fts(rowid, name, path) VALUES (new.id, new.name, new.path);
-        END;"
-    ).map_err(|e| format!("ensure tables error: {}", e))?;
+        CREATE TRIGGER IF NOT EXISTS files_index_au AFTER UPDATE OF name, path ON files_index
+        WHEN old.name IS NOT new.name OR old.path IS NOT new.path BEGIN
+          UPDATE files_index_fts SET name = new.name, path = new.path WHERE rowid = old.id;
+        END;",
+    )
+    .map_err(|e| format!("ensure tables error: {}", e))?;
     Ok(())
 }
 
@@ -1115,113 +1029,8 @@ pub fn index_path_start(session_token: String, root_path: String) -> Result<Stri
     let root = std::fs::canonicalize(&root_path)
         .map_err(|e| format!("cannot canonicalize root: {}", e))?;
 
-    // create job id and set initial state
-    let job_id = Uuid::new_v4().to_string();
-    {
-        let mut m = INDEX_JOBS
-            .lock()
-            .map_err(|_| "job mutex poisoned".to_string())?;
-        m.insert(
-            job_id.clone(),
-            IndexJobState::Running {
-                processed: 0,
-                last_path: None,
-            },
-        );
-    }
-
-    // clone needed values to move into thread
-    let root_clone = root.clone();
-    let job_clone = job_id.clone();
-
-    // Spawn background thread (uses its own DB connection)
-    std::thread::spawn(move || {
-        match crate::db::open_connection() {
-            Ok(conn2) => {
-                if let Err(e) = ensure_files_index_tables(&conn2) {
-                    let mut m = INDEX_JOBS.lock().unwrap();
-                    m.insert(job_clone.clone(), IndexJobState::Failed { message: e });
-                    return;
-                }
-
-                let mut processed: usize = 0usize;
-                let now = Utc::now().timestamp();
-                let mut stack = vec![root_clone];
-
-                while let Some(p) = stack.pop() {
-                    // update last_path
-                    {
-                        let mut m = INDEX_JOBS.lock().unwrap();
-                        m.insert(
-                            job_clone.clone(),
-                            IndexJobState::Running {
-                                processed,
-                                last_path: Some(p.to_string_lossy().to_string()),
-                            },
-                        );
-                    }
-
-                    let read = match std::fs::read_dir(&p) {
-                        Ok(r) => r,
-                        Err(_) => continue,
-                    };
-
-                    for entry in read.flatten() {
-                        let path_buf = entry.path();
-                        let meta = match entry.metadata() {
-                            Ok(m) => m,
-                            Err(_) => continue,
-                        };
-                        let is_dir = meta.is_dir();
-                        let size_opt = if is_dir {
-                            None
-                        } else {
-                            Some(meta.len() as i64)
-                        };
-                        let path_str = path_buf.to_string_lossy().to_string();
-                        let name = entry.file_name().to_string_lossy().to_string();
-                        let file_type = if is_dir { "dir" } else { "file" }.to_string();
-
-                        // Normalize drive via top-level helper; conn2 used here (not outer conn)
-                        let drive_val = normalize_drive_for_storage(&path_str);
-                        let doc_type = doc_type_for_path(&path_str, is_dir); // NEW
-
-                        let _ = conn2.execute(
-    "INSERT INTO files_index (path, name, file_type, doc_type, size, indexed_at, drive)
-     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-     ON CONFLICT(path)
-     DO UPDATE SET
-       name=excluded.name,
-       file_type=excluded.file_type,
-       doc_type=excluded.doc_type,
-       size=excluded.size,
-       indexed_at=excluded.indexed_at,
-       drive=excluded.drive",
-    params![path_str, name, file_type, doc_type, size_opt, now, drive_val],
-);
-
-                        processed += 1;
-                        if is_dir && !is_excluded_path(&path_str) {
-                            stack.push(path_buf);
-                        }
-                    }
-                }
-
-                // mark finished
-                let mut m = INDEX_JOBS.lock().unwrap();
-                m.insert(job_clone.clone(), IndexJobState::Finished { processed });
-            }
-            Err(e) => {
-                let mut m = INDEX_JOBS.lock().unwrap();
-                m.insert(
-                    job_clone.clone(),
-                    IndexJobState::Failed {
-                        message: format!("db open error in worker: {}", e),
-                    },
-                );
-            }
-        }
-    });
+    let job_id = crate::index_jobs::create(&conn, "path", &[root], Some(uid), &user.username)?;
+    crate::index_jobs::spawn_runner(job_id.clone(), None);
 
     // audit using outer conn
     let _ = crate::db::insert_audit_log(
@@ -1239,15 +1048,15 @@ pub fn index_path_start(session_token: String, root_path: String) -> Result<Stri
 /// Poll index status by job id
 #[tauri::command]
 pub fn get_index_status(job_id: String) -> Result<Option<IndexJobState>, String> {
-    let m = INDEX_JOBS
-        .lock()
-        .map_err(|_| "job mutex poisoned".to_string())?;
-    Ok(m.get(&job_id).cloned())
+    let conn = crate::db::open_connection().map_err(|e| format!("db open: {}", e))?;
+    crate::index_jobs::state(&conn, &job_id)
 }
 
 /// Search across indexed files using FTS5 (fast). Returns FileEntry-like list.
 /// Search across indexed files using FTS5 (fast). Returns FileEntry-like list.
 /// q is the query (supports simple FTS5 syntax). limit/offset optional.
+/// `filters` narrows the results by embedded metadata (title, author, dates,
+/// page count, camera, GPS); with filters, q may be empty.
 ///
 /// NOTE: session_token is now optional. If provided, we validate session; if not, we allow unauthenticated global search.
 #[tauri::command]
@@ -1256,56 +1065,97 @@ pub fn search_files(
     q: String,
     limit: Option<i64>,
     offset: Option<i64>,
+    filters: Option<crate::file_metadata::MetadataFilter>,
 ) -> Result<Vec<FileEntry>, String> {
     // If token supplied, validate session. If not, allow unauthenticated search.
+    let mut searcher: Option<i64> = None;
     if let Some(ref token) = session_token {
         let conn_check =
             crate::db::open_connection().map_err(|e| format!("db open (session check): {}", e))?;
         let maybe_uid = crate::session::validate_session(&conn_check, token)
             .map_err(|e| format!("session validation: {}", e))?;
-        let _ = maybe_uid.ok_or_else(|| "invalid session".to_string())?;
+        searcher = Some(maybe_uid.ok_or_else(|| "invalid session".to_string())?);
         // drop conn_check (goes out of scope)
     }
 
     let conn = crate::db::open_connection().map_err(|e| format!("db open: {}", e))?;
     let q_trim = q.trim();
-    if q_trim.is_empty() {
+    let mut values: Vec<rusqlite::types::Value> = Vec::new();
+    let mut conditions = vec![location_filter(&conn, searcher)];
+    if !q_trim.is_empty() {
+        // naive safe-ish transform for prefix match
+        values.push(format!("\"{}\"*", q_trim.replace('"', "\"\"")).into());
+        conditions.push("files_index_fts MATCH ?1".to_string());
+    }
+    let meta_conditions = filters.unwrap_or_default().clauses(&mut values);
+    if q_trim.is_empty() && meta_conditions.is_empty() {
         return Ok(Vec::new());
     }
+    conditions.extend(meta_conditions);
 
     let lim = limit.unwrap_or(200);
     let off = offset.unwrap_or(0);
+    values.push(lim.into());
+    values.push(off.into());
 
     ensure_files_index_tables(&conn).map_err(|e| format!("ensure tables: {}", e))?;
 
-    // naive safe-ish transform for prefix match
-    let fts_query = format!("\"{}\"*", q_trim.replace('"', "\"\""));
-
-    let sql = "SELECT fi.path, fi.name, fi.file_type, fi.size, fi.indexed_at
+    // snippet() always yields an excerpt of the body column; it only carries
+    // highlight markers when the body itself matched. The markers are
+    // private-use characters so no document text can be mistaken for a hit.
+    // rows of offline locations stay in the results, flagged unavailable;
+    // restricted locations are left out unless the searcher is an admin
+    let (snippet, fts_join) = if q_trim.is_empty() {
+        ("NULL", "")
+    } else {
+        (
+            "snippet(files_index_fts, 2, char(57344), char(57345), '…', 12)",
+            "JOIN files_index_fts ON files_index_fts.rowid = fi.id",
+        )
+    };
+    let sql = format!(
+        "SELECT fi.path, fi.name, fi.file_type, fi.size, COALESCE(fi.mtime, fi.indexed_at),
+       {}, fm.title, fm.author, {}
 FROM files_index fi
-JOIN files_index_fts ON files_index_fts.rowid = fi.id
-WHERE files_index_fts MATCH ?1
+{}
+LEFT JOIN file_metadata fm ON fm.file_id = fi.id
+{}
+WHERE {}
 ORDER BY fi.indexed_at DESC
-LIMIT ?2 OFFSET ?3
-";
+LIMIT ?{} OFFSET ?{}
+",
+        snippet,
+        LOCATION_COLUMNS,
+        fts_join,
+        LOCATION_JOIN,
+        conditions.join(" AND "),
+        values.len() - 1,
+        values.len()
+    );
 
     let mut stmt = conn
-        .prepare(sql)
+        .prepare(&sql)
         .map_err(|e| format!("prepare error: {}", e))?;
 
     let rows = stmt
-        .query_map(params![fts_query, lim, off], |row| {
+        .query_map(rusqlite::params_from_iter(values), |row| {
             let path: String = row.get(0)?;
             let name: String = row.get(1)?;
             let file_type: Option<String> = row.get(2)?;
             let size: Option<i64> = row.get(3)?;
             let indexed_at: Option<i64> = row.get(4)?;
+            let snippet: Option<String> = row.get(5)?;
             Ok(FileEntry {
                 name,
                 is_dir: file_type.as_deref() == Some("dir"),
                 size,
                 modified: indexed_at,
                 path,
+                snippet: snippet.filter(|s| s.contains(SNIPPET_HIT_OPEN)),
+                title: row.get(6)?,
+                author: row.get(7)?,
+                location: row.get(8)?,
+                unavailable: row.get(9)?,
             })
         })
         .map_err(|e| format!("query_map error: {}", e))?;
@@ -1316,9 +1166,103 @@ LIMIT ?2 OFFSET ?3
             out.push(e);
         }
     }
+
+    // audit the first page only so paging through results is not counted twice
+    if off == 0 {
+        log_search(&conn, searcher, "fs_search", q_trim, out.len());
+    }
     Ok(out)
 }
 
+/// Opening hit marker passed to snippet() as char(57344); hits close with U+E001.
+const SNIPPET_HIT_OPEN: char = '\u{E000}';
+
+/// Storage location name and availability of a files_index row `fi`.
+const LOCATION_COLUMNS: &str =
+    "sl.name, COALESCE(v.online = 0 OR sl.available = 0, 0)";
+const LOCATION_JOIN: &str = "LEFT JOIN volumes v ON v.id = fi.volume_id
+LEFT JOIN storage_locations sl ON sl.volume_key = v.key";
+
+/// Search condition hiding restricted locations from everyone but admins.
+fn location_filter(conn: &Connection, searcher: Option<i64>) -> String {
+    let admin = searcher
+        .and_then(|uid| crate::db::get_user_by_id_row(conn, uid).ok().flatten())
+        .is_some_and(|u| u.role == "admin");
+    if admin {
+        "1".to_string()
+    } else {
+        format!(
+            "(sl.access_policy IS NULL OR sl.access_policy != '{}')",
+            crate::locations::POLICY_RESTRICTED
+        )
+    }
+}
+
+/// files_index id of the file at `path` if `uid` may see it in search results
+/// (rows of restricted locations are admin-only).
+pub(crate) fn visible_file_id(
+    conn: &Connection,
+    uid: i64,
+    path: &str,
+) -> Result<Option<i64>, String> {
+    conn.query_row(
+        &format!(
+            "SELECT fi.id FROM files_index fi
+             {}
+             WHERE fi.path = ?1 AND fi.file_type != 'dir' AND {}",
+            LOCATION_JOIN,
+            location_filter(conn, Some(uid))
+        ),
+        params![path],
+        |r| r.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Whether `path` lies on a location `uid` may not see, judged by its index
+/// row and by the volume it is on (so files not indexed yet are covered).
+pub(crate) fn location_hidden(conn: &Connection, uid: i64, path: &str) -> Result<bool, String> {
+    let volume_id = crate::volumes::locate(path).map(|l| l.volume_id);
+    let filter = location_filter(conn, Some(uid));
+    let hidden: i64 = conn
+        .query_row(
+            &format!(
+                "SELECT EXISTS (SELECT 1 FROM files_index fi {join}
+                                WHERE fi.path = ?1 AND NOT {filter})
+                     OR EXISTS (SELECT 1 FROM volumes v
+                                LEFT JOIN storage_locations sl ON sl.volume_key = v.key
+                                WHERE v.id = ?2 AND NOT {filter})",
+                join = LOCATION_JOIN,
+                filter = filter
+            ),
+            params![path, volume_id],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(hidden != 0)
+}
+
+/// Record a search in the audit log (feeds per-user activity reports).
+fn log_search(conn: &Connection, uid: Option<i64>, action: &str, query: &str, hits: usize) {
+    let uid = match uid {
+        Some(u) => u,
+        None => return,
+    };
+    let username = crate::db::get_user_by_id_row(conn, uid)
+        .ok()
+        .flatten()
+        .map(|u| u.username);
+    let _ = crate::db::insert_audit_log(
+        conn,
+        Some(uid),
+        username.as_deref(),
+        action,
+        None,
+        Some(&format!("query:{} hits:{}", query, hits)),
+    );
+}
+
 // -----------------------------
 // Additional file/folder CRUD commands
 // -----------------------------
@@ -1399,6 +1343,13 @@ fn canonical_and_allow(path: &str) -> Result<std::path::PathBuf, String> {
     }
 }
 
+/// Canonical form of a path that exists after an operation (falls back to the input).
+fn canonical_string(path: &str) -> String {
+    std::fs::canonicalize(path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
 // Move / Rename (exposed as fs_move_by_session)
 #[tauri::command]
 pub fn fs_move_by_session(
@@ -1445,12 +1396,13 @@ pub fn fs_move_by_session(
 
     // audit
     let details = format!("move: {} -> {}", src_canon.to_string_lossy(), dst_path);
-    let _ = crate::db::insert_audit_log(
+    let _ = crate::db::insert_file_audit_log(
         &conn,
         Some(uid),
         Some(&user.username),
         "fs_move",
-        None,
+        &src_canon.to_string_lossy(),
+        Some(&canonical_string(&dst_path)),
         Some(&details),
     );
 
@@ -1518,12 +1470,13 @@ pub fn fs_copy_by_session(
 
     // audit
     let details = format!("copy: {} -> {}", src_canon.to_string_lossy(), dst_path);
-    let _ = crate::db::insert_audit_log(
+    let _ = crate::db::insert_file_audit_log(
         &conn,
         Some(uid),
         Some(&user.username),
         "fs_copy",
-        None,
+        &src_canon.to_string_lossy(),
+        Some(&canonical_string(&dst_path)),
         Some(&details),
     );
 
@@ -1589,12 +1542,13 @@ pub fn fs_copy(
         if let Ok(conn) = crate::db::open_connection() {
             if let Ok(Some(uid)) = crate::session::validate_session(&conn, &token) {
                 if let Ok(Some(user_row)) = crate::db::get_user_by_id_row(&conn, uid) {
-                    let _ = crate::db::insert_audit_log(
+                    let _ = crate::db::insert_file_audit_log(
                         &conn,
                         Some(uid),
                         Some(&user_row.username),
                         "fs_copy",
-                        None,
+                        &src_canon.to_string_lossy(),
+                        Some(&canonical_string(&dest_path)),
                         Some(&format!("from:{} to:{}", src_path, dest_path)),
                     );
                 }
@@ -1637,12 +1591,13 @@ pub fn fs_move(
         if let Ok(conn) = crate::db::open_connection() {
             if let Ok(Some(uid)) = crate::session::validate_session(&conn, &token) {
                 if let Ok(Some(user_row)) = crate::db::get_user_by_id_row(&conn, uid) {
-                    let _ = crate::db::insert_audit_log(
+                    let _ = crate::db::insert_file_audit_log(
                         &conn,
                         Some(uid),
                         Some(&user_row.username),
                         "fs_move",
-                        None,
+                        &src_canon.to_string_lossy(),
+                        Some(&canonical_string(&dest_path)),
                         Some(&format!("from:{} to:{}", src_path, dest_path)),
                     );
                 }
@@ -1681,11 +1636,12 @@ pub fn fs_mkdir_by_session(session_token: String, path: String) -> Result<(), St
 
     std::fs::create_dir_all(&path).map_err(|e| format!("mkdir failed: {}", e))?;
 
-    let _ = crate::db::insert_audit_log(
+    let _ = crate::db::insert_file_audit_log(
         &conn,
         Some(uid),
         Some(&user.username),
         "fs_mkdir",
+        &canonical_string(&path),
         None,
         Some(&path),
     );
@@ -1733,11 +1689,12 @@ pub fn fs_create_file_by_session(
             .map_err(|e| format!("create file failed: {}", e))?;
     }
 
-    let _ = crate::db::insert_audit_log(
+    let _ = crate::db::insert_file_audit_log(
         &conn,
         Some(uid),
         Some(&user.username),
         "fs_create_file",
+        &canonical_string(&path),
         None,
         Some(&path),
     );
@@ -1809,11 +1766,12 @@ pub fn fs_delete_by_session(session_token: String, path: String) -> Result<(), S
     }
 
     // audit log
-    let _ = crate::db::insert_audit_log(
+    let _ = crate::db::insert_file_audit_log(
         &conn,
         Some(uid),
         Some(&user.username),
         "fs_delete",
+        &canonical_str,
         None,
         Some(&canonical_str),
     );
@@ -1864,12 +1822,13 @@ pub fn fs_rename_by_session(
 
     // audit
     let details = format!("from: {} -> to: {}", old_canon.to_string_lossy(), new_path);
-    let _ = crate::db::insert_audit_log(
+    let _ = crate::db::insert_file_audit_log(
         &conn,
         Some(uid),
         Some(&user.username),
         "fs_rename",
-        None,
+        &old_canon.to_string_lossy(),
+        Some(&canonical_string(&new_path)),
         Some(&details),
     );
 
@@ -1948,11 +1907,12 @@ pub fn fs_tag_item_by_session(
 
     // audit
     let detail = format!("tag='{}' on {}", tag_id, canonical.to_string_lossy());
-    let _ = crate::db::insert_audit_log(
+    let _ = crate::db::insert_file_audit_log(
         &conn,
         Some(uid),
         Some(&user.username),
         "fs_tag",
+        &canonical_str,
         None,
         Some(&detail),
     );
@@ -1970,6 +1930,12 @@ pub fn list_drives(session_token: String) -> Result<Vec<DriveInfo>, String> {
         .map_err(|e| format!("session validation error: {}", e))?;
     let _uid = maybe_uid.ok_or_else(|| "invalid or expired session".to_string())?;
 
+    // identify volumes plugged in (or moved) since the last look
+    if let Err(e) = crate::volumes::refresh(&conn) {
+        eprintln!("[volumes] refresh failed: {}", e);
+    }
+    let location_names = crate::locations::names_by_volume(&conn).unwrap_or_default();
+
     // Use sysinfo to get disks
     let mut sys = System::new_all();
     sys.refresh_disks_list();
@@ -1988,8 +1954,17 @@ pub fn list_drives(session_token: String) -> Result<Vec<DriveInfo>, String> {
         let total = disk.total_space();
         let avail = disk.available_space();
         // Try to use a user-friendly label: on Windows, mount_point is like "C:\"
-        let label = mount.clone();
-        let id = mount.clone();
+        // Known volumes are identified by UUID/label rather than mount point,
+        // and named after their storage location if one is registered.
+        let (id, label) = match crate::volumes::volume_at(&mount) {
+            Some((key, _)) if location_names.contains_key(&key) => {
+                let label = format!("{} · {}", location_names[&key], mount);
+                (key, label)
+            }
+            Some((key, Some(name))) => (key, format!("{} · {}", name, mount)),
+            Some((key, None)) => (key, mount.clone()),
+            None => (mount.clone(), mount.clone()),
+        };
 
         out.push(DriveInfo {
             id,
@@ -2144,11 +2119,12 @@ pub fn fs_untag_item_by_session(
 
     // audit
     let detail = format!("untag='{}' on {}", tag_id, path);
-    let _ = crate::db::insert_audit_log(
+    let _ = crate::db::insert_file_audit_log(
         &conn,
         Some(uid),
         Some(&user.username),
         "fs_untag",
+        &canonical_str,
         None,
         Some(&detail),
     );
@@ -2180,12 +2156,13 @@ pub fn search_files_by_tag(
     offset: Option<i64>,
 ) -> Result<Vec<FileEntry>, String> {
     // If token supplied, validate session (non-fatal if None)
+    let mut searcher: Option<i64> = None;
     if let Some(ref token) = session_token {
         let conn_check =
             crate::db::open_connection().map_err(|e| format!("db open (session check): {}", e))?;
         let maybe_uid = crate::session::validate_session(&conn_check, token)
             .map_err(|e| format!("session validation: {}", e))?;
-        let _ = maybe_uid.ok_or_else(|| "invalid session".to_string())?;
+        searcher = Some(maybe_uid.ok_or_else(|| "invalid session".to_string())?);
     }
 
     let conn = crate::db::open_connection().map_err(|e| format!("db open: {}", e))?;
@@ -2197,7 +2174,8 @@ pub fn search_files_by_tag(
 
     // Use replace to normalize slashes for matching (handles Windows backslash vs stored path)
     // LEFT JOIN so tag entries without index rows still show (but we'll filter those out later if needed).
-    let sql = r#"
+    let sql = format!(
+        r#"
         SELECT
           COALESCE(fi.path, ft.path) as path,
           COALESCE(fi.name,
@@ -2212,17 +2190,23 @@ pub fn search_files_by_tag(
                    , ft.path) as name,
           COALESCE(fi.file_type, 'file') as file_type,
           fi.size,
-          fi.indexed_at
+          COALESCE(fi.mtime, fi.indexed_at),
+          {}
         FROM file_tags ft
         LEFT JOIN files_index fi
           ON replace(ft.path, '\\', '/') = replace(fi.path, '\\', '/')
-        WHERE ft.tag = ?1
+        {}
+        WHERE ft.tag = ?1 AND {}
         ORDER BY ft.created_at DESC
         LIMIT ?2 OFFSET ?3
-    "#;
+    "#,
+        LOCATION_COLUMNS,
+        LOCATION_JOIN,
+        location_filter(&conn, searcher)
+    );
 
     let mut stmt = conn
-        .prepare(sql)
+        .prepare(&sql)
         .map_err(|e| format!("prepare error: {}", e))?;
     let rows = stmt
         .query_map(params![tag, lim, off], |row| {
@@ -2237,6 +2221,11 @@ pub fn search_files_by_tag(
                 size,
                 modified: indexed_at,
                 path,
+                snippet: None,
+                location: row.get(5)?,
+                title: None,
+                author: None,
+                unavailable: row.get(6)?,
             })
         })
         .map_err(|e| format!("query_map error: {}", e))?;
@@ -2247,5 +2236,8 @@ pub fn search_files_by_tag(
             out.push(e);
         }
     }
+    if off == 0 {
+        log_search(&conn, searcher, "fs_search_tag", &tag, out.len());
+    }
     Ok(out)
 }
@@ -1,4 +1,3 @@
-use crate::fs_ops::{doc_type_for_path, normalize_drive_for_storage};
 use notify::event::{ModifyKind, RenameMode};
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use rusqlite::params;
@@ -47,10 +46,14 @@ fn run_watcher_once(roots: Vec<PathBuf>, db_path: PathBuf, app: AppHandle) -> Re
     }
 
     let conn = rusqlite::Connection::open(db_path).map_err(|e| format!("db open failed: {}", e))?;
+    let mut rules = WatchRules::load(&conn, roots);
 
     for evt in rx {
         match evt {
-            Ok(event) => handle_event(&conn, &app, event),
+            Ok(event) => {
+                rules.refresh(&conn);
+                handle_event(&conn, &app, &rules.rules, event)
+            }
             Err(e) => return Err(format!("watch channel error: {}", e)),
         }
     }
@@ -58,6 +61,42 @@ fn run_watcher_once(roots: Vec<PathBuf>, db_path: PathBuf, app: AppHandle) -> Re
     Err("watcher loop exited unexpectedly".into())
 }
 
+/// Index rules for the watched roots, reloaded when an admin changes them.
+struct WatchRules {
+    roots: Vec<PathBuf>,
+    version: u64,
+    rules: crate::index_rules::IndexRules,
+}
+
+impl WatchRules {
+    fn load(conn: &rusqlite::Connection, roots: Vec<PathBuf>) -> Self {
+        let version = crate::index_rules::version();
+        let rules = crate::index_rules::IndexRules::load(conn, &roots).unwrap_or_else(|e| {
+            eprintln!("[fs_watch] loading index rules failed: {}", e);
+            Default::default()
+        });
+        WatchRules { roots, version, rules }
+    }
+
+    fn refresh(&mut self, conn: &rusqlite::Connection) {
+        if self.version != crate::index_rules::version() {
+            *self = WatchRules::load(conn, std::mem::take(&mut self.roots));
+        }
+    }
+}
+
+/// Whether the rules let `path` into the index; if not, any row for it is dropped.
+fn admit(conn: &rusqlite::Connection, rules: &crate::index_rules::IndexRules, path: &str) -> bool {
+    let meta = std::fs::symlink_metadata(path).ok();
+    let is_dir = meta.as_ref().map(|m| m.is_dir()).unwrap_or(false);
+    let size = meta.as_ref().filter(|m| !m.is_dir()).map(|m| m.len() as i64);
+    if rules.check(path, is_dir, size).indexed {
+        return true;
+    }
+    let _ = crate::indexer::remove_entry(conn, path);
+    false
+}
+
 fn normalize(p: &PathBuf) -> String {
     let mut s = p.to_string_lossy().to_string();
 
@@ -77,7 +116,12 @@ fn normalize(p: &PathBuf) -> String {
     s
 }
 
-fn handle_event(conn: &rusqlite::Connection, app: &AppHandle, event: Event) {
+fn handle_event(
+    conn: &rusqlite::Connection,
+    app: &AppHandle,
+    rules: &crate::index_rules::IndexRules,
+    event: Event,
+) {
     let now = chrono::Utc::now().timestamp();
 
     match event.kind {
@@ -86,7 +130,8 @@ fn handle_event(conn: &rusqlite::Connection, app: &AppHandle, event: Event) {
             for p in event.paths {
                 let path_str = normalize(&p);
 
-                let _ = conn.execute("DELETE FROM files_index WHERE path = ?1", params![path_str]);
+                let _ = crate::indexer::remove_entry(conn, &path_str);
+                let _ = crate::file_history::watcher_removed(conn, &path_str);
 
                 emit_fs_event(app, "delete", &path_str);
             }
@@ -97,39 +142,16 @@ fn handle_event(conn: &rusqlite::Connection, app: &AppHandle, event: Event) {
             for p in event.paths {
                 let canonical = std::fs::canonicalize(&p).unwrap_or(p.clone());
                 let path_str = normalize(&canonical);
-
-                let name = canonical
-                    .file_name()
-                    .map(|s| s.to_string_lossy().to_string())
-                    .unwrap_or_default();
+                if !admit(conn, rules, &path_str) {
+                    continue;
+                }
 
                 let is_dir = canonical.is_dir();
-                let size = if !is_dir {
-                    std::fs::metadata(&canonical).ok().map(|m| m.len() as i64)
-                } else {
-                    None
-                };
-
-                let doc_type = doc_type_for_path(&path_str, is_dir);
-                let drive = normalize_drive_for_storage(&path_str);
-
-                let _ = conn.execute(
-                    "INSERT INTO files_index (path, name, file_type, doc_type, size, indexed_at, drive)
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-                     ON CONFLICT(path)
-                     DO UPDATE SET
-                       indexed_at = excluded.indexed_at,
-                       size = excluded.size",
-                    params![
-                        path_str,
-                        name,
-                        if is_dir { "dir" } else { "file" },
-                        doc_type,
-                        size,
-                        now,
-                        drive
-                    ],
-                );
+                let _ = crate::indexer::upsert_path(conn, &path_str, now);
+
+                if !is_dir {
+                    forget_content(conn, &path_str);
+                }
 
                 emit_fs_event(app, "create", &path_str);
             }
@@ -140,23 +162,45 @@ fn handle_event(conn: &rusqlite::Connection, app: &AppHandle, event: Event) {
             if event.paths.len() == 2 {
                 let old = normalize(&event.paths[0]);
                 let new = normalize(&event.paths[1]);
+                if !admit(conn, rules, &new) {
+                    // moved somewhere the index does not cover
+                    let _ = crate::indexer::remove_entry(conn, &old);
+                    return;
+                }
 
-                let _ = conn.execute(
-                    "UPDATE files_index SET path = ?1, name = ?2 WHERE path = ?3",
-                    params![
-                        new,
-                        PathBuf::from(&new)
-                            .file_name()
-                            .unwrap_or_default()
-                            .to_string_lossy(),
-                        old
-                    ],
-                );
+                let _ = crate::indexer::rename_entry(conn, &old, &new);
+
+                let _ = crate::file_history::watcher_renamed(conn, &old, &new);
 
                 emit_fs_event(app, "move", &format!("{} -> {}", old, new));
             }
         }
 
+        // ---------- CONTENT CHANGE ----------
+        EventKind::Modify(ModifyKind::Data(_)) => {
+            for p in event.paths {
+                let path_str = normalize(&std::fs::canonicalize(&p).unwrap_or(p.clone()));
+                if !std::path::Path::new(&path_str).is_file() || !admit(conn, rules, &path_str) {
+                    continue;
+                }
+                let _ = crate::indexer::upsert_path(conn, &path_str, now);
+                forget_content(conn, &path_str);
+            }
+        }
+
         _ => {}
     }
 }
+
+/// Drop extracted text state for `path` so the content indexer re-reads it;
+/// the changed mtime already marks its content hash (and, for an archive,
+/// its member list) stale.
+fn forget_content(conn: &rusqlite::Connection, path: &str) {
+    let _ = conn.execute(
+        "DELETE FROM file_content WHERE file_id = (SELECT id FROM files_index WHERE path = ?1)",
+        params![path],
+    );
+    crate::content_index::schedule_pass();
+    crate::content_hash::schedule_pass();
+    crate::archive_index::schedule_pass();
+}
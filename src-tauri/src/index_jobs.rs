@@ -0,0 +1,706 @@
+// src-tauri/src/index_jobs.rs
+//
+// Persistent indexing jobs. Each background index run (`index_path_start`,
+// `index_all_drives_start`) is a row in `index_jobs`; the runner checkpoints
+// its counters and the pending directory stack every few seconds, so a job
+// interrupted by a crash or restart is resumed from its last checkpoint at
+// startup instead of rescanning from the top. Finished, failed and cancelled
+// jobs stay in the table as history.
+//
+// A running job can be paused (the walkers stop taking directories and the
+// writer checkpoints and waits; a job paused across a restart stays paused
+// until resumed), resumed or cancelled. An optional throttle caps the walk rate so
+// indexing does not starve interactive disk I/O.
+use crate::fs_ops::IndexJobState;
+use crate::indexer::WalkStats;
+use once_cell::sync::Lazy;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+const CHECKPOINT_EVERY: Duration = Duration::from_secs(2);
+const THROTTLE_KEY: &str = "index.throttle";
+/// How often a running job re-reads the throttle setting.
+const THROTTLE_RELOAD_EVERY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Run,
+    Pause,
+    Cancel,
+}
+
+/// Pause/cancel signal shared between the commands and a job's runner.
+struct JobControl {
+    state: Mutex<Control>,
+    changed: Condvar,
+}
+
+impl JobControl {
+    fn get(&self) -> Control {
+        *self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn set(&self, c: Control) {
+        *self.state.lock().unwrap_or_else(|e| e.into_inner()) = c;
+        self.changed.notify_all();
+    }
+
+    /// Block while paused; returns how long it waited.
+    fn wait_while_paused(&self) -> Duration {
+        let started = Instant::now();
+        let mut st = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        while *st == Control::Pause {
+            st = self.changed.wait(st).unwrap_or_else(|e| e.into_inner());
+        }
+        started.elapsed()
+    }
+}
+
+/// Controls of jobs whose runner is alive in this process.
+static CONTROLS: Lazy<Mutex<HashMap<String, Arc<JobControl>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Removes the job's control when its runner exits, however it exits.
+struct ControlGuard(String);
+
+impl Drop for ControlGuard {
+    fn drop(&mut self) {
+        if let Ok(mut m) = CONTROLS.lock() {
+            m.remove(&self.0);
+        }
+    }
+}
+
+fn register_control(id: &str) -> (Arc<JobControl>, ControlGuard) {
+    let control = Arc::new(JobControl {
+        state: Mutex::new(Control::Run),
+        changed: Condvar::new(),
+    });
+    if let Ok(mut m) = CONTROLS.lock() {
+        m.insert(id.to_string(), control.clone());
+    }
+    (control, ControlGuard(id.to_string()))
+}
+
+fn live_control(id: &str) -> Option<Arc<JobControl>> {
+    CONTROLS.lock().ok().and_then(|m| m.get(id).cloned())
+}
+
+/// Roots of the jobs whose runner is alive in this process.
+pub(crate) fn live_roots(conn: &Connection) -> Result<Vec<PathBuf>, String> {
+    let ids: Vec<String> = match CONTROLS.lock() {
+        Ok(m) => m.keys().cloned().collect(),
+        Err(_) => return Err("index job registry poisoned".into()),
+    };
+    let mut roots = Vec::new();
+    for id in ids {
+        let raw: Option<String> = conn
+            .query_row("SELECT roots FROM index_jobs WHERE id = ?1", params![id], |r| r.get(0))
+            .optional()
+            .map_err(|e| e.to_string())?;
+        roots.extend(raw.as_deref().map(paths_from_json).unwrap_or_default());
+    }
+    Ok(roots)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndexThrottle {
+    /// Upper bound on entries stat'ed per second per job; 0 = unlimited.
+    #[serde(default)]
+    pub max_entries_per_sec: u32,
+}
+
+pub fn load_throttle(conn: &Connection) -> IndexThrottle {
+    crate::db::get_setting(conn, THROTTLE_KEY)
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn ensure_tables(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS index_jobs (
+            id TEXT PRIMARY KEY,
+            kind TEXT NOT NULL,
+            roots TEXT NOT NULL,
+            status TEXT NOT NULL,
+            processed INTEGER NOT NULL DEFAULT 0,
+            added INTEGER NOT NULL DEFAULT 0,
+            updated INTEGER NOT NULL DEFAULT 0,
+            unchanged INTEGER NOT NULL DEFAULT 0,
+            removed INTEGER NOT NULL DEFAULT 0,
+            last_path TEXT,
+            pending TEXT,
+            error TEXT,
+            started_by INTEGER,
+            started_by_username TEXT,
+            started_at INTEGER NOT NULL,
+            checkpoint_at INTEGER,
+            finished_at INTEGER,
+            active_ms INTEGER NOT NULL DEFAULT 0,
+            resumes INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE INDEX IF NOT EXISTS idx_index_jobs_status ON index_jobs(status);
+        CREATE INDEX IF NOT EXISTS idx_index_jobs_started ON index_jobs(started_at);",
+    )
+    .map_err(|e| format!("ensure index_jobs: {}", e))
+}
+
+fn paths_to_json(paths: &[PathBuf]) -> String {
+    let v: Vec<String> = paths.iter().map(|p| p.to_string_lossy().to_string()).collect();
+    serde_json::to_string(&v).unwrap_or_else(|_| "[]".into())
+}
+
+fn paths_from_json(raw: &str) -> Vec<PathBuf> {
+    serde_json::from_str::<Vec<String>>(raw)
+        .unwrap_or_default()
+        .into_iter()
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Record a new job; the caller then starts it with `spawn_runner`.
+pub fn create(
+    conn: &Connection,
+    kind: &str,
+    roots: &[PathBuf],
+    started_by: Option<i64>,
+    started_by_username: &str,
+) -> Result<String, String> {
+    ensure_tables(conn)?;
+    let id = Uuid::new_v4().to_string();
+    let roots_json = paths_to_json(roots);
+    conn.execute(
+        "INSERT INTO index_jobs (id, kind, roots, status, pending, started_by, started_by_username, started_at)
+         VALUES (?1, ?2, ?3, 'running', ?3, ?4, ?5, ?6)",
+        params![
+            id,
+            kind,
+            roots_json,
+            started_by,
+            started_by_username,
+            chrono::Utc::now().timestamp()
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+fn checkpoint(
+    conn: &Connection,
+    id: &str,
+    stats: &WalkStats,
+    last_path: Option<&str>,
+    pending: &[PathBuf],
+    active_ms: i64,
+) -> Result<(), String> {
+    conn.execute(
+        "UPDATE index_jobs
+         SET processed = ?2, added = ?3, updated = ?4, unchanged = ?5, removed = ?6,
+             last_path = ?7, pending = ?8, checkpoint_at = ?9, active_ms = ?10
+         WHERE id = ?1",
+        params![
+            id,
+            stats.visited as i64,
+            stats.added as i64,
+            stats.updated as i64,
+            stats.unchanged as i64,
+            stats.removed as i64,
+            last_path,
+            paths_to_json(pending),
+            chrono::Utc::now().timestamp(),
+            active_ms
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn finish(
+    conn: &Connection,
+    id: &str,
+    status: &str,
+    stats: &WalkStats,
+    error: Option<&str>,
+    active_ms: i64,
+) -> Result<(), String> {
+    let now = chrono::Utc::now().timestamp();
+    conn.execute(
+        "UPDATE index_jobs
+         SET status = ?2, processed = ?3, added = ?4, updated = ?5, unchanged = ?6, removed = ?7,
+             error = ?8, pending = NULL, checkpoint_at = ?9, finished_at = ?9, active_ms = ?10
+         WHERE id = ?1",
+        params![
+            id,
+            status,
+            stats.visited as i64,
+            stats.added as i64,
+            stats.updated as i64,
+            stats.unchanged as i64,
+            stats.removed as i64,
+            error,
+            now,
+            active_ms
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn set_status(conn: &Connection, id: &str, status: &str) -> Result<(), String> {
+    conn.execute(
+        "UPDATE index_jobs SET status = ?2 WHERE id = ?1",
+        params![id, status],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Current state of a job as the frontend polls it.
+pub fn state(conn: &Connection, id: &str) -> Result<Option<IndexJobState>, String> {
+    ensure_tables(conn)?;
+    conn.query_row(
+        "SELECT status, processed, added, updated, unchanged, removed, last_path, error
+         FROM index_jobs WHERE id = ?1",
+        params![id],
+        |r| {
+            let status: String = r.get(0)?;
+            let stats = WalkStats {
+                visited: r.get::<_, i64>(1)? as usize,
+                added: r.get::<_, i64>(2)? as usize,
+                updated: r.get::<_, i64>(3)? as usize,
+                unchanged: r.get::<_, i64>(4)? as usize,
+                removed: r.get::<_, i64>(5)? as usize,
+            };
+            let last_path: Option<String> = r.get(6)?;
+            let error: Option<String> = r.get(7)?;
+            Ok(match status.as_str() {
+                "finished" => IndexJobState::Finished {
+                    processed: stats.visited,
+                    stats,
+                },
+                "failed" => IndexJobState::Failed {
+                    message: error.unwrap_or_default(),
+                },
+                "cancelled" => IndexJobState::Cancelled {
+                    processed: stats.visited,
+                    stats,
+                },
+                "paused" => IndexJobState::Paused {
+                    processed: stats.visited,
+                    last_path,
+                },
+                _ => IndexJobState::Running {
+                    processed: stats.visited,
+                    last_path,
+                },
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Run (or resume) job `id` on a background thread. `app` is needed by
+/// all-drive jobs, which start the filesystem watcher once they finish.
+pub fn spawn_runner(id: String, app: Option<AppHandle>) {
+    std::thread::spawn(move || {
+        if let Err(e) = run(&id, app) {
+            mark_failed(&id, &e);
+        }
+    });
+}
+
+/// Run job `id` on the calling thread and return its final state (the
+/// scheduler waits for its rescans so it can keep conflicting work apart).
+pub(crate) fn run_blocking(id: &str) -> Result<Option<IndexJobState>, String> {
+    if let Err(e) = run(id, None) {
+        mark_failed(id, &e);
+    }
+    let conn = crate::db::open_connection()?;
+    state(&conn, id)
+}
+
+fn mark_failed(id: &str, e: &str) {
+    eprintln!("[index_jobs] job {} failed: {}", id, e);
+    if let Ok(conn) = crate::db::open_connection() {
+        let _ = conn.execute(
+            "UPDATE index_jobs SET status = 'failed', error = ?2, finished_at = ?3
+             WHERE id = ?1 AND status = 'running'",
+            params![id, e, chrono::Utc::now().timestamp()],
+        );
+    }
+}
+
+fn run(id: &str, app: Option<AppHandle>) -> Result<(), String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("worker db open failed: {}", e))?;
+    crate::fs_ops::ensure_files_index_tables(&conn)
+        .map_err(|e| format!("ensure tables failed: {}", e))?;
+    ensure_tables(&conn)?;
+
+    let (kind, roots, pending, stats, active_ms): (String, String, Option<String>, WalkStats, i64) =
+        conn.query_row(
+            "SELECT kind, roots, pending, processed, added, updated, unchanged, removed, active_ms
+             FROM index_jobs WHERE id = ?1",
+            params![id],
+            |r| {
+                Ok((
+                    r.get(0)?,
+                    r.get(1)?,
+                    r.get(2)?,
+                    WalkStats {
+                        visited: r.get::<_, i64>(3)? as usize,
+                        added: r.get::<_, i64>(4)? as usize,
+                        updated: r.get::<_, i64>(5)? as usize,
+                        unchanged: r.get::<_, i64>(6)? as usize,
+                        removed: r.get::<_, i64>(7)? as usize,
+                    },
+                    r.get(8)?,
+                ))
+            },
+        )
+        .map_err(|e| format!("load job {}: {}", id, e))?;
+    let stack = paths_from_json(pending.as_deref().unwrap_or(&roots));
+    // pick up volumes mounted or moved since the last walk
+    crate::volumes::refresh(&conn)?;
+
+    let (control, _guard) = register_control(id);
+    let mut throttle_loaded: Option<Instant> = None;
+
+    let started = Instant::now();
+    let mut paused_for = Duration::ZERO;
+    let mut last_checkpoint = Instant::now();
+    let mut checkpoint_err: Option<String> = None;
+    let rules = crate::index_rules::IndexRules::load(&conn, &paths_from_json(&roots))?;
+    let walked = crate::indexer::walk_roots(&conn, stack, stats, &rules, |progress| {
+        let stats = progress.stats;
+        let pending = || progress.queue.pending();
+        let last_path = progress.last_dir.map(|d| d.to_string_lossy().to_string());
+
+        match control.get() {
+            Control::Run => {}
+            Control::Cancel => return ControlFlow::Break(()),
+            Control::Pause => {
+                let active = active_ms + (started.elapsed() - paused_for).as_millis() as i64;
+                if let Err(e) = checkpoint(&conn, id, stats, last_path.as_deref(), &pending(), active) {
+                    checkpoint_err.get_or_insert(e);
+                }
+                let _ = set_status(&conn, id, "paused");
+                progress.queue.hold(true);
+                paused_for += control.wait_while_paused();
+                progress.queue.hold(false);
+                if control.get() == Control::Cancel {
+                    return ControlFlow::Break(());
+                }
+                let _ = set_status(&conn, id, "running");
+            }
+        }
+
+        if !matches!(throttle_loaded, Some(t) if t.elapsed() <= THROTTLE_RELOAD_EVERY) {
+            progress
+                .queue
+                .set_rate_limit(load_throttle(&conn).max_entries_per_sec);
+            throttle_loaded = Some(Instant::now());
+        }
+
+        if last_checkpoint.elapsed() >= CHECKPOINT_EVERY {
+            last_checkpoint = Instant::now();
+            let active = active_ms + (started.elapsed() - paused_for).as_millis() as i64;
+            if let Err(e) = checkpoint(&conn, id, stats, last_path.as_deref(), &pending(), active) {
+                checkpoint_err.get_or_insert(e);
+            }
+        }
+        ControlFlow::Continue(())
+    });
+    if let Some(e) = checkpoint_err {
+        eprintln!("[index_jobs] checkpoint for {} failed: {}", id, e);
+    }
+    let elapsed = active_ms + (started.elapsed() - paused_for).as_millis() as i64;
+
+    let stats = match walked {
+        Ok(stats) => stats,
+        Err(e) => {
+            // keep the counters from the last checkpoint
+            conn.execute(
+                "UPDATE index_jobs SET status = 'failed', error = ?2, pending = NULL,
+                   finished_at = ?3, active_ms = ?4
+                 WHERE id = ?1",
+                params![id, e, chrono::Utc::now().timestamp(), elapsed],
+            )
+            .map_err(|e| e.to_string())?;
+            return Ok(());
+        }
+    };
+    let cancelled = control.get() == Control::Cancel;
+    finish(
+        &conn,
+        id,
+        if cancelled { "cancelled" } else { "finished" },
+        &stats,
+        None,
+        elapsed,
+    )?;
+
+    // extract document text and hash content for the new rows in the background
+    crate::content_index::schedule_pass();
+    crate::content_hash::schedule_pass();
+    crate::archive_index::schedule_pass();
+
+    if kind == "all_drives" && !cancelled {
+        if let Some(app) = app {
+            start_watcher(app, paths_from_json(&roots));
+        }
+    }
+    Ok(())
+}
+
+fn start_watcher(app: AppHandle, roots: Vec<PathBuf>) {
+    let db_path = match crate::db::get_db_path() {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("[watcher] db path error: {}", e);
+            return;
+        }
+    };
+    std::thread::spawn(move || {
+        if let Err(e) = crate::fs_watch::start_fs_watcher(roots, db_path, app) {
+            eprintln!("[fs_watch] error: {}", e);
+        }
+    });
+}
+
+/// Resume jobs left `running` by a previous process (crash or restart).
+pub fn resume_interrupted(app: AppHandle) {
+    let conn = match crate::db::open_connection() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("[index_jobs] resume: db open failed: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = ensure_tables(&conn) {
+        eprintln!("[index_jobs] resume: {}", e);
+        return;
+    }
+    let ids: Vec<String> = match conn
+        .prepare("SELECT id FROM index_jobs WHERE status = 'running' ORDER BY started_at")
+        .and_then(|mut s| {
+            s.query_map([], |r| r.get(0))
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        }) {
+        Ok(ids) => ids,
+        Err(e) => {
+            eprintln!("[index_jobs] resume: {}", e);
+            return;
+        }
+    };
+    for id in ids {
+        let _ = conn.execute(
+            "UPDATE index_jobs SET resumes = resumes + 1 WHERE id = ?1",
+            params![id],
+        );
+        eprintln!("[index_jobs] resuming interrupted job {}", id);
+        spawn_runner(id, Some(app.clone()));
+    }
+}
+
+#[derive(Serialize)]
+pub struct IndexJobRecord {
+    pub id: String,
+    pub kind: String,
+    pub roots: Vec<String>,
+    pub status: String,
+    pub processed: i64,
+    pub added: i64,
+    pub updated: i64,
+    pub unchanged: i64,
+    pub removed: i64,
+    pub last_path: Option<String>,
+    pub error: Option<String>,
+    pub started_by_username: Option<String>,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+    /// Wall-clock seconds from start to finish (includes any downtime).
+    pub duration_secs: Option<i64>,
+    /// Time actually spent walking, across resumes.
+    pub active_ms: i64,
+    pub resumes: i64,
+}
+
+/// Open a connection and check that the session belongs to an admin.
+fn require_admin(session_token: &str) -> Result<(Connection, i64, String), String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("db open: {}", e))?;
+    let uid = crate::session::validate_session(&conn, session_token)
+        .map_err(|e| format!("session validation: {}", e))?
+        .ok_or_else(|| "invalid session".to_string())?;
+    let user = crate::db::get_user_by_id_row(&conn, uid)
+        .map_err(|e| format!("db user lookup: {}", e))?
+        .ok_or_else(|| "user not found".to_string())?;
+    if user.role != "admin" {
+        return Err("admin role required".into());
+    }
+    Ok((conn, uid, user.username))
+}
+
+fn job_status(conn: &Connection, id: &str) -> Result<String, String> {
+    ensure_tables(conn)?;
+    conn.query_row(
+        "SELECT status FROM index_jobs WHERE id = ?1",
+        params![id],
+        |r| r.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("index job {} not found", id))
+}
+
+fn audit_job_action(conn: &Connection, uid: i64, username: &str, action: &str, id: &str) {
+    let _ = crate::db::insert_audit_log(
+        conn,
+        Some(uid),
+        Some(username),
+        action,
+        None,
+        Some(&format!("job_id:{}", id)),
+    );
+}
+
+/// Admin: pause a running job after the directory it is currently reading.
+#[tauri::command]
+pub fn index_job_pause(session_token: String, job_id: String) -> Result<IndexJobState, String> {
+    let (conn, uid, username) = require_admin(&session_token)?;
+    let control = live_control(&job_id)
+        .filter(|c| c.get() == Control::Run)
+        .ok_or_else(|| format!("index job {} is not running", job_id))?;
+    control.set(Control::Pause);
+    // the runner flips the row to 'paused' at its next directory; report it now
+    set_status(&conn, &job_id, "paused")?;
+    audit_job_action(&conn, uid, &username, "index_job_pause", &job_id);
+    state(&conn, &job_id)?.ok_or_else(|| format!("index job {} not found", job_id))
+}
+
+/// Admin: resume a paused job, including one paused before a restart.
+#[tauri::command]
+pub fn index_job_resume(
+    app: AppHandle,
+    session_token: String,
+    job_id: String,
+) -> Result<IndexJobState, String> {
+    let (conn, uid, username) = require_admin(&session_token)?;
+    if job_status(&conn, &job_id)? != "paused" {
+        return Err(format!("index job {} is not paused", job_id));
+    }
+    set_status(&conn, &job_id, "running")?;
+    match live_control(&job_id) {
+        Some(control) => control.set(Control::Run),
+        // no runner in this process: continue from the saved checkpoint
+        None => spawn_runner(job_id.clone(), Some(app)),
+    }
+    audit_job_action(&conn, uid, &username, "index_job_resume", &job_id);
+    state(&conn, &job_id)?.ok_or_else(|| format!("index job {} not found", job_id))
+}
+
+/// Admin: stop a running or paused job. Rows already written stay indexed and
+/// the job ends as `Cancelled` with the counts reached so far.
+#[tauri::command]
+pub fn index_job_cancel(session_token: String, job_id: String) -> Result<IndexJobState, String> {
+    let (conn, uid, username) = require_admin(&session_token)?;
+    let status = job_status(&conn, &job_id)?;
+    if status != "running" && status != "paused" {
+        return Err(format!("index job {} is already {}", job_id, status));
+    }
+    match live_control(&job_id) {
+        // the runner records the final counts itself
+        Some(control) => control.set(Control::Cancel),
+        None => {
+            conn.execute(
+                "UPDATE index_jobs SET status = 'cancelled', pending = NULL, finished_at = ?2
+                 WHERE id = ?1",
+                params![job_id, chrono::Utc::now().timestamp()],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    audit_job_action(&conn, uid, &username, "index_job_cancel", &job_id);
+    state(&conn, &job_id)?.ok_or_else(|| format!("index job {} not found", job_id))
+}
+
+#[tauri::command]
+pub fn admin_get_index_throttle(session_token: String) -> Result<IndexThrottle, String> {
+    let (conn, _, _) = require_admin(&session_token)?;
+    Ok(load_throttle(&conn))
+}
+
+/// Admin: cap how fast index jobs walk the disk (0 = unlimited). Running jobs
+/// pick up the new value within a few seconds.
+#[tauri::command]
+pub fn admin_set_index_throttle(
+    session_token: String,
+    throttle: IndexThrottle,
+) -> Result<IndexThrottle, String> {
+    let (conn, uid, username) = require_admin(&session_token)?;
+    let raw = serde_json::to_string(&throttle).map_err(|e| e.to_string())?;
+    crate::db::set_setting(&conn, THROTTLE_KEY, &raw)?;
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(uid),
+        Some(&username),
+        "admin_set_index_throttle",
+        None,
+        Some(&format!("max_entries_per_sec={}", throttle.max_entries_per_sec)),
+    );
+    Ok(throttle)
+}
+
+/// Admin: recent index jobs, newest first (running, paused and finished ones).
+#[tauri::command]
+pub fn list_index_jobs(
+    session_token: String,
+    limit: Option<i64>,
+) -> Result<Vec<IndexJobRecord>, String> {
+    let (conn, _, _) = require_admin(&session_token)?;
+    ensure_tables(&conn)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, kind, roots, status, processed, added, updated, unchanged, removed,
+                    last_path, error, started_by_username, started_at, finished_at, active_ms, resumes
+             FROM index_jobs ORDER BY started_at DESC LIMIT ?1",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![limit.unwrap_or(50).clamp(1, 1000)], |r| {
+            let roots: String = r.get(2)?;
+            let started_at: i64 = r.get(12)?;
+            let finished_at: Option<i64> = r.get(13)?;
+            Ok(IndexJobRecord {
+                id: r.get(0)?,
+                kind: r.get(1)?,
+                roots: serde_json::from_str(&roots).unwrap_or_default(),
+                status: r.get(3)?,
+                processed: r.get(4)?,
+                added: r.get(5)?,
+                updated: r.get(6)?,
+                unchanged: r.get(7)?,
+                removed: r.get(8)?,
+                last_path: r.get(9)?,
+                error: r.get(10)?,
+                started_by_username: r.get(11)?,
+                started_at,
+                finished_at,
+                duration_secs: finished_at.map(|f| f - started_at),
+                active_ms: r.get(14)?,
+                resumes: r.get(15)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
@@ -0,0 +1,797 @@
+// src-tauri/src/index_rules.rs
+//
+// Admin-managed include/exclude rules for the indexer, on top of the
+// built-in exclusions in `fs_ops::is_excluded_path`.
+//
+// A rule is a glob or a regex matched against the full path (with `/`
+// separators; case-insensitive on Windows), optionally limited to one root.
+// A rule that matches a directory covers everything below it. For a given
+// path the nearest match wins: the path's own rules first, then its parent's
+// and so on up. At one level, root-scoped rules beat global ones and include
+// beats exclude. Only when no rule matches do the built-in exclusions apply,
+// so an include rule can bring back e.g. one project's `node_modules`.
+//
+// The walk still enters an excluded directory when an include rule could
+// match below it, judged by the rule's root and the literal start of its
+// pattern (up to the first wildcard; for a regex only when anchored with
+// `^`). An include pattern without such a start, e.g. `**/keep/**`, makes the
+// walk enter every excluded directory.
+//
+// Limits (maximum depth, maximum file size) are set globally and can be
+// overridden per root; they apply even to force-included paths. A global
+// depth counts from the walk root, a per-root depth from that root.
+//
+// Walks (`indexer::walk_roots`) and the filesystem watcher both consult a
+// loaded `IndexRules`. Rows that a new rule excludes are dropped on the next
+// rescan of their directory.
+use globset::GlobBuilder;
+use regex::RegexBuilder;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{command, AppHandle};
+
+/// Bumped on every rule or limit change so long-lived holders (the watcher)
+/// know to reload.
+static VERSION: AtomicU64 = AtomicU64::new(0);
+
+pub fn version() -> u64 {
+    VERSION.load(Ordering::SeqCst)
+}
+
+pub(crate) fn ensure_tables(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS index_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            action TEXT NOT NULL,
+            syntax TEXT NOT NULL,
+            pattern TEXT NOT NULL,
+            root TEXT,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            note TEXT,
+            created_by_username TEXT,
+            created_at INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS index_limits (
+            root TEXT PRIMARY KEY,
+            max_depth INTEGER,
+            max_file_size INTEGER,
+            updated_by_username TEXT,
+            updated_at INTEGER NOT NULL
+        );",
+    )
+    .map_err(|e| format!("ensure index_rules: {}", e))
+}
+
+/// Path form used for all matching.
+fn norm(path: &str) -> String {
+    let mut s = path.replace('\\', "/");
+    while s.len() > 1 && s.ends_with('/') {
+        s.pop();
+    }
+    if s == "/" {
+        s.clear();
+    }
+    if cfg!(windows) {
+        s = s.to_lowercase();
+    }
+    s
+}
+
+/// `path` is `root` or below it (both normalized; "" is the filesystem root).
+fn under(path: &str, root: &str) -> bool {
+    root.is_empty()
+        || path == root
+        || (path.starts_with(root) && path[root.len()..].starts_with('/'))
+}
+
+/// Path components between `root` and `path`.
+fn depth_below(path: &str, root: &str) -> u32 {
+    path[root.len()..].split('/').filter(|c| !c.is_empty()).count() as u32
+}
+
+fn parent(path: &str) -> Option<&str> {
+    path.rfind('/').map(|i| &path[..i])
+}
+
+enum Matcher {
+    Glob(globset::GlobMatcher),
+    Regex(regex::Regex),
+}
+
+fn compile(syntax: &str, pattern: &str) -> Result<Matcher, String> {
+    match syntax {
+        "glob" => GlobBuilder::new(&pattern.replace('\\', "/"))
+            .case_insensitive(cfg!(windows))
+            .build()
+            .map(|g| Matcher::Glob(g.compile_matcher()))
+            .map_err(|e| format!("invalid glob: {}", e)),
+        "regex" => RegexBuilder::new(pattern)
+            .case_insensitive(cfg!(windows))
+            .size_limit(1 << 20)
+            .build()
+            .map(Matcher::Regex)
+            .map_err(|e| format!("invalid regex: {}", e)),
+        other => Err(format!("unknown syntax '{}' (glob|regex)", other)),
+    }
+}
+
+const GLOB_META: &[char] = &['*', '?', '[', '{'];
+const REGEX_META: &[char] = &[
+    '\\', '.', '+', '*', '?', '(', ')', '|', '[', ']', '{', '}', '^', '$',
+];
+
+/// Literal text every match of the pattern starts with ("" when unknown).
+fn literal_prefix(syntax: &str, pattern: &str) -> String {
+    let (pattern, meta) = match syntax {
+        "glob" => (pattern.replace('\\', "/"), GLOB_META),
+        _ => match pattern.strip_prefix('^') {
+            Some(rest) => (rest.to_string(), REGEX_META),
+            None => return String::new(),
+        },
+    };
+    let end = pattern.find(meta).unwrap_or(pattern.len());
+    let prefix = &pattern[..end];
+    if cfg!(windows) {
+        prefix.to_lowercase()
+    } else {
+        prefix.to_string()
+    }
+}
+
+struct Rule {
+    id: i64,
+    include: bool,
+    root: Option<String>,
+    /// See `literal_prefix`.
+    prefix: String,
+    matcher: Matcher,
+}
+
+impl Rule {
+    fn matches(&self, path: &str) -> bool {
+        if let Some(root) = &self.root {
+            if !under(path, root) {
+                return false;
+            }
+        }
+        match &self.matcher {
+            Matcher::Glob(g) => g.is_match(path),
+            Matcher::Regex(r) => r.is_match(path),
+        }
+    }
+
+    /// Whether the rule could match `dir` (normalized) or a path below it.
+    fn may_match_below(&self, dir: &str) -> bool {
+        if let Some(root) = &self.root {
+            if !under(dir, root) && !under(root, dir) {
+                return false;
+            }
+        }
+        let dir = format!("{}/", dir);
+        dir.starts_with(&self.prefix) || self.prefix.starts_with(&dir)
+    }
+}
+
+#[derive(Default)]
+struct Limits {
+    max_depth: Option<u32>,
+    max_file_size: Option<i64>,
+}
+
+/// Why a path is or is not indexed.
+#[derive(Debug, Clone, Copy)]
+pub enum Reason {
+    Rule { id: i64, include: bool },
+    Builtin,
+    Depth(u32),
+    FileSize(i64),
+    Default,
+}
+
+impl std::fmt::Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reason::Rule { id, include: true } => write!(f, "included by rule #{}", id),
+            Reason::Rule { id, include: false } => write!(f, "excluded by rule #{}", id),
+            Reason::Builtin => write!(f, "built-in exclusion"),
+            Reason::Depth(d) => write!(f, "deeper than max depth {}", d),
+            Reason::FileSize(n) => write!(f, "larger than max file size {} bytes", n),
+            Reason::Default => write!(f, "no rule matched"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Verdict {
+    pub indexed: bool,
+    /// For directories: walk into it. Also set on an excluded directory that
+    /// an include rule may match below.
+    pub descend: bool,
+    pub reason: Reason,
+}
+
+/// Rules and limits as loaded for one walk (or one watcher session).
+#[derive(Default)]
+pub struct IndexRules {
+    /// Most specific first: longer root, then include before exclude.
+    rules: Vec<Rule>,
+    default_limits: Limits,
+    /// (normalized root, limits), longest root first.
+    root_limits: Vec<(String, Limits)>,
+    /// Walk roots, longest first; the global depth limit counts from these.
+    walk_roots: Vec<String>,
+}
+
+impl IndexRules {
+    /// Load the enabled rules and limits for a walk over `roots`. A rule whose
+    /// pattern no longer compiles is skipped with a log line.
+    pub fn load(conn: &Connection, roots: &[PathBuf]) -> Result<Self, String> {
+        ensure_tables(conn)?;
+        let mut rules = Vec::new();
+        {
+            let mut stmt = conn
+                .prepare("SELECT id, action, syntax, pattern, root FROM index_rules WHERE enabled = 1")
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map([], |r| {
+                    Ok((
+                        r.get::<_, i64>(0)?,
+                        r.get::<_, String>(1)?,
+                        r.get::<_, String>(2)?,
+                        r.get::<_, String>(3)?,
+                        r.get::<_, Option<String>>(4)?,
+                    ))
+                })
+                .map_err(|e| e.to_string())?;
+            for row in rows {
+                let (id, action, syntax, pattern, root) = row.map_err(|e| e.to_string())?;
+                match compile(&syntax, &pattern) {
+                    Ok(matcher) => rules.push(Rule {
+                        id,
+                        include: action == "include",
+                        root: root.as_deref().map(norm),
+                        prefix: literal_prefix(&syntax, &pattern),
+                        matcher,
+                    }),
+                    Err(e) => eprintln!("[index_rules] skipping rule #{}: {}", id, e),
+                }
+            }
+        }
+        rules.sort_by_key(|r| {
+            (
+                std::cmp::Reverse(r.root.as_ref().map(|s| s.len() + 1).unwrap_or(0)),
+                !r.include,
+            )
+        });
+
+        let mut default_limits = Limits::default();
+        let mut root_limits = Vec::new();
+        {
+            let mut stmt = conn
+                .prepare("SELECT root, max_depth, max_file_size FROM index_limits")
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map([], |r| {
+                    Ok((
+                        r.get::<_, String>(0)?,
+                        Limits {
+                            max_depth: r.get::<_, Option<i64>>(1)?.map(|d| d.max(0) as u32),
+                            max_file_size: r.get(2)?,
+                        },
+                    ))
+                })
+                .map_err(|e| e.to_string())?;
+            for row in rows {
+                let (root, limits) = row.map_err(|e| e.to_string())?;
+                if root.is_empty() {
+                    default_limits = limits;
+                } else {
+                    root_limits.push((norm(&root), limits));
+                }
+            }
+        }
+        root_limits.sort_by_key(|(r, _)| std::cmp::Reverse(r.len()));
+
+        let mut walk_roots: Vec<String> = roots.iter().map(|p| norm(&p.to_string_lossy())).collect();
+        walk_roots.sort_by_key(|r| std::cmp::Reverse(r.len()));
+
+        Ok(IndexRules {
+            rules,
+            default_limits,
+            root_limits,
+            walk_roots,
+        })
+    }
+
+    fn is_plain(&self) -> bool {
+        self.rules.is_empty()
+            && self.root_limits.is_empty()
+            && self.default_limits.max_depth.is_none()
+            && self.default_limits.max_file_size.is_none()
+    }
+
+    /// Rule decision for exactly this (normalized) path.
+    fn own_match(&self, path: &str) -> Option<&Rule> {
+        self.rules.iter().find(|r| r.matches(path))
+    }
+
+    /// Decision a directory passes down to its entries: the nearest rule match
+    /// on `dir` or one of its ancestors. Compute once per directory and hand
+    /// it to `check_entry` for each child.
+    pub fn inherited(&self, dir: &str) -> Option<Reason> {
+        if self.rules.is_empty() {
+            return None;
+        }
+        let mut cur = norm(dir);
+        loop {
+            if let Some(rule) = self.own_match(&cur) {
+                return Some(Reason::Rule {
+                    id: rule.id,
+                    include: rule.include,
+                });
+            }
+            match parent(&cur) {
+                Some(p) => cur = p.to_string(),
+                None => return None,
+            }
+        }
+    }
+
+    /// Decide one entry of a directory whose `inherited` decision is known.
+    pub fn check_entry(
+        &self,
+        path: &str,
+        is_dir: bool,
+        size: Option<i64>,
+        inherited: Option<Reason>,
+    ) -> Verdict {
+        if self.is_plain() {
+            let excluded = crate::fs_ops::is_excluded_path(path);
+            return Verdict {
+                indexed: !excluded,
+                descend: is_dir && !excluded,
+                reason: if excluded { Reason::Builtin } else { Reason::Default },
+            };
+        }
+        let p = norm(path);
+
+        let reason = match self.own_match(&p) {
+            Some(rule) => Reason::Rule {
+                id: rule.id,
+                include: rule.include,
+            },
+            None => match inherited {
+                Some(r) => r,
+                None if crate::fs_ops::is_excluded_path(path) => Reason::Builtin,
+                None => Reason::Default,
+            },
+        };
+        let mut verdict = Verdict {
+            indexed: matches!(reason, Reason::Default | Reason::Rule { include: true, .. }),
+            descend: false,
+            reason,
+        };
+        if !verdict.indexed {
+            // a nested include rule only takes effect if the walk gets there
+            verdict.descend =
+                is_dir && self.rules.iter().any(|r| r.include && r.may_match_below(&p));
+            return verdict;
+        }
+
+        let scoped = self.root_limits.iter().find(|(root, _)| under(&p, root));
+        // depth: a per-root limit counts from that root, the default one from
+        // the walk root
+        let depth_limit = match scoped {
+            Some((root, l)) if l.max_depth.is_some() => l.max_depth.map(|m| (m, depth_below(&p, root))),
+            _ => self.default_limits.max_depth.and_then(|m| {
+                self.walk_roots
+                    .iter()
+                    .find(|root| under(&p, root))
+                    .map(|root| (m, depth_below(&p, root)))
+            }),
+        };
+        if let Some((max, depth)) = depth_limit {
+            if depth > max {
+                verdict.indexed = false;
+                verdict.reason = Reason::Depth(max);
+                return verdict;
+            }
+            verdict.descend = is_dir && depth < max;
+        } else {
+            verdict.descend = is_dir;
+        }
+
+        if !is_dir {
+            let max_size = scoped
+                .and_then(|(_, l)| l.max_file_size)
+                .or(self.default_limits.max_file_size);
+            if let (Some(max), Some(size)) = (max_size, size) {
+                if size > max {
+                    verdict.indexed = false;
+                    verdict.reason = Reason::FileSize(max);
+                }
+            }
+        }
+        verdict
+    }
+
+    /// Full decision for a single path (watcher events, admin preview).
+    pub fn check(&self, path: &str, is_dir: bool, size: Option<i64>) -> Verdict {
+        let inherited = parent(&norm(path)).and_then(|dir| self.inherited(dir));
+        self.check_entry(path, is_dir, size, inherited)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndexRule {
+    pub id: i64,
+    pub action: String,
+    pub syntax: String,
+    pub pattern: String,
+    pub root: Option<String>,
+    pub enabled: bool,
+    pub note: Option<String>,
+    pub created_by_username: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewIndexRule {
+    /// "include" | "exclude"
+    pub action: String,
+    /// "glob" | "regex"
+    pub syntax: String,
+    pub pattern: String,
+    /// Limit the rule to paths under this root.
+    pub root: Option<String>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndexLimit {
+    /// None = default for all roots.
+    pub root: Option<String>,
+    pub max_depth: Option<i64>,
+    pub max_file_size: Option<i64>,
+    pub updated_by_username: Option<String>,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndexRulesView {
+    pub rules: Vec<IndexRule>,
+    pub limits: Vec<IndexLimit>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndexPathCheck {
+    pub path: String,
+    pub indexed: bool,
+    pub descend: bool,
+    pub reason: String,
+}
+
+fn view(conn: &Connection) -> Result<IndexRulesView, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, action, syntax, pattern, root, enabled, note, created_by_username, created_at
+             FROM index_rules ORDER BY id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rules = stmt
+        .query_map([], |r| {
+            Ok(IndexRule {
+                id: r.get(0)?,
+                action: r.get(1)?,
+                syntax: r.get(2)?,
+                pattern: r.get(3)?,
+                root: r.get(4)?,
+                enabled: r.get::<_, i64>(5)? != 0,
+                note: r.get(6)?,
+                created_by_username: r.get(7)?,
+                created_at: r.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT root, max_depth, max_file_size, updated_by_username, updated_at
+             FROM index_limits ORDER BY root",
+        )
+        .map_err(|e| e.to_string())?;
+    let limits = stmt
+        .query_map([], |r| {
+            let root: String = r.get(0)?;
+            Ok(IndexLimit {
+                root: if root.is_empty() { None } else { Some(root) },
+                max_depth: r.get(1)?,
+                max_file_size: r.get(2)?,
+                updated_by_username: r.get(3)?,
+                updated_at: r.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(IndexRulesView { rules, limits })
+}
+
+fn open_admin(
+    session_token: Option<String>,
+    sessionToken: Option<String>,
+) -> Result<(Connection, i64, String), String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    ensure_tables(&conn)?;
+    Ok((conn, admin_id, admin_username))
+}
+
+#[command]
+pub fn admin_list_index_rules(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<IndexRulesView, String> {
+    let (conn, _, _) = open_admin(session_token, sessionToken)?;
+    view(&conn)
+}
+
+#[command]
+pub fn admin_add_index_rule(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    rule: NewIndexRule,
+) -> Result<IndexRulesView, String> {
+    let (conn, admin_id, admin_username) = open_admin(session_token, sessionToken)?;
+
+    if rule.action != "include" && rule.action != "exclude" {
+        return Err(format!("unknown action '{}' (include|exclude)", rule.action));
+    }
+    let pattern = rule.pattern.trim();
+    if pattern.is_empty() {
+        return Err("pattern is required".into());
+    }
+    compile(&rule.syntax, pattern)?;
+    let root = rule
+        .root
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+
+    conn.execute(
+        "INSERT INTO index_rules (action, syntax, pattern, root, enabled, note, created_by_username, created_at)
+         VALUES (?1, ?2, ?3, ?4, 1, ?5, ?6, ?7)",
+        params![
+            rule.action,
+            rule.syntax,
+            pattern,
+            root,
+            rule.note,
+            admin_username,
+            chrono::Utc::now().timestamp()
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    let id = conn.last_insert_rowid();
+    VERSION.fetch_add(1, Ordering::SeqCst);
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_add_index_rule",
+        None,
+        Some(&format!(
+            "id={} {} {} '{}' root={}",
+            id,
+            rule.action,
+            rule.syntax,
+            pattern,
+            root.unwrap_or("*")
+        )),
+    );
+    view(&conn)
+}
+
+#[command]
+pub fn admin_set_index_rule_enabled(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    id: i64,
+    enabled: bool,
+) -> Result<IndexRulesView, String> {
+    let (conn, admin_id, admin_username) = open_admin(session_token, sessionToken)?;
+    let n = conn
+        .execute(
+            "UPDATE index_rules SET enabled = ?2 WHERE id = ?1",
+            params![id, enabled as i64],
+        )
+        .map_err(|e| e.to_string())?;
+    if n == 0 {
+        return Err(format!("index rule {} not found", id));
+    }
+    VERSION.fetch_add(1, Ordering::SeqCst);
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_set_index_rule_enabled",
+        None,
+        Some(&format!("id={} enabled={}", id, enabled)),
+    );
+    view(&conn)
+}
+
+#[command]
+pub fn admin_delete_index_rule(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    id: i64,
+) -> Result<IndexRulesView, String> {
+    let (conn, admin_id, admin_username) = open_admin(session_token, sessionToken)?;
+    let removed: Option<(String, String, String)> = conn
+        .query_row(
+            "SELECT action, syntax, pattern FROM index_rules WHERE id = ?1",
+            params![id],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    let (action, syntax, pattern) = removed.ok_or_else(|| format!("index rule {} not found", id))?;
+    conn.execute("DELETE FROM index_rules WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    VERSION.fetch_add(1, Ordering::SeqCst);
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_delete_index_rule",
+        None,
+        Some(&format!("id={} {} {} '{}'", id, action, syntax, pattern)),
+    );
+    view(&conn)
+}
+
+/// Set the depth/size limits for `root` (None = the default for all roots).
+/// Clearing both limits of a root removes its override.
+#[command]
+pub fn admin_set_index_limits(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    root: Option<String>,
+    max_depth: Option<i64>,
+    max_file_size: Option<i64>,
+) -> Result<IndexRulesView, String> {
+    let (conn, admin_id, admin_username) = open_admin(session_token, sessionToken)?;
+    if max_depth.is_some_and(|d| d < 0) || max_file_size.is_some_and(|s| s < 0) {
+        return Err("limits must not be negative".into());
+    }
+    let key = root.as_deref().map(str::trim).unwrap_or("").to_string();
+
+    if max_depth.is_none() && max_file_size.is_none() {
+        conn.execute("DELETE FROM index_limits WHERE root = ?1", params![key])
+            .map_err(|e| e.to_string())?;
+    } else {
+        conn.execute(
+            "INSERT INTO index_limits (root, max_depth, max_file_size, updated_by_username, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(root) DO UPDATE SET
+               max_depth = excluded.max_depth,
+               max_file_size = excluded.max_file_size,
+               updated_by_username = excluded.updated_by_username,
+               updated_at = excluded.updated_at",
+            params![
+                key,
+                max_depth,
+                max_file_size,
+                admin_username,
+                chrono::Utc::now().timestamp()
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    VERSION.fetch_add(1, Ordering::SeqCst);
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_set_index_limits",
+        None,
+        Some(&format!(
+            "root={} max_depth={:?} max_file_size={:?}",
+            if key.is_empty() { "*" } else { &key },
+            max_depth,
+            max_file_size
+        )),
+    );
+    view(&conn)
+}
+
+/// Admin: show whether `path` would be indexed under the current rules, and
+/// why. Depth limits are evaluated against `root` when given.
+#[command]
+pub fn admin_check_index_path(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    path: String,
+    root: Option<String>,
+) -> Result<IndexPathCheck, String> {
+    let (conn, _, _) = open_admin(session_token, sessionToken)?;
+    let roots: Vec<PathBuf> = root.into_iter().map(PathBuf::from).collect();
+    let rules = IndexRules::load(&conn, &roots)?;
+    let meta = std::fs::symlink_metadata(&path).ok();
+    let is_dir = meta.as_ref().map(|m| m.is_dir()).unwrap_or(false);
+    let size = meta.as_ref().filter(|m| !m.is_dir()).map(|m| m.len() as i64);
+    let v = rules.check(&path, is_dir, size);
+    Ok(IndexPathCheck {
+        path,
+        indexed: v.indexed,
+        descend: v.descend,
+        reason: v.reason.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(conn: &Connection, rows: &[(&str, &str, &str)]) -> IndexRules {
+        ensure_tables(conn).unwrap();
+        for (action, syntax, pattern) in rows {
+            conn.execute(
+                "INSERT INTO index_rules (action, syntax, pattern, created_at) VALUES (?1, ?2, ?3, 0)",
+                params![action, syntax, pattern],
+            )
+            .unwrap();
+        }
+        IndexRules::load(conn, &[PathBuf::from("/data")]).unwrap()
+    }
+
+    #[test]
+    fn include_below_an_excluded_directory_is_reached() {
+        let conn = Connection::open_in_memory().unwrap();
+        let rules = rules(
+            &conn,
+            &[
+                ("exclude", "glob", "/data/build"),
+                ("include", "glob", "/data/build/reports/**"),
+                ("include", "regex", "^/data/proj/node_modules/keep(/|$)"),
+            ],
+        );
+
+        // excluded by rule: walked towards the include, not elsewhere
+        let build = rules.check("/data/build", true, None);
+        assert!(!build.indexed && build.descend);
+        let reports = rules.check("/data/build/reports", true, None);
+        assert!(!reports.indexed && reports.descend);
+        let tmp = rules.check("/data/build/tmp", true, None);
+        assert!(!tmp.indexed && !tmp.descend);
+        assert!(rules.check("/data/build/reports/q1.pdf", false, Some(1)).indexed);
+        assert!(!rules.check("/data/build/tmp/x.o", false, Some(1)).indexed);
+
+        // built-in exclusion
+        let modules = rules.check("/data/proj/node_modules", true, None);
+        assert!(!modules.indexed && modules.descend);
+        let other = rules.check("/data/other/node_modules", true, None);
+        assert!(!other.indexed && !other.descend);
+        assert!(rules.check("/data/proj/node_modules/keep", true, None).indexed);
+        assert!(rules.check("/data/proj/node_modules/keep/a.js", false, Some(1)).indexed);
+        assert!(!rules.check("/data/proj/node_modules/left-pad/a.js", false, Some(1)).indexed);
+    }
+}
@@ -0,0 +1,798 @@
+// src-tauri/src/indexer.rs
+//
+// Incremental directory walk shared by `index_path`, `index_path_start` and
+// `index_all_drives_start`, plus the row helpers fs_watch uses.
+//
+// Every files_index row records its parent directory, so a walk reconciles
+// one directory at a time: it loads the rows already stored for the
+// directory, compares them with what read_dir returns and
+//   - skips entries whose (type, size, mtime, ctime, inode) are unchanged,
+//   - upserts new or changed entries (indexed_at = time of that write),
+//   - removes rows (and their subtrees) that are no longer on disk.
+// A rescan of an unchanged tree therefore only stats and reads the database.
+//
+// Directories are read by a pool of walker threads (each with its own
+// read connection) sharing one work stack. Walkers only compute the changes;
+// they hand them over a bounded channel to the calling thread, the single
+// writer, which applies them in batched transactions with cached statements.
+// A directory counts as pending until the batch holding its changes has
+// been committed, so the work stack plus those in-flight directories is a
+// safe checkpoint.
+use rusqlite::{params, Connection};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use crate::fs_ops::{doc_type_for_path, normalize_drive_for_storage};
+use crate::index_rules::IndexRules;
+
+/// Change-detection fields for one entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EntryStat {
+    pub is_dir: bool,
+    pub size: Option<i64>,
+    pub mtime: Option<i64>,
+    pub ctime: Option<i64>,
+    /// inode on unix; not available from std on Windows
+    pub inode: Option<i64>,
+}
+
+impl EntryStat {
+    pub fn from_metadata(meta: &fs::Metadata) -> Self {
+        let is_dir = meta.is_dir();
+        let mtime = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64);
+        let (ctime, inode) = platform_ids(meta);
+        EntryStat {
+            is_dir,
+            size: if is_dir { None } else { Some(meta.len() as i64) },
+            mtime,
+            ctime,
+            inode,
+        }
+    }
+
+    fn file_type(&self) -> &'static str {
+        if self.is_dir {
+            "dir"
+        } else {
+            "file"
+        }
+    }
+}
+
+#[cfg(unix)]
+fn platform_ids(meta: &fs::Metadata) -> (Option<i64>, Option<i64>) {
+    use std::os::unix::fs::MetadataExt;
+    (Some(meta.ctime()), Some(meta.ino() as i64))
+}
+
+#[cfg(windows)]
+fn platform_ids(meta: &fs::Metadata) -> (Option<i64>, Option<i64>) {
+    use std::os::windows::fs::MetadataExt;
+    // FILETIME: 100ns ticks since 1601-01-01
+    let created = (meta.creation_time() / 10_000_000) as i64 - 11_644_473_600;
+    (Some(created), None)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn platform_ids(_meta: &fs::Metadata) -> (Option<i64>, Option<i64>) {
+    (None, None)
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct WalkStats {
+    pub visited: usize,
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+}
+
+/// `[lo, hi)` bounds of the paths strictly below `path` (index-friendly
+/// alternative to LIKE, which would need escaping).
+pub(crate) fn descendant_range(path: &str) -> (String, String) {
+    let mut lo = path.to_string();
+    if !lo.ends_with(MAIN_SEPARATOR) {
+        lo.push(MAIN_SEPARATOR);
+    }
+    let mut hi = lo.clone();
+    hi.pop();
+    hi.push((MAIN_SEPARATOR as u8 + 1) as char);
+    (lo, hi)
+}
+
+/// Parent directory of `path`; for an archive member, the archive holding it.
+pub(crate) fn parent_of(path: &str) -> Option<String> {
+    if let Some(archive) = crate::archive_index::member_parent(path) {
+        return Some(archive.to_string());
+    }
+    Path::new(path)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+}
+
+/// Insert or update one files_index row.
+pub(crate) fn upsert_entry(
+    conn: &Connection,
+    path: &str,
+    name: &str,
+    parent: Option<&str>,
+    st: &EntryStat,
+    now: i64,
+) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare_cached(
+        "INSERT INTO files_index (path, name, file_type, doc_type, size, indexed_at, drive, parent, mtime, ctime, inode, volume_id, rel_path)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+         ON CONFLICT(path)
+         DO UPDATE SET
+           name=excluded.name,
+           file_type=excluded.file_type,
+           doc_type=excluded.doc_type,
+           size=excluded.size,
+           indexed_at=excluded.indexed_at,
+           drive=excluded.drive,
+           parent=excluded.parent,
+           mtime=excluded.mtime,
+           ctime=excluded.ctime,
+           inode=excluded.inode,
+           volume_id=excluded.volume_id,
+           rel_path=excluded.rel_path",
+        )
+        .map_err(|e| e.to_string())?;
+    let volume = crate::volumes::locate(path);
+    stmt.execute(params![
+            path,
+            name,
+            st.file_type(),
+            doc_type_for_path(path, st.is_dir),
+            st.size,
+            now,
+            match &volume {
+                Some(v) => v.key.clone(),
+                None => normalize_drive_for_storage(path),
+            },
+            parent,
+            st.mtime,
+            st.ctime,
+            st.inode,
+            volume.as_ref().map(|v| v.volume_id),
+            volume.as_ref().map(|v| v.rel_path.as_str())
+        ])
+    .map_err(|e| format!("db upsert failed at {}: {}", path, e))?;
+    Ok(())
+}
+
+/// Stat `path` and upsert it (watcher create/modify events).
+pub(crate) fn upsert_path(conn: &Connection, path: &str, now: i64) -> Result<(), String> {
+    let meta = fs::symlink_metadata(path).map_err(|e| format!("stat {}: {}", path, e))?;
+    let name = Path::new(path)
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    upsert_entry(
+        conn,
+        path,
+        &name,
+        parent_of(path).as_deref(),
+        &EntryStat::from_metadata(&meta),
+        now,
+    )
+}
+
+/// Rows on an unmounted volume (see volumes.rs) or an unreachable storage
+/// location (see locations.rs); walks must not treat them as gone.
+fn on_unavailable_volume() -> String {
+    format!(
+        "volume_id IS NOT NULL AND volume_id IN ({})",
+        crate::locations::UNAVAILABLE_VOLUME_IDS
+    )
+}
+
+/// Delete `path` and everything indexed below it, except rows on an unavailable
+/// volume mounted inside it. Returns rows removed.
+pub(crate) fn remove_entry(conn: &Connection, path: &str) -> Result<usize, String> {
+    let (lo, hi) = descendant_range(path);
+    let n = conn
+        .prepare_cached(&format!(
+            "DELETE FROM files_index WHERE (path = ?1 OR (path >= ?2 AND path < ?3))
+               AND NOT ({})",
+            on_unavailable_volume()
+        ))
+        .and_then(|mut stmt| stmt.execute(params![path, lo, hi]))
+        .map_err(|e| e.to_string())?;
+    Ok(n)
+}
+
+/// Re-point `old` (and, for directories, its indexed subtree) at `new`.
+pub(crate) fn rename_entry(conn: &Connection, old: &str, new: &str) -> Result<usize, String> {
+    let name = Path::new(new)
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut n = conn
+        .execute(
+            "UPDATE files_index SET path = ?1, name = ?2, parent = ?3 WHERE path = ?4",
+            params![new, name, parent_of(new), old],
+        )
+        .map_err(|e| e.to_string())?;
+    let (lo, hi) = descendant_range(old);
+    let old_len = old.chars().count() as i64;
+    n += conn
+        .execute(
+            "UPDATE files_index
+             SET path = ?1 || substr(path, ?2 + 1),
+                 parent = ?1 || substr(parent, ?2 + 1)
+             WHERE path >= ?3 AND path < ?4",
+            params![new, old_len, lo, hi],
+        )
+        .map_err(|e| e.to_string())?;
+    // members listed inside an archive (archive_index.rs) move with it
+    let (lo, hi) = crate::archive_index::member_range(old);
+    n += conn
+        .execute(
+            "UPDATE files_index
+             SET path = ?1 || substr(path, ?2 + 1),
+                 parent = ?1 || substr(parent, ?2 + 1)
+             WHERE path >= ?3 AND path < ?4",
+            params![new, old_len, lo, hi],
+        )
+        .map_err(|e| e.to_string())?;
+    // keep the volume-relative paths in step (a move may cross volumes)
+    let volume = crate::volumes::locate(new);
+    let (new_lo, new_hi) = descendant_range(new);
+    let (member_lo, member_hi) = crate::archive_index::member_range(new);
+    conn.execute(
+        "UPDATE files_index
+         SET volume_id = ?2,
+             rel_path = CASE WHEN path = ?1 THEN ?3 ELSE ?3 || substr(path, ?4 + 1) END
+         WHERE path = ?1 OR (path >= ?5 AND path < ?6) OR (path >= ?7 AND path < ?8)",
+        params![
+            new,
+            volume.as_ref().map(|v| v.volume_id),
+            volume.as_ref().map(|v| v.rel_path.as_str()),
+            new.chars().count() as i64,
+            new_lo,
+            new_hi,
+            member_lo,
+            member_hi
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(n)
+}
+
+/// Fill `parent` for rows written before the column existed.
+pub(crate) fn backfill_parents(conn: &Connection) -> Result<usize, String> {
+    let rows: Vec<(i64, String)> = {
+        let mut stmt = conn
+            .prepare("SELECT id, path FROM files_index WHERE parent IS NULL")
+            .map_err(|e| e.to_string())?;
+        let it = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .map_err(|e| e.to_string())?;
+        it.filter_map(|r| r.ok()).collect()
+    };
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    for (id, path) in &rows {
+        tx.execute(
+            "UPDATE files_index SET parent = ?1 WHERE id = ?2",
+            params![parent_of(path), id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(rows.len())
+}
+
+type StoredRow = (String, Option<i64>, Option<i64>, Option<i64>, Option<i64>);
+
+fn stored_children(conn: &Connection, dir: &str) -> Result<HashMap<String, StoredRow>, String> {
+    let mut stmt = conn
+        .prepare_cached(&format!(
+            "SELECT path, file_type, size, mtime, ctime, inode FROM files_index
+             WHERE parent = ?1 AND NOT ({})",
+            on_unavailable_volume()
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![dir], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                (
+                    r.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                    r.get(2)?,
+                    r.get(3)?,
+                    r.get(4)?,
+                    r.get(5)?,
+                ),
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+    let mut out = HashMap::new();
+    for r in rows {
+        let (path, row) = r.map_err(|e| e.to_string())?;
+        out.insert(path, row);
+    }
+    Ok(out)
+}
+
+fn unchanged(stored: &StoredRow, st: &EntryStat) -> bool {
+    stored.0 == st.file_type()
+        && stored.1 == st.size
+        && stored.2 == st.mtime
+        && stored.3 == st.ctime
+        && stored.4 == st.inode
+}
+
+/// Walker threads. The walk waits on stat/readdir latency (network mounts)
+/// far more than on CPU, so this is not tied to the core count alone.
+fn walker_count() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .clamp(4, 16)
+}
+
+/// Directory results a walker may queue ahead of the writer.
+const RESULT_QUEUE: usize = 256;
+/// Rows written per transaction before it is committed.
+const BATCH_ROWS: usize = 5_000;
+/// Longest a directory result waits in an open batch; also how often the
+/// progress callback runs while the walk is stalled.
+const BATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Changes for one directory, computed by a walker.
+struct DirResult {
+    dir: PathBuf,
+    dir_str: String,
+    /// The directory disappeared before it could be read.
+    vanished: bool,
+    /// (path, name, stat, previously indexed)
+    upserts: Vec<(String, String, EntryStat, bool)>,
+    unchanged: usize,
+    gone: Vec<String>,
+    /// Indexed before but excluded by the current rules.
+    dropped: Vec<String>,
+    /// Excluded directories that are still walked (an include rule may match
+    /// below them): only their own row goes, their children are settled when
+    /// they are read.
+    dropped_alone: Vec<String>,
+}
+
+impl DirResult {
+    fn empty(dir: PathBuf, dir_str: String) -> Self {
+        DirResult {
+            dir,
+            dir_str,
+            vanished: false,
+            upserts: Vec::new(),
+            unchanged: 0,
+            gone: Vec::new(),
+            dropped: Vec::new(),
+            dropped_alone: Vec::new(),
+        }
+    }
+
+    fn rows(&self) -> usize {
+        1 + self.upserts.len() + self.gone.len() + self.dropped.len() + self.dropped_alone.len()
+    }
+}
+
+#[derive(Default)]
+struct QueueState {
+    stack: Vec<PathBuf>,
+    /// Taken by a walker but not yet committed by the writer.
+    in_flight: HashSet<PathBuf>,
+    /// Walkers currently reading a directory.
+    busy: usize,
+    /// Walkers wait instead of taking more work (job paused).
+    held: bool,
+    stopped: bool,
+}
+
+/// Work stack shared by the walkers, plus their common rate limit.
+pub struct WorkQueue {
+    state: Mutex<QueueState>,
+    ready: Condvar,
+    /// Entries per second across all walkers; 0 = unlimited.
+    rate: AtomicU32,
+    /// When the entries reserved so far have been "paid" for.
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl WorkQueue {
+    fn new(mut stack: Vec<PathBuf>) -> Self {
+        // a resumed checkpoint may list a directory twice
+        let mut seen = HashSet::new();
+        stack.retain(|p| seen.insert(p.clone()));
+        WorkQueue {
+            state: Mutex::new(QueueState {
+                stack,
+                ..Default::default()
+            }),
+            ready: Condvar::new(),
+            rate: AtomicU32::new(0),
+            next_slot: Mutex::new(None),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Next directory to read; `None` once the walk is complete or stopped.
+    fn next(&self) -> Option<PathBuf> {
+        let mut st = self.lock();
+        loop {
+            if st.stopped {
+                return None;
+            }
+            if st.held {
+                st = self.ready.wait(st).unwrap_or_else(|e| e.into_inner());
+                continue;
+            }
+            if let Some(dir) = st.stack.pop() {
+                st.busy += 1;
+                st.in_flight.insert(dir.clone());
+                return Some(dir);
+            }
+            if st.busy == 0 {
+                // nothing queued and nobody left to queue more
+                self.ready.notify_all();
+                return None;
+            }
+            st = self.ready.wait(st).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    fn done_reading(&self, subdirs: Vec<PathBuf>) {
+        let mut st = self.lock();
+        st.busy -= 1;
+        st.stack.extend(subdirs);
+        self.ready.notify_all();
+    }
+
+    fn committed<'a>(&self, dirs: impl Iterator<Item = &'a PathBuf>) {
+        let mut st = self.lock();
+        for d in dirs {
+            st.in_flight.remove(d);
+        }
+    }
+
+    fn stop(&self) {
+        self.lock().stopped = true;
+        self.ready.notify_all();
+    }
+
+    /// Stop (or let) walkers take new directories; reads already underway
+    /// still complete.
+    pub fn hold(&self, on: bool) {
+        self.lock().held = on;
+        self.ready.notify_all();
+    }
+
+    /// Cap the walk at `per_sec` entries per second (0 = unlimited). Takes
+    /// effect from the next directory read.
+    pub fn set_rate_limit(&self, per_sec: u32) {
+        self.rate.store(per_sec, Ordering::Relaxed);
+    }
+
+    /// Called by a walker after reading `entries`; sleeps until the rate
+    /// limit allows them. Idle time builds no credit, so there is no burst
+    /// after a pause.
+    fn throttle(&self, entries: usize) {
+        let rate = self.rate.load(Ordering::Relaxed);
+        if rate == 0 || entries == 0 {
+            return;
+        }
+        let cost = Duration::from_secs_f64(entries as f64 / rate as f64);
+        let until = {
+            let mut next = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let end = next.map_or(now, |n| n.max(now)) + cost;
+            *next = Some(end);
+            end
+        };
+        // short naps so a stopped walk is not held up by a long reservation
+        loop {
+            let now = Instant::now();
+            if now >= until || self.lock().stopped {
+                return;
+            }
+            std::thread::sleep((until - now).min(BATCH_INTERVAL));
+        }
+    }
+
+    /// Directories whose changes are not committed yet: queued plus in flight.
+    pub fn pending(&self) -> Vec<PathBuf> {
+        let st = self.lock();
+        let mut out = st.stack.clone();
+        out.extend(st.in_flight.iter().cloned());
+        out
+    }
+}
+
+/// What the progress callback sees after each commit.
+pub struct WalkProgress<'a> {
+    pub stats: &'a WalkStats,
+    /// Most recently committed directory.
+    pub last_dir: Option<&'a Path>,
+    pub queue: &'a WorkQueue,
+}
+
+/// Read one directory and diff it against its stored children.
+fn read_directory(
+    conn: &Connection,
+    rules: &IndexRules,
+    dir: PathBuf,
+) -> Result<(DirResult, Vec<PathBuf>), String> {
+    let dir_str = dir.to_string_lossy().to_string();
+    let mut result = DirResult::empty(dir, dir_str);
+    let mut subdirs = Vec::new();
+
+    let read = match fs::read_dir(&result.dir) {
+        Ok(r) => r,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            // vanished between being listed and being read
+            result.vanished = true;
+            return Ok((result, subdirs));
+        }
+        Err(e) => {
+            // unreadable (permissions etc.): keep what is indexed
+            eprintln!("[index] read_dir failed at {}: {}", result.dir.display(), e);
+            return Ok((result, subdirs));
+        }
+    };
+
+    let mut stored = stored_children(conn, &result.dir_str)?;
+    let inherited = rules.inherited(&result.dir_str);
+    for entry in read {
+        let entry = match entry {
+            Ok(e) => e,
+            Err(e) => {
+                eprintln!("[index] dir entry read error in {}: {}", result.dir.display(), e);
+                continue;
+            }
+        };
+        let path_buf = entry.path();
+        let path_str = path_buf.to_string_lossy().to_string();
+        let previous = stored.remove(&path_str);
+
+        let meta = match entry.metadata() {
+            Ok(m) => m,
+            Err(e) => {
+                // leave the stored row alone; it is neither confirmed nor gone
+                eprintln!("[index] metadata failed for {}: {}", path_buf.display(), e);
+                continue;
+            }
+        };
+        let st = EntryStat::from_metadata(&meta);
+
+        let verdict = rules.check_entry(&path_str, st.is_dir, st.size, inherited);
+        if !verdict.indexed {
+            if verdict.descend {
+                if previous.is_some() {
+                    result.dropped_alone.push(path_str);
+                }
+                subdirs.push(path_buf);
+            } else if previous.is_some() {
+                result.dropped.push(path_str);
+            }
+            continue;
+        }
+        if verdict.descend {
+            subdirs.push(path_buf);
+        }
+        match &previous {
+            Some(prev) if unchanged(prev, &st) => result.unchanged += 1,
+            _ => {
+                let name = entry.file_name().to_string_lossy().to_string();
+                result.upserts.push((path_str, name, st, previous.is_some()));
+            }
+        }
+    }
+    // whatever is left was indexed before but is gone now
+    result.gone = stored.into_keys().collect();
+    Ok((result, subdirs))
+}
+
+fn walker(
+    queue: &WorkQueue,
+    rules: &IndexRules,
+    results: SyncSender<Result<DirResult, String>>,
+) {
+    let conn = match crate::db::open_connection() {
+        Ok(c) => c,
+        Err(e) => {
+            let _ = results.send(Err(format!("walker db open failed: {}", e)));
+            queue.stop();
+            return;
+        }
+    };
+    while let Some(dir) = queue.next() {
+        match read_directory(&conn, rules, dir) {
+            Ok((result, subdirs)) => {
+                // queue the children first so other walkers can start on them
+                queue.done_reading(subdirs);
+                queue.throttle(result.upserts.len() + result.unchanged);
+                if results.send(Ok(result)).is_err() {
+                    return;
+                }
+            }
+            Err(e) => {
+                queue.done_reading(Vec::new());
+                let _ = results.send(Err(e));
+                queue.stop();
+                return;
+            }
+        }
+    }
+}
+
+/// Apply a batch of directory results in one transaction.
+fn write_batch(
+    conn: &Connection,
+    batch: &[DirResult],
+    stats: &mut WalkStats,
+    now: i64,
+) -> Result<(), String> {
+    let mut delta = WalkStats::default();
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    for r in batch {
+        if r.vanished {
+            delta.removed += remove_entry(&tx, &r.dir_str)?;
+            continue;
+        }
+        for (path, name, st, existed) in &r.upserts {
+            upsert_entry(&tx, path, name, Some(&r.dir_str), st, now)?;
+            if *existed {
+                delta.updated += 1;
+            } else {
+                delta.added += 1;
+            }
+        }
+        for gone in &r.gone {
+            delta.removed += remove_entry(&tx, gone)?;
+            let _ = crate::file_history::watcher_removed(&tx, gone);
+        }
+        // still on disk, so no history entry; the index just stops covering it
+        for dropped in &r.dropped {
+            delta.removed += remove_entry(&tx, dropped)?;
+        }
+        for dropped in &r.dropped_alone {
+            delta.removed += tx
+                .execute("DELETE FROM files_index WHERE path = ?1", params![dropped])
+                .map_err(|e| e.to_string())?;
+        }
+        delta.unchanged += r.unchanged;
+        delta.visited += r.upserts.len() + r.unchanged;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    // only count what is durable, so checkpointed counters match the rows
+    stats.visited += delta.visited;
+    stats.added += delta.added;
+    stats.updated += delta.updated;
+    stats.unchanged += delta.unchanged;
+    stats.removed += delta.removed;
+    Ok(())
+}
+
+/// Reconcile the subtrees under the directories in `stack` with files_index,
+/// continuing from `stats` (non-default when resuming a checkpoint). Entries
+/// excluded by `rules` are skipped (and dropped if indexed before).
+/// `on_progress` runs on the calling thread once before the walkers start,
+/// then after each committed batch (and periodically while the walk is
+/// stalled); `WorkQueue::pending` at that
+/// point is exactly the work still to do, which is what a checkpoint has to
+/// save. Returning `Break` stops the walk there (pause/cancel); the changes
+/// already read are committed first.
+pub fn walk_roots(
+    conn: &Connection,
+    stack: Vec<PathBuf>,
+    mut stats: WalkStats,
+    rules: &IndexRules,
+    mut on_progress: impl FnMut(&WalkProgress) -> ControlFlow<()>,
+) -> Result<WalkStats, String> {
+    let now = chrono::Utc::now().timestamp();
+    let queue = WorkQueue::new(stack);
+    let start = WalkProgress {
+        stats: &stats,
+        last_dir: None,
+        queue: &queue,
+    };
+    if on_progress(&start).is_break() {
+        return Ok(stats);
+    }
+    let (tx, rx) = mpsc::sync_channel::<Result<DirResult, String>>(RESULT_QUEUE);
+
+    std::thread::scope(|scope| {
+        for _ in 0..walker_count() {
+            let tx = tx.clone();
+            let queue = &queue;
+            scope.spawn(move || walker(queue, rules, tx));
+        }
+        // the channel disconnects once every walker has exited
+        drop(tx);
+
+        let mut batch: Vec<DirResult> = Vec::new();
+        let mut batch_rows = 0usize;
+        let mut batch_opened = Instant::now();
+        let mut last_dir: Option<PathBuf> = None;
+        let mut last_progress = Instant::now();
+        let mut failure: Option<String> = None;
+
+        loop {
+            let msg = rx.recv_timeout(BATCH_INTERVAL);
+            let finished = matches!(msg, Err(RecvTimeoutError::Disconnected));
+            match msg {
+                Ok(Ok(r)) => {
+                    if batch.is_empty() {
+                        batch_opened = Instant::now();
+                    }
+                    batch_rows += r.rows();
+                    batch.push(r);
+                }
+                Ok(Err(e)) => {
+                    failure = Some(e);
+                    break;
+                }
+                Err(_) => {}
+            }
+
+            let mut committed = false;
+            if !batch.is_empty()
+                && (finished
+                    || batch_rows >= BATCH_ROWS
+                    || batch_opened.elapsed() >= BATCH_INTERVAL)
+            {
+                if let Err(e) = write_batch(conn, &batch, &mut stats, now) {
+                    failure = Some(e);
+                    break;
+                }
+                queue.committed(batch.iter().map(|r| &r.dir));
+                last_dir = batch.pop().map(|r| r.dir);
+                batch.clear();
+                batch_rows = 0;
+                committed = true;
+            }
+            if finished {
+                break;
+            }
+
+            if committed || last_progress.elapsed() >= BATCH_INTERVAL {
+                last_progress = Instant::now();
+                let progress = WalkProgress {
+                    stats: &stats,
+                    last_dir: last_dir.as_deref(),
+                    queue: &queue,
+                };
+                if on_progress(&progress).is_break() {
+                    if let Err(e) = write_batch(conn, &batch, &mut stats, now) {
+                        failure = Some(e);
+                    } else {
+                        queue.committed(batch.iter().map(|r| &r.dir));
+                    }
+                    break;
+                }
+            }
+        }
+
+        // unblock walkers waiting on a full channel, then let them drain out
+        queue.stop();
+        drop(rx);
+        match failure {
+            Some(e) => Err(e),
+            None => Ok(stats),
+        }
+    })
+}
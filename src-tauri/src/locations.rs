@@ -0,0 +1,454 @@
+// src-tauri/src/locations.rs
+//
+// Registry of named storage locations ("Ops NAS", "Training USB #3", ...).
+// A location names a volume (see volumes.rs) and carries a description, the
+// owning unit and an access policy. It is available while its volume is
+// mounted and its root can be read; a network share that is still mounted
+// but unreachable counts as unavailable too.
+//
+// Index rows of an unavailable location stay searchable and are flagged as
+// such in results; walks and `reconcile_missing_files` leave them alone
+// until the location comes back. Rows of a `restricted` location are only
+// returned to admins.
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::mpsc::Receiver;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle};
+
+pub const POLICY_OPEN: &str = "open";
+pub const POLICY_RESTRICTED: &str = "restricted";
+
+/// volumes.id of volumes whose rows must not be treated as gone: unmounted,
+/// or registered to a location that failed its last probe.
+pub(crate) const UNAVAILABLE_VOLUME_IDS: &str = "SELECT v.id FROM volumes v
+     LEFT JOIN storage_locations sl ON sl.volume_key = v.key
+     WHERE v.online = 0 OR sl.available = 0";
+
+/// How long `probe` waits for location roots to list.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Roots whose listing is still running. A hung share can block `read_dir`
+/// for minutes; later probes count it unreachable instead of piling up
+/// threads behind it.
+static PROBING: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+pub(crate) fn ensure_tables(conn: &Connection) -> Result<(), String> {
+    crate::volumes::ensure_tables(conn)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS storage_locations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            description TEXT,
+            owner_unit TEXT,
+            access_policy TEXT NOT NULL DEFAULT 'open',
+            volume_key TEXT NOT NULL UNIQUE,
+            available INTEGER NOT NULL DEFAULT 0,
+            last_seen_at INTEGER,
+            created_by INTEGER,
+            created_by_username TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );",
+    )
+    .map_err(|e| format!("ensure storage_locations: {}", e))
+}
+
+/// Re-check every location against the mount table of the last volume
+/// refresh and record which are reachable. Returns the number available.
+/// A root that does not list within `PROBE_TIMEOUT` counts as unreachable.
+pub(crate) fn probe(conn: &Connection) -> Result<usize, String> {
+    ensure_tables(conn)?;
+    let locations: Vec<(i64, bool, Option<String>)> = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT sl.id, COALESCE(v.online, 0), v.mount_point
+                 FROM storage_locations sl
+                 LEFT JOIN volumes v ON v.key = sl.volume_key",
+            )
+            .map_err(|e| e.to_string())?;
+        let it = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get::<_, i64>(1)? != 0, r.get(2)?)))
+            .map_err(|e| e.to_string())?;
+        it.filter_map(|r| r.ok()).collect()
+    };
+
+    // list all roots at once, off this thread, and stop waiting at the deadline
+    let pending: Vec<(i64, Option<Receiver<bool>>)> = locations
+        .into_iter()
+        .map(|(id, online, mount)| (id, mount.filter(|_| online).and_then(spawn_listing)))
+        .collect();
+    let deadline = Instant::now() + PROBE_TIMEOUT;
+
+    let now = Utc::now().timestamp();
+    let mut available = 0;
+    for (id, listing) in pending {
+        let reachable = listing.is_some_and(|rx| {
+            rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                .unwrap_or(false)
+        });
+        if reachable {
+            available += 1;
+            conn.execute(
+                "UPDATE storage_locations SET available = 1, last_seen_at = ?2 WHERE id = ?1",
+                params![id, now],
+            )
+        } else {
+            conn.execute(
+                "UPDATE storage_locations SET available = 0 WHERE id = ?1",
+                params![id],
+            )
+        }
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(available)
+}
+
+/// List `root` on a thread of its own; the receiver gets whether it could be
+/// read. None while an earlier listing of `root` has not returned.
+fn spawn_listing(root: String) -> Option<Receiver<bool>> {
+    if !PROBING.lock().ok()?.insert(root.clone()) {
+        return None;
+    }
+    let (tx, rx) = std::sync::mpsc::channel();
+    let key = root.clone();
+    let spawned = std::thread::Builder::new()
+        .name("location-probe".into())
+        .spawn(move || {
+            let ok = std::fs::read_dir(&root).is_ok();
+            listing_done(&root);
+            let _ = tx.send(ok);
+        });
+    match spawned {
+        Ok(_) => Some(rx),
+        Err(_) => {
+            listing_done(&key);
+            None
+        }
+    }
+}
+
+fn listing_done(root: &str) {
+    if let Ok(mut probing) = PROBING.lock() {
+        probing.remove(root);
+    }
+}
+
+/// volume key -> location name, for labelling drives.
+pub(crate) fn names_by_volume(
+    conn: &Connection,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    ensure_tables(conn)?;
+    let mut stmt = conn
+        .prepare("SELECT volume_key, name FROM storage_locations")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+// -----------------------------------------------------------------------------
+// Commands
+// -----------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+pub struct StorageLocation {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub owner_unit: Option<String>,
+    pub access_policy: String,
+    pub volume_key: String,
+    pub volume_label: Option<String>,
+    pub mount_point: Option<String>,
+    pub available: bool,
+    pub last_seen_at: Option<i64>,
+    pub indexed_entries: i64,
+    pub created_by_username: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+const SELECT_LOCATION: &str = "SELECT sl.id, sl.name, sl.description, sl.owner_unit,
+        sl.access_policy, sl.volume_key, v.label, v.mount_point, sl.available,
+        sl.last_seen_at,
+        (SELECT COUNT(*) FROM files_index fi WHERE fi.volume_id = v.id),
+        sl.created_by_username, sl.created_at, sl.updated_at
+     FROM storage_locations sl
+     LEFT JOIN volumes v ON v.key = sl.volume_key";
+
+fn location_from_row(r: &rusqlite::Row) -> rusqlite::Result<StorageLocation> {
+    Ok(StorageLocation {
+        id: r.get(0)?,
+        name: r.get(1)?,
+        description: r.get(2)?,
+        owner_unit: r.get(3)?,
+        access_policy: r.get(4)?,
+        volume_key: r.get(5)?,
+        volume_label: r.get(6)?,
+        mount_point: r.get(7)?,
+        available: r.get::<_, i64>(8)? != 0,
+        last_seen_at: r.get(9)?,
+        indexed_entries: r.get(10)?,
+        created_by_username: r.get(11)?,
+        created_at: r.get(12)?,
+        updated_at: r.get(13)?,
+    })
+}
+
+pub fn list_locations(conn: &Connection) -> Result<Vec<StorageLocation>, String> {
+    crate::fs_ops::ensure_files_index_tables(conn)?;
+    ensure_tables(conn)?;
+    let mut stmt = conn
+        .prepare(&format!("{} ORDER BY sl.name", SELECT_LOCATION))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], location_from_row)
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn get_location(conn: &Connection, id: i64) -> Result<StorageLocation, String> {
+    crate::fs_ops::ensure_files_index_tables(conn)?;
+    ensure_tables(conn)?;
+    conn.query_row(
+        &format!("{} WHERE sl.id = ?1", SELECT_LOCATION),
+        params![id],
+        location_from_row,
+    )
+    .optional()
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("storage location {} not found", id))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StorageLocationInput {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub owner_unit: Option<String>,
+    #[serde(default = "default_policy")]
+    pub access_policy: String,
+    /// Volume key as listed by `admin_list_volumes`...
+    #[serde(default)]
+    pub volume_key: Option<String>,
+    /// ...or the mount point of a volume that is plugged in now.
+    #[serde(default)]
+    pub mount_point: Option<String>,
+}
+
+fn default_policy() -> String {
+    POLICY_OPEN.to_string()
+}
+
+fn non_empty(s: Option<String>) -> Option<String> {
+    s.map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+/// Normalized input and the key of the volume it names.
+fn validate_input(
+    conn: &Connection,
+    input: StorageLocationInput,
+) -> Result<(StorageLocationInput, String), String> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err("name is required".into());
+    }
+    let access_policy = input.access_policy.trim().to_string();
+    if access_policy != POLICY_OPEN && access_policy != POLICY_RESTRICTED {
+        return Err(format!(
+            "access_policy must be '{}' or '{}'",
+            POLICY_OPEN, POLICY_RESTRICTED
+        ));
+    }
+    let key = match (non_empty(input.volume_key), non_empty(input.mount_point)) {
+        (Some(key), _) => key,
+        (None, Some(mount)) => {
+            crate::volumes::refresh(conn)?;
+            crate::volumes::volume_at(&mount)
+                .map(|(key, _)| key)
+                .ok_or_else(|| format!("no volume is mounted at {}", mount))?
+        }
+        (None, None) => return Err("volume_key or mount_point is required".into()),
+    };
+    let known: Option<i64> = conn
+        .query_row("SELECT id FROM volumes WHERE key = ?1", params![key], |r| {
+            r.get(0)
+        })
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if known.is_none() {
+        return Err(format!("unknown volume {}", key));
+    }
+    Ok((
+        StorageLocationInput {
+            name,
+            description: non_empty(input.description),
+            owner_unit: non_empty(input.owner_unit),
+            access_policy,
+            volume_key: Some(key.clone()),
+            mount_point: None,
+        },
+        key,
+    ))
+}
+
+fn duplicate_error(e: rusqlite::Error, name: &str, key: &str) -> String {
+    match e {
+        rusqlite::Error::SqliteFailure(f, _)
+            if f.code == rusqlite::ErrorCode::ConstraintViolation =>
+        {
+            format!(
+                "a location named '{}' or on volume {} already exists",
+                name, key
+            )
+        }
+        e => e.to_string(),
+    }
+}
+
+/// Admin: registered locations with their current availability.
+#[command]
+pub fn admin_list_storage_locations(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<Vec<StorageLocation>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    crate::volumes::refresh(&conn)?;
+    probe(&conn)?;
+    list_locations(&conn)
+}
+
+#[command]
+pub fn admin_create_storage_location(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    location: StorageLocationInput,
+) -> Result<StorageLocation, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    ensure_tables(&conn)?;
+
+    let (location, key) = validate_input(&conn, location)?;
+    let now = Utc::now().timestamp();
+    conn.execute(
+        "INSERT INTO storage_locations (name, description, owner_unit, access_policy, volume_key,
+                                        created_by, created_by_username, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+        params![
+            location.name,
+            location.description,
+            location.owner_unit,
+            location.access_policy,
+            key,
+            admin_id,
+            admin_username,
+            now
+        ],
+    )
+    .map_err(|e| duplicate_error(e, &location.name, &key))?;
+    let id = conn.last_insert_rowid();
+    probe(&conn)?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_create_storage_location",
+        None,
+        Some(&format!(
+            "id={} '{}' volume={} policy={}",
+            id, location.name, key, location.access_policy
+        )),
+    );
+    get_location(&conn, id)
+}
+
+#[command]
+pub fn admin_update_storage_location(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    id: i64,
+    location: StorageLocationInput,
+) -> Result<StorageLocation, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    get_location(&conn, id)?;
+
+    let (location, key) = validate_input(&conn, location)?;
+    conn.execute(
+        "UPDATE storage_locations SET name = ?2, description = ?3, owner_unit = ?4,
+                access_policy = ?5, volume_key = ?6, updated_at = ?7
+         WHERE id = ?1",
+        params![
+            id,
+            location.name,
+            location.description,
+            location.owner_unit,
+            location.access_policy,
+            key,
+            Utc::now().timestamp()
+        ],
+    )
+    .map_err(|e| duplicate_error(e, &location.name, &key))?;
+    probe(&conn)?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_update_storage_location",
+        None,
+        Some(&format!(
+            "id={} '{}' volume={} policy={}",
+            id, location.name, key, location.access_policy
+        )),
+    );
+    get_location(&conn, id)
+}
+
+/// Admin: forget a location. Its volume and index rows are kept.
+#[command]
+pub fn admin_delete_storage_location(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    id: i64,
+) -> Result<Vec<StorageLocation>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    let location = get_location(&conn, id)?;
+
+    conn.execute("DELETE FROM storage_locations WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_delete_storage_location",
+        None,
+        Some(&format!(
+            "id={} '{}' volume={}",
+            id, location.name, location.volume_key
+        )),
+    );
+    list_locations(&conn)
+}
@@ -0,0 +1,435 @@
+// src-tauri/src/login_reports.rs
+//
+// Admin reporting over `login_history` and `failed_logins`: per-user login
+// timelines, failed attempts grouped by username / source IP, never-logged-in
+// and dormant accounts, and success-rate trends bucketed by hour/day/week/month.
+// Buckets are computed in UTC. Periods take the same window shorthands as the
+// portal audit view ("24h", "48h", "1w", "1m", "1y") or explicit timestamps.
+use crate::audit::{ensure_admin_session, window_to_ts};
+use chrono::Utc;
+use rusqlite::{params, params_from_iter, Connection};
+use serde::Serialize;
+use tauri::{command, AppHandle};
+
+const DEFAULT_DORMANT_DAYS: i64 = 90;
+const MAX_DORMANT_DAYS: i64 = 36_500;
+
+#[derive(Debug, Serialize)]
+pub struct LoginEvent {
+    pub id: i64,
+    pub user_id: Option<i64>,
+    pub username: Option<String>,
+    pub success: bool,
+    pub ip: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoginTimeline {
+    pub user_id: Option<i64>,
+    pub username: String,
+    pub last_login: Option<i64>,
+    pub successes: i64,
+    pub failures: i64,
+    pub events: Vec<LoginEvent>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailedLoginGroup {
+    pub username: Option<String>,
+    pub ip: Option<String>,
+    pub attempts: i64,
+    pub first_at: i64,
+    pub last_at: i64,
+    /// False when the username does not belong to any account (guessing).
+    pub known_user: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountActivity {
+    pub id: i64,
+    pub username: String,
+    pub name: String,
+    pub role: String,
+    pub created_at: i64,
+    pub last_login: Option<i64>,
+    pub days_inactive: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InactiveAccounts {
+    pub dormant_days: i64,
+    pub never_logged_in: Vec<AccountActivity>,
+    pub dormant: Vec<AccountActivity>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoginTrendBucket {
+    pub bucket_start: i64,
+    pub successes: i64,
+    pub failures: i64,
+    /// successes / (successes + failures), 0..1
+    pub success_rate: f64,
+}
+
+fn resolve_range(window: Option<String>, from_ts: Option<i64>, to_ts: Option<i64>) -> (i64, i64) {
+    let from = from_ts.unwrap_or_else(|| window_to_ts(window.as_deref().unwrap_or("1m")));
+    let to = to_ts.unwrap_or_else(|| Utc::now().timestamp());
+    (from, to)
+}
+
+/// SQL expression mapping `created_at` to the start of its bucket.
+fn bucket_expr(period: &str) -> Result<&'static str, String> {
+    match period {
+        "hour" => Ok("(created_at / 3600) * 3600"),
+        "day" => Ok("CAST(strftime('%s', created_at, 'unixepoch', 'start of day') AS INTEGER)"),
+        // Monday-based weeks
+        "week" => Ok(
+            "CAST(strftime('%s', created_at, 'unixepoch', 'start of day', '-6 days', 'weekday 1') AS INTEGER)",
+        ),
+        "month" => Ok("CAST(strftime('%s', created_at, 'unixepoch', 'start of month') AS INTEGER)"),
+        other => Err(format!("unknown period '{}'", other)),
+    }
+}
+
+fn user_login_timeline(
+    conn: &Connection,
+    user_id: Option<i64>,
+    username: Option<String>,
+    from: i64,
+    to: i64,
+    limit: i64,
+) -> Result<LoginTimeline, String> {
+    let (user_id, username, last_login) = if let Some(uid) = user_id {
+        let u = crate::db::get_user_by_id_row(conn, uid)?.ok_or("User not found".to_string())?;
+        (Some(u.id), u.username, u.last_login)
+    } else {
+        let name = username.ok_or("user_id or username required".to_string())?;
+        match crate::db::get_user_by_username_row(conn, &name)? {
+            Some(u) => (Some(u.id), u.username, u.last_login),
+            // attempts against a username that never existed are still reportable
+            None => (None, name, None),
+        }
+    };
+
+    // unknown-username attempts are recorded with user_id NULL
+    let filter = "(user_id = ?1 OR (user_id IS NULL AND username = ?2)) AND created_at >= ?3 AND created_at <= ?4";
+    let (successes, failures): (i64, i64) = conn
+        .query_row(
+            &format!(
+                "SELECT COALESCE(SUM(success = 1), 0), COALESCE(SUM(success = 0), 0) FROM login_history WHERE {}",
+                filter
+            ),
+            params![user_id, username, from, to],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT id, user_id, username, success, ip, created_at FROM login_history
+             WHERE {} ORDER BY created_at DESC, id DESC LIMIT ?5",
+            filter
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![user_id, username, from, to, limit], |r| {
+            Ok(LoginEvent {
+                id: r.get(0)?,
+                user_id: r.get(1)?,
+                username: r.get(2)?,
+                success: r.get::<_, i64>(3)? != 0,
+                ip: r.get(4)?,
+                created_at: r.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    let mut events = Vec::new();
+    for r in rows {
+        events.push(r.map_err(|e| e.to_string())?);
+    }
+
+    Ok(LoginTimeline {
+        user_id,
+        username,
+        last_login,
+        successes,
+        failures,
+        events,
+    })
+}
+
+fn failed_login_groups(
+    conn: &Connection,
+    group_by: &str,
+    from: i64,
+    to: i64,
+    min_attempts: i64,
+    limit: i64,
+) -> Result<Vec<FailedLoginGroup>, String> {
+    let (username_col, ip_col, group) = match group_by {
+        "username" => ("f.username", "NULL", "f.username"),
+        "ip" => ("NULL", "f.ip", "f.ip"),
+        "username_ip" => ("f.username", "f.ip", "f.username, f.ip"),
+        other => return Err(format!("unknown group_by '{}'", other)),
+    };
+    let known = if group_by == "ip" {
+        "0"
+    } else {
+        "EXISTS(SELECT 1 FROM users u WHERE u.username = f.username)"
+    };
+    let sql = format!(
+        "SELECT {u}, {i}, COUNT(*), MIN(f.attempted_at), MAX(f.attempted_at), {k}
+         FROM failed_logins f
+         WHERE f.attempted_at >= ?1 AND f.attempted_at <= ?2
+         GROUP BY {g}
+         HAVING COUNT(*) >= ?3
+         ORDER BY COUNT(*) DESC, MAX(f.attempted_at) DESC
+         LIMIT ?4",
+        u = username_col,
+        i = ip_col,
+        k = known,
+        g = group
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![from, to, min_attempts, limit], |r| {
+            Ok(FailedLoginGroup {
+                username: r.get(0)?,
+                ip: r.get(1)?,
+                attempts: r.get(2)?,
+                first_at: r.get(3)?,
+                last_at: r.get(4)?,
+                known_user: r.get::<_, i64>(5)? != 0,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(out)
+}
+
+fn inactive_accounts(conn: &Connection, dormant_days: i64) -> Result<InactiveAccounts, String> {
+    let now = Utc::now().timestamp();
+    let cutoff = now.saturating_sub(dormant_days.saturating_mul(86_400));
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, username, name, role, created_at, last_login FROM users
+             WHERE last_login IS NULL OR last_login < ?1
+             ORDER BY COALESCE(last_login, created_at) ASC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![cutoff], |r| {
+            let created_at: i64 = r.get(4)?;
+            let last_login: Option<i64> = r.get(5)?;
+            Ok(AccountActivity {
+                id: r.get(0)?,
+                username: r.get(1)?,
+                name: r.get(2)?,
+                role: r.get(3)?,
+                created_at,
+                last_login,
+                days_inactive: (now - last_login.unwrap_or(created_at)).max(0) / 86_400,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut never_logged_in = Vec::new();
+    let mut dormant = Vec::new();
+    for r in rows {
+        let a = r.map_err(|e| e.to_string())?;
+        if a.last_login.is_none() {
+            never_logged_in.push(a);
+        } else {
+            dormant.push(a);
+        }
+    }
+    Ok(InactiveAccounts {
+        dormant_days,
+        never_logged_in,
+        dormant,
+    })
+}
+
+fn login_trend(
+    conn: &Connection,
+    period: &str,
+    from: i64,
+    to: i64,
+    user_id: Option<i64>,
+) -> Result<Vec<LoginTrendBucket>, String> {
+    let bucket = bucket_expr(period)?;
+    let mut sql = format!(
+        "SELECT {b} AS bucket, COALESCE(SUM(success = 1), 0), COALESCE(SUM(success = 0), 0)
+         FROM login_history WHERE created_at >= ? AND created_at <= ?",
+        b = bucket
+    );
+    let mut vals: Vec<rusqlite::types::Value> = vec![from.into(), to.into()];
+    if let Some(uid) = user_id {
+        sql.push_str(" AND user_id = ?");
+        vals.push(uid.into());
+    }
+    sql.push_str(" GROUP BY bucket ORDER BY bucket ASC");
+
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params_from_iter(vals), |r| {
+            let successes: i64 = r.get(1)?;
+            let failures: i64 = r.get(2)?;
+            let total = successes + failures;
+            Ok(LoginTrendBucket {
+                bucket_start: r.get(0)?,
+                successes,
+                failures,
+                success_rate: if total > 0 {
+                    successes as f64 / total as f64
+                } else {
+                    0.0
+                },
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(out)
+}
+
+/// Login successes/failures for one account (by id or username), newest first.
+#[command]
+pub fn admin_login_timeline(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    user_id: Option<i64>,
+    username: Option<String>,
+    window: Option<String>,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+    limit: Option<i64>,
+) -> Result<LoginTimeline, String> {
+    let conn = crate::db::open_connection()?;
+    crate::db::init_db_schema(&conn)?;
+    let (admin_id, admin_username) = ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let (from, to) = resolve_range(window, from_ts, to_ts);
+    let timeline = user_login_timeline(&conn, user_id, username, from, to, limit.unwrap_or(500))?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_report_login_timeline",
+        timeline.user_id,
+        Some(&format!("Login timeline for '{}'", timeline.username)),
+    );
+    Ok(timeline)
+}
+
+/// Failed attempts grouped by "username", "ip" or "username_ip".
+#[command]
+pub fn admin_failed_login_report(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    group_by: Option<String>,
+    window: Option<String>,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+    min_attempts: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Vec<FailedLoginGroup>, String> {
+    let conn = crate::db::open_connection()?;
+    crate::db::init_db_schema(&conn)?;
+    let (admin_id, admin_username) = ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let group_by = group_by.unwrap_or_else(|| "username".to_string());
+    let (from, to) = resolve_range(window, from_ts, to_ts);
+    let out = failed_login_groups(
+        &conn,
+        &group_by,
+        from,
+        to,
+        min_attempts.unwrap_or(1).max(1),
+        limit.unwrap_or(200),
+    )?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_report_failed_logins",
+        None,
+        Some(&format!("Failed-login report by {} ({} groups)", group_by, out.len())),
+    );
+    Ok(out)
+}
+
+/// Accounts that never logged in, and accounts idle for `dormant_days` (default 90).
+#[command]
+pub fn admin_inactive_accounts(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    dormant_days: Option<i64>,
+) -> Result<InactiveAccounts, String> {
+    let conn = crate::db::open_connection()?;
+    crate::db::init_db_schema(&conn)?;
+    let (admin_id, admin_username) = ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let days = dormant_days
+        .unwrap_or(DEFAULT_DORMANT_DAYS)
+        .clamp(1, MAX_DORMANT_DAYS);
+    let out = inactive_accounts(&conn, days)?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_report_inactive_accounts",
+        None,
+        Some(&format!(
+            "{} never logged in, {} dormant > {} days",
+            out.never_logged_in.len(),
+            out.dormant.len(),
+            days
+        )),
+    );
+    Ok(out)
+}
+
+/// Success rate per "hour" | "day" | "week" | "month" bucket, optionally for one user.
+/// Buckets without any login attempt are omitted.
+#[command]
+pub fn admin_login_success_trend(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    period: Option<String>,
+    window: Option<String>,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+    user_id: Option<i64>,
+) -> Result<Vec<LoginTrendBucket>, String> {
+    let conn = crate::db::open_connection()?;
+    crate::db::init_db_schema(&conn)?;
+    let (admin_id, admin_username) = ensure_admin_session(&conn, session_token, sessionToken)?;
+
+    let period = period.unwrap_or_else(|| "day".to_string());
+    let (from, to) = resolve_range(window, from_ts, to_ts);
+    let out = login_trend(&conn, &period, from, to, user_id)?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_report_login_trend",
+        user_id,
+        Some(&format!("Login success trend per {} ({} buckets)", period, out.len())),
+    );
+    Ok(out)
+}
@@ -1,25 +1,75 @@
 // src-tauri/src/main.rs
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod activity_reports;
 mod admin_backend;
+mod alerts;
+mod archive_index;
 mod audit;
+mod audit_export;
+mod audit_forward;
+mod audit_retention;
+mod audit_stream;
 mod auth_backend;
+mod content_extract;
+mod content_hash;
+mod content_index;
 mod db;
+mod duplicates;
+mod file_history;
+mod file_metadata;
 mod fs_ops;
 mod fs_watch;
+mod index_jobs;
+mod index_rules;
+mod indexer;
+mod locations;
+mod login_reports;
+mod ocr;
+mod scheduler;
 mod security;
 mod session;
 mod session_store; // NEW
+mod space_analytics;
+mod storage_history;
+mod thumbnails;
 mod user_backend;
+mod volumes;
 
+use activity_reports::{admin_activity_overview, admin_user_activity_report};
 use admin_backend::{
     admin_create_user_cmd, admin_delete_user_cmd, admin_get_user_cmd, admin_list_users_cmd,
     admin_update_user_cmd,
 };
+use alerts::{
+    admin_acknowledge_alert, admin_list_alert_rules, admin_list_alerts, admin_update_alert_rule,
+};
+use archive_index::{
+    admin_get_archive_settings, admin_set_archive_settings, extract_archive_member,
+};
 use audit::{admin_list_audit_logs, get_portal_audit_logs, get_watchlist_blocked_attempts}; // your renamed command in audit.rs
+use audit_export::{admin_export_audit, admin_verify_audit_export};
+use audit_forward::{
+    admin_get_audit_forwarding, admin_set_audit_forwarding, admin_test_audit_forwarding,
+};
+use audit_retention::{
+    admin_delete_retention_policy, admin_list_audit_archives, admin_list_retention_policies,
+    admin_query_audit_archive, admin_restore_audit_archive, admin_run_retention,
+    admin_set_retention_policy,
+};
+use audit_stream::{
+    audit_stream_ack, audit_stream_resume, audit_stream_subscribe, audit_stream_unsubscribe,
+};
 use auth_backend::{
     auth_login, auth_logout, auth_register, get_profile_by_session, validate_session,
 };
+use content_hash::{
+    admin_get_hash_settings, admin_set_hash_settings, find_files_by_hash, get_file_hash,
+};
+use content_index::{content_index_rebuild, content_index_status};
+use duplicates::{admin_find_duplicates, admin_list_duplicate_removals, admin_resolve_duplicates};
+use file_history::admin_file_history;
+use file_metadata::get_file_metadata;
 use fs_ops::{
     fs_copy, fs_copy_by_session, fs_create_file_by_session, fs_delete_by_session,
     fs_list_tags_by_session, fs_mkdir_by_session, fs_move, fs_move_by_session,
@@ -30,14 +80,60 @@ use fs_ops::{
     recent_items, search_files, search_files_by_tag,
 };
 // use fs_watch::{start_fs_watcher};
+use index_jobs::{
+    admin_get_index_throttle, admin_set_index_throttle, index_job_cancel, index_job_pause,
+    index_job_resume, list_index_jobs,
+};
+use index_rules::{
+    admin_add_index_rule, admin_check_index_path, admin_delete_index_rule,
+    admin_list_index_rules, admin_set_index_limits, admin_set_index_rule_enabled,
+};
+use locations::{
+    admin_create_storage_location, admin_delete_storage_location, admin_list_storage_locations,
+    admin_update_storage_location,
+};
+use login_reports::{
+    admin_failed_login_report, admin_inactive_accounts, admin_login_success_trend,
+    admin_login_timeline,
+};
+use ocr::{admin_get_ocr_settings, admin_ocr_retry_failed, admin_set_ocr_settings};
+use scheduler::{
+    admin_create_scheduled_job, admin_delete_scheduled_job, admin_list_scheduled_job_runs,
+    admin_list_scheduled_jobs, admin_run_scheduled_job, admin_update_scheduled_job,
+};
 use session_store::{session_store_clear, session_store_get, session_store_set};
+use space_analytics::{
+    admin_folder_sizes, admin_largest_files, admin_old_large_files, admin_space_breakdown,
+};
+use storage_history::{admin_storage_history, admin_storage_sample_now, admin_storage_trends};
+use thumbnails::{
+    admin_clear_thumbnail_cache, admin_get_thumbnail_settings, admin_set_thumbnail_settings,
+    get_thumbnail,
+};
 use user_backend::{
     admin_can_create_user_cmd, admin_can_delete_user_cmd, admin_can_list_users_cmd,
     admin_can_update_user_cmd, update_profile_by_session,
 };
+use volumes::admin_list_volumes;
 
 fn main() {
     tauri::Builder::default()
+        .setup(|app| {
+            audit_stream::init(app.handle().clone());
+            audit_forward::start_forwarder();
+            // re-base volumes that came back under a different mount point
+            volumes::refresh_logged();
+            // continue index jobs cut short by a crash or restart
+            index_jobs::resume_interrupted(app.handle().clone());
+            // pick up documents left unextracted or unhashed by a previous run
+            content_index::schedule_pass();
+            content_hash::schedule_pass();
+            archive_index::schedule_pass();
+            archive_index::prune_open_copies();
+            // recurring scans and maintenance; catches up runs missed while closed
+            scheduler::start_scheduler();
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // auth
             auth_login,
@@ -60,6 +156,43 @@ fn main() {
             admin_list_audit_logs,
             get_portal_audit_logs,
             get_watchlist_blocked_attempts,
+            admin_export_audit,
+            admin_verify_audit_export,
+            admin_list_retention_policies,
+            admin_set_retention_policy,
+            admin_delete_retention_policy,
+            admin_run_retention,
+            admin_list_audit_archives,
+            admin_restore_audit_archive,
+            admin_query_audit_archive,
+            admin_get_audit_forwarding,
+            admin_set_audit_forwarding,
+            admin_test_audit_forwarding,
+            // live audit stream
+            audit_stream_subscribe,
+            audit_stream_resume,
+            audit_stream_ack,
+            audit_stream_unsubscribe,
+            // login reports (admin)
+            admin_login_timeline,
+            admin_failed_login_report,
+            admin_inactive_accounts,
+            admin_login_success_trend,
+            admin_user_activity_report,
+            admin_activity_overview,
+            admin_file_history,
+            // alerts (admin)
+            admin_list_alerts,
+            admin_acknowledge_alert,
+            admin_list_alert_rules,
+            admin_update_alert_rule,
+            // scheduled jobs (admin)
+            admin_list_scheduled_jobs,
+            admin_create_scheduled_job,
+            admin_update_scheduled_job,
+            admin_delete_scheduled_job,
+            admin_run_scheduled_job,
+            admin_list_scheduled_job_runs,
             // session store (OS keyring)
             session_store_set,
             session_store_get,
@@ -76,10 +209,54 @@ fn main() {
             get_files_per_drive,
             get_indexing_by_drive_and_type,
             get_storage_info_with_scan,
+            admin_storage_history,
+            admin_storage_trends,
+            admin_storage_sample_now,
+            admin_largest_files,
+            admin_old_large_files,
+            admin_space_breakdown,
+            admin_folder_sizes,
             search_files,
+            get_file_metadata,
+            get_thumbnail,
+            admin_get_thumbnail_settings,
+            admin_set_thumbnail_settings,
+            admin_clear_thumbnail_cache,
+            extract_archive_member,
+            admin_get_archive_settings,
+            admin_set_archive_settings,
+            content_index_status,
+            content_index_rebuild,
+            find_files_by_hash,
+            get_file_hash,
+            admin_get_hash_settings,
+            admin_set_hash_settings,
+            admin_find_duplicates,
+            admin_resolve_duplicates,
+            admin_list_duplicate_removals,
+            admin_get_ocr_settings,
+            admin_set_ocr_settings,
+            admin_ocr_retry_failed,
             index_path,
             index_path_start,
             get_index_status,
+            list_index_jobs,
+            index_job_pause,
+            index_job_resume,
+            index_job_cancel,
+            admin_get_index_throttle,
+            admin_set_index_throttle,
+            admin_list_index_rules,
+            admin_add_index_rule,
+            admin_set_index_rule_enabled,
+            admin_delete_index_rule,
+            admin_set_index_limits,
+            admin_check_index_path,
+            admin_list_volumes,
+            admin_list_storage_locations,
+            admin_create_storage_location,
+            admin_update_storage_location,
+            admin_delete_storage_location,
             index_all_drives_start,
             get_indexing_summary_global,
             get_session_user,
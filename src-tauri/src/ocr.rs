@@ -0,0 +1,711 @@
+// src-tauri/src/ocr.rs
+//
+// Optional offline OCR for scanned PDFs and image files, using the local
+// `tesseract` binary (with the configured language packs, English + Hindi by
+// default) and poppler's `pdftoppm` to rasterise PDF pages. Disabled until an
+// admin turns it on.
+//
+// The stage runs after each content pass (content_index.rs) on files whose
+// extracted text is empty (images) or which have pages without a text layer
+// (PDFs; only those pages are OCR'd). Tools run at reduced OS priority, one
+// file at a time. Results are cached in `ocr_cache` by SHA-256 of the file
+// bytes together with the settings that produced them (languages, dpi, page
+// limit, tesseract version), so a file is never OCR'd twice with the same
+// settings, even after a move or as a copy.
+use crate::content_extract::{self, MAX_TEXT_CHARS};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle};
+
+const CONFIG_KEY: &str = "ocr.config";
+const BATCH: i64 = 50;
+/// Pause between files so OCR never saturates the machine.
+const YIELD_BETWEEN_FILES: Duration = Duration::from_millis(200);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static RERUN: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrConfig {
+    pub enabled: bool,
+    /// tesseract `-l` value, e.g. "eng+hin"
+    #[serde(default = "default_languages")]
+    pub languages: String,
+    /// Explicit binary paths; looked up on PATH when unset.
+    #[serde(default)]
+    pub tesseract_path: Option<String>,
+    #[serde(default)]
+    pub pdftoppm_path: Option<String>,
+    /// Rasterisation resolution for PDF pages.
+    #[serde(default = "default_dpi")]
+    pub dpi: u32,
+    /// Textless pages OCR'd per PDF; later pages are skipped.
+    #[serde(default = "default_max_pages")]
+    pub max_pages: u32,
+    /// Limit for a single tool invocation (one page or one image).
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_languages() -> String {
+    "eng+hin".into()
+}
+
+fn default_dpi() -> u32 {
+    300
+}
+
+fn default_max_pages() -> u32 {
+    50
+}
+
+fn default_timeout_secs() -> u64 {
+    120
+}
+
+impl Default for OcrConfig {
+    fn default() -> Self {
+        OcrConfig {
+            enabled: false,
+            languages: default_languages(),
+            tesseract_path: None,
+            pdftoppm_path: None,
+            dpi: default_dpi(),
+            max_pages: default_max_pages(),
+            timeout_secs: default_timeout_secs(),
+        }
+    }
+}
+
+pub fn load_config(conn: &Connection) -> Result<OcrConfig, String> {
+    match crate::db::get_setting(conn, CONFIG_KEY)? {
+        Some(raw) => serde_json::from_str(&raw).map_err(|e| format!("ocr config: {}", e)),
+        None => Ok(OcrConfig::default()),
+    }
+}
+
+fn validate_config(cfg: &OcrConfig) -> Result<(), String> {
+    let langs_ok = !cfg.languages.is_empty()
+        && cfg
+            .languages
+            .split('+')
+            .all(|l| !l.is_empty() && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+    if !langs_ok {
+        return Err("languages must look like 'eng' or 'eng+hin'".into());
+    }
+    if !(72..=600).contains(&cfg.dpi) {
+        return Err("dpi must be 72..=600".into());
+    }
+    if !(1..=1000).contains(&cfg.max_pages) {
+        return Err("max_pages must be 1..=1000".into());
+    }
+    if !(5..=3600).contains(&cfg.timeout_secs) {
+        return Err("timeout_secs must be 5..=3600".into());
+    }
+    for (name, path) in [
+        ("tesseract_path", &cfg.tesseract_path),
+        ("pdftoppm_path", &cfg.pdftoppm_path),
+    ] {
+        if let Some(p) = path {
+            let p = Path::new(p);
+            if !p.is_absolute() || !p.is_file() {
+                return Err(format!("{} must be the absolute path of an existing file", name));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn ensure_tables(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS ocr_cache (
+            sha256 TEXT PRIMARY KEY,
+            status TEXT NOT NULL,
+            text TEXT NOT NULL DEFAULT '',
+            languages TEXT NOT NULL,
+            pages INTEGER NOT NULL DEFAULT 0,
+            error TEXT,
+            duration_ms INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL
+        );",
+    )
+    .map_err(|e| e.to_string())?;
+    crate::db::ensure_column(conn, "ocr_cache", "settings", "TEXT NOT NULL DEFAULT ''")?;
+    crate::fs_ops::ensure_files_index_tables(conn)
+}
+
+// -----------------------------------------------------------------------------
+// External tools
+// -----------------------------------------------------------------------------
+
+struct Tools {
+    tesseract: String,
+    /// `tesseract --version` first line, part of the cache key.
+    tesseract_version: String,
+    pdftoppm: Option<String>,
+}
+
+pub(crate) enum ToolError {
+    Timeout,
+    Failed(String),
+}
+
+/// Command for `program` at reduced scheduling priority.
+pub(crate) fn low_priority(program: &str) -> Command {
+    #[cfg(unix)]
+    {
+        for nice in ["/usr/bin/nice", "/bin/nice"] {
+            if Path::new(nice).exists() {
+                let mut cmd = Command::new(nice);
+                cmd.arg("-n").arg("19").arg(program);
+                return cmd;
+            }
+        }
+    }
+    #[allow(unused_mut)]
+    let mut cmd = Command::new(program);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x0000_4000;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        cmd.creation_flags(BELOW_NORMAL_PRIORITY_CLASS | CREATE_NO_WINDOW);
+    }
+    cmd
+}
+
+pub(crate) fn run_tool(mut cmd: Command, timeout: Duration) -> Result<(), ToolError> {
+    // tesseract parallelises internally; keep it to one core
+    cmd.env("OMP_THREAD_LIMIT", "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| ToolError::Failed(format!("spawn: {}", e)))?;
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(st)) if st.success() => return Ok(()),
+            Ok(Some(st)) => return Err(ToolError::Failed(format!("exited with {}", st))),
+            Ok(None) if started.elapsed() > timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(ToolError::Timeout);
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(100)),
+            Err(e) => return Err(ToolError::Failed(e.to_string())),
+        }
+    }
+}
+
+/// First line of `<program> <arg>` output, if the program runs at all.
+pub(crate) fn tool_probe(program: &str, arg: &str) -> Option<String> {
+    let out = Command::new(program).arg(arg).output().ok()?;
+    let text = if out.stdout.is_empty() { out.stderr } else { out.stdout };
+    String::from_utf8_lossy(&text)
+        .lines()
+        .next()
+        .map(|l| l.trim().to_string())
+}
+
+fn tesseract_languages(program: &str) -> Vec<String> {
+    Command::new(program)
+        .arg("--list-langs")
+        .output()
+        .map(|o| {
+            // "List of available languages ..." header, then one per line
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .skip(1)
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn resolve_tools(cfg: &OcrConfig) -> Option<Tools> {
+    let tesseract = cfg.tesseract_path.clone().unwrap_or_else(|| "tesseract".into());
+    let tesseract_version = tool_probe(&tesseract, "--version")?;
+    let pdftoppm = cfg.pdftoppm_path.clone().unwrap_or_else(|| "pdftoppm".into());
+    let pdftoppm = tool_probe(&pdftoppm, "-v").map(|_| pdftoppm);
+    Some(Tools {
+        tesseract,
+        tesseract_version,
+        pdftoppm,
+    })
+}
+
+// -----------------------------------------------------------------------------
+// OCR
+// -----------------------------------------------------------------------------
+
+struct OcrResult {
+    status: &'static str,
+    text: String,
+    pages: u32,
+    error: Option<String>,
+}
+
+impl OcrResult {
+    fn failed(err: ToolError, text: String, pages: u32) -> Self {
+        match err {
+            ToolError::Timeout => OcrResult {
+                status: "timeout",
+                text,
+                pages,
+                error: Some("ocr timed out".into()),
+            },
+            ToolError::Failed(m) => OcrResult { status: "error", text, pages, error: Some(m) },
+        }
+    }
+
+    fn finished(text: String, pages: u32) -> Self {
+        let text = content_extract::normalize_whitespace(&text);
+        let status = if text.is_empty() { "empty" } else { "ok" };
+        OcrResult { status, text, pages, error: None }
+    }
+}
+
+/// OCR one image into text via `<work>/out.txt`.
+fn tesseract_image(
+    tools: &Tools,
+    cfg: &OcrConfig,
+    image: &Path,
+    work: &Path,
+) -> Result<String, ToolError> {
+    let out_base = work.join("out");
+    let mut cmd = low_priority(&tools.tesseract);
+    cmd.arg(image).arg(&out_base).arg("-l").arg(&cfg.languages);
+    run_tool(cmd, Duration::from_secs(cfg.timeout_secs))
+        .map_err(|e| match e {
+            ToolError::Failed(m) => ToolError::Failed(format!("tesseract: {}", m)),
+            t => t,
+        })?;
+    let txt = out_base.with_extension("txt");
+    let text = std::fs::read_to_string(&txt)
+        .map_err(|e| ToolError::Failed(format!("tesseract output: {}", e)))?;
+    let _ = std::fs::remove_file(txt);
+    Ok(text)
+}
+
+fn ocr_pdf(tools: &Tools, cfg: &OcrConfig, pdf: &Path, pages: &[u32], work: &Path) -> OcrResult {
+    let pdftoppm = match &tools.pdftoppm {
+        Some(p) => p,
+        None => return OcrResult::failed(ToolError::Failed("pdftoppm not found".into()), String::new(), 0),
+    };
+    let mut text = String::new();
+    let mut done = 0u32;
+    for page in pages.iter().take(cfg.max_pages as usize) {
+        let prefix = work.join("page");
+        let mut cmd = low_priority(pdftoppm);
+        cmd.arg("-r")
+            .arg(cfg.dpi.to_string())
+            .arg("-f")
+            .arg(page.to_string())
+            .arg("-l")
+            .arg(page.to_string())
+            .arg("-singlefile")
+            .arg("-png")
+            .arg(pdf)
+            .arg(&prefix);
+        if let Err(e) = run_tool(cmd, Duration::from_secs(cfg.timeout_secs)) {
+            let e = match e {
+                ToolError::Failed(m) => ToolError::Failed(format!("pdftoppm page {}: {}", page, m)),
+                t => t,
+            };
+            return OcrResult::failed(e, text, done);
+        }
+        let png = prefix.with_extension("png");
+        let page_text = tesseract_image(tools, cfg, &png, work);
+        let _ = std::fs::remove_file(&png);
+        match page_text {
+            Ok(t) => {
+                text.push_str(&t);
+                text.push_str("\n\n");
+                done += 1;
+            }
+            Err(e) => return OcrResult::failed(e, text, done),
+        }
+    }
+    OcrResult::finished(text, done)
+}
+
+/// Everything besides the file bytes that changes what OCR produces.
+fn cache_settings(cfg: &OcrConfig, tools: &Tools) -> String {
+    format!(
+        "languages={};dpi={};max_pages={};engine={}",
+        cfg.languages, cfg.dpi, cfg.max_pages, tools.tesseract_version
+    )
+}
+
+fn cached(
+    conn: &Connection,
+    sha256: &str,
+    settings: &str,
+) -> Result<Option<(String, String)>, String> {
+    conn.query_row(
+        "SELECT status, text FROM ocr_cache WHERE sha256 = ?1 AND settings = ?2",
+        params![sha256, settings],
+        |r| Ok((r.get(0)?, r.get(1)?)),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+fn store_cache(
+    conn: &Connection,
+    sha256: &str,
+    settings: &str,
+    cfg: &OcrConfig,
+    res: &OcrResult,
+    duration_ms: i64,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO ocr_cache (sha256, status, text, languages, pages, error, duration_ms, created_at, settings)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            sha256,
+            res.status,
+            res.text,
+            cfg.languages,
+            res.pages,
+            res.error,
+            duration_ms,
+            chrono::Utc::now().timestamp(),
+            settings
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Append OCR text to the file's searchable body and record the outcome.
+fn apply(conn: &Connection, file_id: i64, sha256: &str, status: &str, text: &str) -> Result<(), String> {
+    if !text.is_empty() {
+        let body: String = conn
+            .query_row(
+                "SELECT body FROM files_index_fts WHERE rowid = ?1",
+                params![file_id],
+                |r| r.get::<_, Option<String>>(0),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?
+            .flatten()
+            .unwrap_or_default();
+        let merged = if body.trim().is_empty() {
+            text.to_string()
+        } else {
+            format!("{}\n\n{}", body, text)
+        };
+        conn.execute(
+            "UPDATE files_index_fts SET body = ?1 WHERE rowid = ?2",
+            params![content_extract::truncate_chars(merged, MAX_TEXT_CHARS), file_id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    conn.execute(
+        "UPDATE file_content SET ocr_status = ?1, ocr_sha256 = ?2 WHERE file_id = ?3",
+        params![status, sha256, file_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn process_file(
+    conn: &Connection,
+    cfg: &OcrConfig,
+    tools: &Tools,
+    file_id: i64,
+    path: &str,
+    extractor: &str,
+) -> Result<(), String> {
+    let bytes = match std::fs::read(path) {
+        Ok(b) => b,
+        Err(e) => {
+            conn.execute(
+                "UPDATE file_content SET ocr_status = 'error' WHERE file_id = ?1",
+                params![file_id],
+            )
+            .map_err(|e| e.to_string())?;
+            return Err(format!("read {}: {}", path, e));
+        }
+    };
+    let sha256 = crate::security::sha256_hex(&bytes);
+    let settings = cache_settings(cfg, tools);
+    if let Some((status, text)) = cached(conn, &sha256, &settings)? {
+        return apply(conn, file_id, &sha256, &status, &text);
+    }
+
+    let started = Instant::now();
+    let work = std::env::temp_dir().join(format!("vaultguard-ocr-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&work).map_err(|e| format!("ocr work dir: {}", e))?;
+    let result = if extractor == "pdf" {
+        match content_extract::pdf_pages_without_text(&bytes) {
+            Ok((_, pages)) if pages.is_empty() => OcrResult {
+                status: "not_needed",
+                text: String::new(),
+                pages: 0,
+                error: None,
+            },
+            Ok((_, pages)) => ocr_pdf(tools, cfg, Path::new(path), &pages, &work),
+            Err(e) => OcrResult::failed(ToolError::Failed(e), String::new(), 0),
+        }
+    } else {
+        match tesseract_image(tools, cfg, Path::new(path), &work) {
+            Ok(t) => OcrResult::finished(t, 1),
+            Err(e) => OcrResult::failed(e, String::new(), 0),
+        }
+    };
+    let _ = std::fs::remove_dir_all(&work);
+
+    let duration_ms = started.elapsed().as_millis() as i64;
+    store_cache(conn, &sha256, &settings, cfg, &result, duration_ms)?;
+    apply(conn, file_id, &sha256, result.status, &result.text)
+}
+
+/// Request an OCR pass; coalesces like `content_index::schedule_pass`.
+pub fn schedule_pass() {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        RERUN.store(true, Ordering::SeqCst);
+        return;
+    }
+    let spawned = std::thread::Builder::new()
+        .name("ocr".into())
+        .spawn(|| loop {
+            RERUN.store(false, Ordering::SeqCst);
+            if let Err(e) = run_pass() {
+                eprintln!("[ocr] pass failed: {}", e);
+            }
+            if !RERUN.load(Ordering::SeqCst) {
+                RUNNING.store(false, Ordering::SeqCst);
+                if RERUN.load(Ordering::SeqCst) && !RUNNING.swap(true, Ordering::SeqCst) {
+                    continue;
+                }
+                break;
+            }
+        });
+    if spawned.is_err() {
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+// archive members have no file on disk for the OCR tools to read
+const PENDING_FILTER: &str = "fc.ocr_status IS NULL
+           AND fc.status IN ('ok', 'empty')
+           AND fc.extractor IN ('pdf', 'image')
+           AND fc.file_id IN (SELECT id FROM files_index WHERE file_type = 'file')";
+
+fn run_pass() -> Result<usize, String> {
+    let conn = crate::db::open_connection()?;
+    ensure_tables(&conn)?;
+    let cfg = load_config(&conn)?;
+    if !cfg.enabled {
+        return Ok(0);
+    }
+    let tools = resolve_tools(&cfg).ok_or_else(|| "tesseract not found".to_string())?;
+
+    let sql = format!(
+        "SELECT fc.file_id, fi.path, fc.extractor
+         FROM file_content fc
+         JOIN files_index fi ON fi.id = fc.file_id
+         WHERE fc.file_id > ?1 AND {}
+         ORDER BY fc.file_id
+         LIMIT ?2",
+        PENDING_FILTER
+    );
+    let mut cursor = 0i64;
+    let mut done = 0usize;
+    loop {
+        let batch: Vec<(i64, String, String)> = {
+            let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map(params![cursor, BATCH], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+                .map_err(|e| e.to_string())?;
+            rows.filter_map(|r| r.ok()).collect()
+        };
+        if batch.is_empty() {
+            break;
+        }
+        for (file_id, path, extractor) in batch {
+            cursor = file_id;
+            // honour a disable while the pass is running
+            let cfg = load_config(&conn)?;
+            if !cfg.enabled {
+                return Ok(done);
+            }
+            // without pdftoppm, PDFs stay pending until it is installed
+            if extractor == "pdf" && tools.pdftoppm.is_none() {
+                continue;
+            }
+            if let Err(e) = process_file(&conn, &cfg, &tools, file_id, &path, &extractor) {
+                eprintln!("[ocr] {}: {}", path, e);
+            }
+            done += 1;
+            std::thread::sleep(YIELD_BETWEEN_FILES);
+        }
+    }
+    Ok(done)
+}
+
+// -----------------------------------------------------------------------------
+// Commands
+// -----------------------------------------------------------------------------
+
+#[derive(Serialize)]
+pub struct OcrStatus {
+    pub config: OcrConfig,
+    pub tesseract_version: Option<String>,
+    pub pdftoppm_version: Option<String>,
+    pub languages_available: Vec<String>,
+    /// Configured languages without an installed traineddata pack.
+    pub languages_missing: Vec<String>,
+    pub running: bool,
+    pub pending: i64,
+    /// (ocr_status, count) over file_content.
+    pub by_status: Vec<(String, i64)>,
+    pub cached: i64,
+}
+
+fn status(conn: &Connection) -> Result<OcrStatus, String> {
+    let config = load_config(conn)?;
+    let tesseract = config.tesseract_path.clone().unwrap_or_else(|| "tesseract".into());
+    let pdftoppm = config.pdftoppm_path.clone().unwrap_or_else(|| "pdftoppm".into());
+    let tesseract_version = tool_probe(&tesseract, "--version");
+    let languages_available = if tesseract_version.is_some() {
+        tesseract_languages(&tesseract)
+    } else {
+        Vec::new()
+    };
+    let languages_missing = config
+        .languages
+        .split('+')
+        .filter(|l| !languages_available.iter().any(|a| a == l))
+        .map(|l| l.to_string())
+        .collect();
+
+    let pending: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM file_content fc WHERE {}", PENDING_FILTER),
+            [],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT ocr_status, COUNT(*) FROM file_content
+             WHERE ocr_status IS NOT NULL GROUP BY ocr_status ORDER BY ocr_status",
+        )
+        .map_err(|e| e.to_string())?;
+    let by_status = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    let cached: i64 = conn
+        .query_row("SELECT COUNT(*) FROM ocr_cache", [], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+
+    Ok(OcrStatus {
+        config,
+        tesseract_version,
+        pdftoppm_version: tool_probe(&pdftoppm, "-v"),
+        languages_available,
+        languages_missing,
+        running: RUNNING.load(Ordering::SeqCst),
+        pending,
+        by_status,
+        cached,
+    })
+}
+
+#[command]
+pub fn admin_get_ocr_settings(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<OcrStatus, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    ensure_tables(&conn)?;
+    status(&conn)
+}
+
+#[command]
+pub fn admin_set_ocr_settings(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    config: OcrConfig,
+) -> Result<OcrStatus, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    ensure_tables(&conn)?;
+
+    validate_config(&config)?;
+    if config.enabled && resolve_tools(&config).is_none() {
+        return Err("tesseract not found; install it or set tesseract_path".into());
+    }
+    let raw = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    crate::db::set_setting(&conn, CONFIG_KEY, &raw)?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_set_ocr_settings",
+        None,
+        Some(&format!(
+            "enabled={} languages={} dpi={} max_pages={}",
+            config.enabled, config.languages, config.dpi, config.max_pages
+        )),
+    );
+    if config.enabled {
+        schedule_pass();
+    }
+
+    status(&conn)
+}
+
+/// Forget failed/timed-out OCR results so those files are tried again.
+#[command]
+pub fn admin_ocr_retry_failed(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<usize, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    ensure_tables(&conn)?;
+
+    conn.execute("DELETE FROM ocr_cache WHERE status IN ('error', 'timeout')", [])
+        .map_err(|e| e.to_string())?;
+    let reset = conn
+        .execute(
+            "UPDATE file_content SET ocr_status = NULL, ocr_sha256 = NULL
+             WHERE ocr_status IN ('error', 'timeout')",
+            [],
+        )
+        .map_err(|e| e.to_string())?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_ocr_retry_failed",
+        None,
+        Some(&format!("reset:{}", reset)),
+    );
+    schedule_pass();
+    Ok(reset)
+}
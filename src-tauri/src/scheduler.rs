@@ -0,0 +1,914 @@
+// src-tauri/src/scheduler.rs
+//
+// Persistent scheduler for recurring background work: index rescans of a
+// root, audit retention, expired-session cleanup, hash verification, disk
+// usage sampling and database maintenance. Jobs live in `scheduled_jobs` with a cron expression
+// (five fields or @hourly/@daily/@weekly/..., evaluated in local time) and
+// every run is recorded in `scheduled_job_runs`.
+//
+// A ticker thread started at launch runs whatever is due. A run missed while
+// the app was closed is caught up once at startup (several missed runs
+// collapse into one) unless the job opts out, in which case the miss is
+// recorded as `skipped`. Jobs that touch the same resources never overlap:
+// a due job whose group is busy waits for a later tick, and `db_optimize`
+// excludes everything else. Work started outside the scheduler counts too:
+// a rescan waits for manual index jobs on an overlapping root and for the
+// background hash, content and archive passes. A job whose cron expression
+// yields no next run is disabled and the failure recorded in its history.
+use chrono::{Local, TimeZone, Utc};
+use croner::Cron;
+use once_cell::sync::Lazy;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{command, AppHandle};
+
+const TICK: Duration = Duration::from_secs(30);
+const SCHEDULER_ACTOR: &str = "scheduler";
+
+pub const KIND_RESCAN: &str = "rescan";
+pub const KIND_AUDIT_RETENTION: &str = "audit_retention";
+pub const KIND_SESSION_CLEANUP: &str = "session_cleanup";
+pub const KIND_HASH_VERIFY: &str = "hash_verify";
+pub const KIND_DB_OPTIMIZE: &str = "db_optimize";
+pub const KIND_STORAGE_SAMPLE: &str = "storage_sample";
+
+const KINDS: &[&str] = &[
+    KIND_RESCAN,
+    KIND_AUDIT_RETENTION,
+    KIND_SESSION_CLEANUP,
+    KIND_HASH_VERIFY,
+    KIND_DB_OPTIMIZE,
+    KIND_STORAGE_SAMPLE,
+];
+
+/// Jobs created once on first start (the marker setting remembers it, so an
+/// admin may delete them): (marker key, name, kind, cron).
+const DEFAULT_JOBS: &[(&str, &str, &str, &str)] = &[(
+    "scheduler.seeded.storage_sample",
+    "Disk usage sample",
+    KIND_STORAGE_SAMPLE,
+    "@hourly",
+)];
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+/// Scheduled runs due before this instant were missed while the app was down.
+static PROCESS_START: Lazy<i64> = Lazy::new(|| Utc::now().timestamp());
+/// (job id, kind) of runs in progress.
+static ACTIVE: Lazy<Mutex<Vec<(i64, String)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Resource group of a job kind; two jobs of the same group never overlap.
+fn group(kind: &str) -> &'static str {
+    match kind {
+        // both read the whole library and rewrite files_index / file_hashes
+        KIND_RESCAN | KIND_HASH_VERIFY => "files",
+        KIND_AUDIT_RETENTION => "audit",
+        KIND_SESSION_CLEANUP => "sessions",
+        KIND_STORAGE_SAMPLE => "storage",
+        _ => "db",
+    }
+}
+
+fn conflicts(a: &str, b: &str) -> bool {
+    a == KIND_DB_OPTIMIZE || b == KIND_DB_OPTIMIZE || group(a) == group(b)
+}
+
+/// Releases the job's slot in ACTIVE when its run ends.
+struct ActiveGuard(i64);
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        if let Ok(mut active) = ACTIVE.lock() {
+            active.retain(|(id, _)| *id != self.0);
+        }
+    }
+}
+
+/// Work outside the scheduler that `job` must not overlap, if any is running.
+fn busy_elsewhere(conn: &Connection, job: &ScheduledJob) -> Result<Option<String>, String> {
+    let passes = || {
+        [
+            ("content hash", crate::content_hash::pass_running()),
+            ("content", crate::content_index::pass_running()),
+            ("archive", crate::archive_index::pass_running()),
+        ]
+        .into_iter()
+        .find(|(_, running)| *running)
+        .map(|(name, _)| format!("{} pass is running", name))
+    };
+    match job.kind.as_str() {
+        KIND_RESCAN => {
+            let root = Path::new(job.params.get("root").and_then(|v| v.as_str()).unwrap_or(""));
+            let overlapping = crate::index_jobs::live_roots(conn)?
+                .into_iter()
+                .find(|r| r.starts_with(root) || root.starts_with(r));
+            if let Some(r) = overlapping {
+                return Ok(Some(format!("index job on {} is running", r.display())));
+            }
+            Ok(passes())
+        }
+        KIND_HASH_VERIFY | KIND_DB_OPTIMIZE => {
+            if !crate::index_jobs::live_roots(conn)?.is_empty() {
+                return Ok(Some("an index job is running".into()));
+            }
+            Ok(passes())
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Claim a slot for `job`, or name the running job it conflicts with.
+fn try_claim(job_id: i64, kind: &str) -> Result<ActiveGuard, String> {
+    let mut active = ACTIVE
+        .lock()
+        .map_err(|_| "scheduler lock poisoned".to_string())?;
+    if let Some((id, other)) = active
+        .iter()
+        .find(|(id, other)| *id == job_id || conflicts(kind, other))
+    {
+        return Err(if *id == job_id {
+            format!("scheduled job {} is already running", job_id)
+        } else {
+            format!("conflicts with running {} job {}", other, id)
+        });
+    }
+    active.push((job_id, kind.to_string()));
+    Ok(ActiveGuard(job_id))
+}
+
+pub(crate) fn ensure_tables(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS scheduled_jobs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            cron TEXT NOT NULL,
+            params TEXT NOT NULL DEFAULT '{}',
+            enabled INTEGER NOT NULL DEFAULT 1,
+            catch_up INTEGER NOT NULL DEFAULT 1,
+            next_run_at INTEGER,
+            last_run_at INTEGER,
+            last_status TEXT,
+            created_by INTEGER,
+            created_by_username TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS scheduled_job_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_id INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            trigger TEXT NOT NULL,
+            status TEXT NOT NULL,
+            message TEXT,
+            scheduled_for INTEGER,
+            started_at INTEGER NOT NULL,
+            finished_at INTEGER
+        );
+        CREATE INDEX IF NOT EXISTS idx_scheduled_job_runs_job ON scheduled_job_runs(job_id, started_at);",
+    )
+    .map_err(|e| format!("ensure scheduled_jobs: {}", e))
+}
+
+fn seed_default_jobs(conn: &Connection) -> Result<(), String> {
+    crate::db::init_db_schema(conn).map_err(|e| format!("init schema: {}", e))?;
+    for (marker, name, kind, cron) in DEFAULT_JOBS {
+        if crate::db::get_setting(conn, marker)?.is_some() {
+            continue;
+        }
+        let now = Utc::now().timestamp();
+        conn.execute(
+            "INSERT INTO scheduled_jobs (name, kind, cron, params, next_run_at, created_by_username,
+                                         created_at, updated_at)
+             VALUES (?1, ?2, ?3, '{}', ?4, ?5, ?6, ?6)",
+            params![name, kind, cron, next_run(cron, now)?, SCHEDULER_ACTOR, now],
+        )
+        .map_err(|e| e.to_string())?;
+        crate::db::set_setting(conn, marker, &conn.last_insert_rowid().to_string())?;
+    }
+    Ok(())
+}
+
+/// First occurrence of `expr` strictly after `after` (unix seconds).
+pub fn next_run(expr: &str, after: i64) -> Result<i64, String> {
+    let cron = Cron::new(expr.trim())
+        .parse()
+        .map_err(|e| format!("invalid cron expression '{}': {}", expr, e))?;
+    let from = Local
+        .timestamp_opt(after, 0)
+        .single()
+        .ok_or_else(|| format!("invalid timestamp {}", after))?;
+    cron.find_next_occurrence(&from, false)
+        .map(|t| t.timestamp())
+        .map_err(|e| format!("no next run for '{}': {}", expr, e))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledJob {
+    pub id: i64,
+    pub name: String,
+    pub kind: String,
+    pub cron: String,
+    pub params: serde_json::Value,
+    pub enabled: bool,
+    pub catch_up: bool,
+    pub next_run_at: Option<i64>,
+    pub last_run_at: Option<i64>,
+    pub last_status: Option<String>,
+    pub created_by_username: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub running: bool,
+}
+
+const JOB_COLUMNS: &str = "id, name, kind, cron, params, enabled, catch_up, next_run_at,
+     last_run_at, last_status, created_by_username, created_at, updated_at";
+
+fn job_from_row(r: &rusqlite::Row) -> rusqlite::Result<ScheduledJob> {
+    let id: i64 = r.get(0)?;
+    let params: String = r.get(4)?;
+    Ok(ScheduledJob {
+        id,
+        name: r.get(1)?,
+        kind: r.get(2)?,
+        cron: r.get(3)?,
+        params: serde_json::from_str(&params).unwrap_or(serde_json::Value::Null),
+        enabled: r.get::<_, i64>(5)? != 0,
+        catch_up: r.get::<_, i64>(6)? != 0,
+        next_run_at: r.get(7)?,
+        last_run_at: r.get(8)?,
+        last_status: r.get(9)?,
+        created_by_username: r.get(10)?,
+        created_at: r.get(11)?,
+        updated_at: r.get(12)?,
+        running: ACTIVE
+            .lock()
+            .map(|a| a.iter().any(|(active, _)| *active == id))
+            .unwrap_or(false),
+    })
+}
+
+pub fn list_jobs(conn: &Connection) -> Result<Vec<ScheduledJob>, String> {
+    ensure_tables(conn)?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM scheduled_jobs ORDER BY name, id",
+            JOB_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], job_from_row)
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+fn get_job(conn: &Connection, id: i64) -> Result<ScheduledJob, String> {
+    ensure_tables(conn)?;
+    conn.query_row(
+        &format!("SELECT {} FROM scheduled_jobs WHERE id = ?1", JOB_COLUMNS),
+        params![id],
+        job_from_row,
+    )
+    .optional()
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("scheduled job {} not found", id))
+}
+
+/// Check `kind` and normalise its parameters.
+fn validate_params(kind: &str, params: serde_json::Value) -> Result<serde_json::Value, String> {
+    if !KINDS.contains(&kind) {
+        return Err(format!(
+            "unknown job kind '{}' (expected one of: {})",
+            kind,
+            KINDS.join(", ")
+        ));
+    }
+    let mut params = match params {
+        serde_json::Value::Null => serde_json::Map::new(),
+        serde_json::Value::Object(m) => m,
+        _ => return Err("params must be an object".into()),
+    };
+    match kind {
+        KIND_RESCAN => {
+            let root = params
+                .get("root")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|r| !r.is_empty())
+                .ok_or_else(|| "rescan jobs need a 'root' path".to_string())?;
+            // a root on an unplugged volume cannot be resolved now; keep it as
+            // given and let the run report it unavailable until it is back
+            let root = match std::fs::canonicalize(root) {
+                Ok(p) => p,
+                Err(_) if Path::new(root).is_absolute() => PathBuf::from(root),
+                Err(e) => return Err(format!("cannot canonicalize root: {}", e)),
+            };
+            params.insert(
+                "root".into(),
+                serde_json::Value::String(root.to_string_lossy().to_string()),
+            );
+        }
+        KIND_HASH_VERIFY => {
+            if let Some(v) = params.get("max_files") {
+                if !v.is_null() && !matches!(v.as_i64(), Some(n) if n > 0) {
+                    return Err("max_files must be a positive integer".into());
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(serde_json::Value::Object(params))
+}
+
+/// Run one job to completion; returns a one-line summary for the history.
+fn execute(conn: &Connection, job: &ScheduledJob) -> Result<String, String> {
+    match job.kind.as_str() {
+        KIND_RESCAN => {
+            let root = job
+                .params
+                .get("root")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "missing root".to_string())?;
+            if !Path::new(root).is_dir() {
+                return Err(format!("root {} is not available", root));
+            }
+            crate::fs_ops::ensure_files_index_tables(conn)?;
+            let index_job = crate::index_jobs::create(
+                conn,
+                "scheduled",
+                &[PathBuf::from(root)],
+                None,
+                SCHEDULER_ACTOR,
+            )?;
+            match crate::index_jobs::run_blocking(&index_job)? {
+                Some(crate::fs_ops::IndexJobState::Finished { stats, .. }) => Ok(format!(
+                    "index job {}: {} visited, {} added, {} updated, {} removed",
+                    index_job, stats.visited, stats.added, stats.updated, stats.removed
+                )),
+                Some(crate::fs_ops::IndexJobState::Failed { message }) => Err(message),
+                Some(crate::fs_ops::IndexJobState::Cancelled { processed, .. }) => Err(format!(
+                    "index job {} cancelled after {} entries",
+                    index_job, processed
+                )),
+                other => Err(format!("index job {} ended as {:?}", index_job, other)),
+            }
+        }
+        KIND_AUDIT_RETENTION => {
+            crate::db::init_db_schema(conn).map_err(|e| format!("init schema: {}", e))?;
+            let out = crate::audit_retention::run_retention(conn, SCHEDULER_ACTOR, false)?;
+            let rows: u64 = out.iter().map(|o| o.rows).sum();
+            Ok(format!(
+                "archived {} rows across {} policies",
+                rows,
+                out.len()
+            ))
+        }
+        KIND_SESSION_CLEANUP => {
+            let n = crate::session::purge_expired_sessions(conn)?;
+            Ok(format!("removed {} expired sessions", n))
+        }
+        KIND_HASH_VERIFY => {
+            let max_files = job.params.get("max_files").and_then(|v| v.as_i64());
+            let r = crate::content_hash::verify_hashes(conn, max_files)?;
+            let summary = format!(
+                "{} verified, {} re-hashed, {} failed, {} mismatched",
+                r.verified,
+                r.rehashed,
+                r.failed,
+                r.mismatched.len()
+            );
+            if r.mismatched.is_empty() {
+                Ok(summary)
+            } else {
+                Err(summary)
+            }
+        }
+        KIND_DB_OPTIMIZE => {
+            conn.execute_batch("PRAGMA optimize; PRAGMA wal_checkpoint(TRUNCATE);")
+                .map_err(|e| e.to_string())?;
+            let has_fts: bool = conn
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'files_index_fts')",
+                    [],
+                    |r| r.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            if has_fts {
+                conn.execute_batch(
+                    "INSERT INTO files_index_fts(files_index_fts) VALUES('optimize');",
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            Ok(if has_fts {
+                "optimized statistics, checkpointed WAL and merged the search index".into()
+            } else {
+                "optimized statistics and checkpointed WAL".into()
+            })
+        }
+        KIND_STORAGE_SAMPLE => {
+            let r = crate::storage_history::record_samples(conn)?;
+            Ok(if r.crossed.is_empty() {
+                format!("sampled {} drives", r.recorded)
+            } else {
+                format!(
+                    "sampled {} drives; over threshold: {}",
+                    r.recorded,
+                    r.crossed.join(", ")
+                )
+            })
+        }
+        other => Err(format!("unknown job kind '{}'", other)),
+    }
+}
+
+/// Start a run of `job` on a background thread. The slot is claimed before
+/// returning, so a conflicting job is refused here rather than later.
+fn start_run(
+    conn: &Connection,
+    job: ScheduledJob,
+    trigger: &str,
+    scheduled_for: Option<i64>,
+) -> Result<i64, String> {
+    let guard = try_claim(job.id, &job.kind)?;
+    if let Some(busy) = busy_elsewhere(conn, &job)? {
+        return Err(format!("conflicts with running work: {}", busy));
+    }
+    let now = Utc::now().timestamp();
+    conn.execute(
+        "INSERT INTO scheduled_job_runs (job_id, kind, trigger, status, scheduled_for, started_at)
+         VALUES (?1, ?2, ?3, 'running', ?4, ?5)",
+        params![job.id, job.kind, trigger, scheduled_for, now],
+    )
+    .map_err(|e| e.to_string())?;
+    let run_id = conn.last_insert_rowid();
+    conn.execute(
+        "UPDATE scheduled_jobs SET last_run_at = ?2, last_status = 'running' WHERE id = ?1",
+        params![job.id, now],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let trigger = trigger.to_string();
+    std::thread::Builder::new()
+        .name(format!("scheduled-{}", job.kind))
+        .spawn(move || {
+            let _guard = guard;
+            let conn = match crate::db::open_connection() {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("[scheduler] job {} db open failed: {}", job.id, e);
+                    return;
+                }
+            };
+            let (status, message) = match execute(&conn, &job) {
+                Ok(m) => ("succeeded", m),
+                Err(e) => ("failed", e),
+            };
+            let _ = conn.execute(
+                "UPDATE scheduled_job_runs SET status = ?2, message = ?3, finished_at = ?4
+                 WHERE id = ?1",
+                params![run_id, status, message, Utc::now().timestamp()],
+            );
+            let _ = conn.execute(
+                "UPDATE scheduled_jobs SET last_status = ?2 WHERE id = ?1",
+                params![job.id, status],
+            );
+            let _ = crate::db::insert_audit_log(
+                &conn,
+                None,
+                Some(SCHEDULER_ACTOR),
+                "scheduled_job_run",
+                None,
+                Some(&format!(
+                    "{} '{}' ({}) {}: {}",
+                    job.kind, job.name, trigger, status, message
+                )),
+            );
+        })
+        .map_err(|e| format!("spawn scheduled job: {}", e))?;
+    Ok(run_id)
+}
+
+/// Without a next run the job would silently never run again; take it out of
+/// the schedule where an admin sees it (disabled, failed run in its history).
+fn disable_unschedulable(
+    conn: &Connection,
+    job: &ScheduledJob,
+    scheduled_for: Option<i64>,
+    error: &str,
+) -> Result<(), String> {
+    let now = Utc::now().timestamp();
+    let message = format!("{}; job disabled", error);
+    eprintln!("[scheduler] job {}: {}", job.id, message);
+    conn.execute(
+        "INSERT INTO scheduled_job_runs (job_id, kind, trigger, status, message, scheduled_for, started_at, finished_at)
+         VALUES (?1, ?2, 'schedule', 'failed', ?3, ?4, ?5, ?5)",
+        params![job.id, job.kind, message, scheduled_for, now],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE scheduled_jobs SET enabled = 0, next_run_at = NULL, last_status = 'failed',
+                updated_at = ?2
+         WHERE id = ?1",
+        params![job.id, now],
+    )
+    .map_err(|e| e.to_string())?;
+    let _ = crate::db::insert_audit_log(
+        conn,
+        None,
+        Some(SCHEDULER_ACTOR),
+        "scheduled_job_disabled",
+        None,
+        Some(&format!("{} '{}': {}", job.kind, job.name, message)),
+    );
+    Ok(())
+}
+
+/// Start every due job that does not conflict with one already running.
+fn tick(conn: &Connection) -> Result<(), String> {
+    let now = Utc::now().timestamp();
+    let due: Vec<ScheduledJob> = list_jobs(conn)?
+        .into_iter()
+        .filter(|j| j.enabled && j.next_run_at.is_some_and(|t| t <= now))
+        .collect();
+    for job in due {
+        let scheduled_for = job.next_run_at;
+        let missed = scheduled_for.is_some_and(|t| t < *PROCESS_START);
+        // the next slot counts from now, so a backlog of misses runs once
+        let next = match next_run(&job.cron, now) {
+            Ok(t) => t,
+            Err(e) => {
+                disable_unschedulable(conn, &job, scheduled_for, &e)?;
+                continue;
+            }
+        };
+        if missed && !job.catch_up {
+            conn.execute(
+                "INSERT INTO scheduled_job_runs (job_id, kind, trigger, status, message, scheduled_for, started_at, finished_at)
+                 VALUES (?1, ?2, 'catch_up', 'skipped', 'missed while the app was not running', ?3, ?4, ?4)",
+                params![job.id, job.kind, scheduled_for, now],
+            )
+            .map_err(|e| e.to_string())?;
+            conn.execute(
+                "UPDATE scheduled_jobs SET next_run_at = ?2, last_status = 'skipped' WHERE id = ?1",
+                params![job.id, next],
+            )
+            .map_err(|e| e.to_string())?;
+            continue;
+        }
+        let id = job.id;
+        let trigger = if missed { "catch_up" } else { "schedule" };
+        // on a conflict the job stays due and is retried on the next tick
+        if start_run(conn, job, trigger, scheduled_for).is_ok() {
+            conn.execute(
+                "UPDATE scheduled_jobs SET next_run_at = ?2 WHERE id = ?1",
+                params![id, next],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Start the ticker thread (once per process). Runs left `running` by a
+/// previous process are closed as `interrupted` first.
+pub fn start_scheduler() {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    Lazy::force(&PROCESS_START);
+    let spawned = std::thread::Builder::new()
+        .name("scheduler".into())
+        .spawn(|| {
+            let mut conn: Option<Connection> = None;
+            loop {
+                if conn.is_none() {
+                    match crate::db::open_connection().and_then(|c| {
+                        ensure_tables(&c)?;
+                        seed_default_jobs(&c)?;
+                        c.execute(
+                            "UPDATE scheduled_job_runs SET status = 'interrupted', finished_at = ?1
+                             WHERE status = 'running'",
+                            params![Utc::now().timestamp()],
+                        )
+                        .map_err(|e| e.to_string())?;
+                        Ok(c)
+                    }) {
+                        Ok(c) => conn = Some(c),
+                        Err(e) => eprintln!("[scheduler] db open failed: {}", e),
+                    }
+                }
+                if let Some(ref c) = conn {
+                    if let Err(e) = tick(c) {
+                        eprintln!("[scheduler] tick failed: {}", e);
+                    }
+                }
+                std::thread::sleep(TICK);
+            }
+        });
+    if spawned.is_err() {
+        STARTED.store(false, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduledJobInput {
+    pub name: String,
+    pub kind: String,
+    pub cron: String,
+    #[serde(default)]
+    pub params: serde_json::Value,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_true")]
+    pub catch_up: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn validate_input(input: ScheduledJobInput) -> Result<(ScheduledJobInput, i64), String> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err("name is required".into());
+    }
+    let cron = input.cron.trim().to_string();
+    let next = next_run(&cron, Utc::now().timestamp())?;
+    let params = validate_params(&input.kind, input.params)?;
+    Ok((
+        ScheduledJobInput {
+            name,
+            cron,
+            params,
+            ..input
+        },
+        next,
+    ))
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScheduledJobRun {
+    pub id: i64,
+    pub job_id: i64,
+    pub job_name: Option<String>,
+    pub kind: String,
+    pub trigger: String,
+    pub status: String,
+    pub message: Option<String>,
+    pub scheduled_for: Option<i64>,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+}
+
+#[command]
+pub fn admin_list_scheduled_jobs(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<Vec<ScheduledJob>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    list_jobs(&conn)
+}
+
+#[command]
+pub fn admin_create_scheduled_job(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    job: ScheduledJobInput,
+) -> Result<ScheduledJob, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    ensure_tables(&conn)?;
+
+    let (job, next) = validate_input(job)?;
+    let now = Utc::now().timestamp();
+    conn.execute(
+        "INSERT INTO scheduled_jobs (name, kind, cron, params, enabled, catch_up, next_run_at,
+                                     created_by, created_by_username, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)",
+        params![
+            job.name,
+            job.kind,
+            job.cron,
+            job.params.to_string(),
+            job.enabled as i64,
+            job.catch_up as i64,
+            next,
+            admin_id,
+            admin_username,
+            now
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    let id = conn.last_insert_rowid();
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_create_scheduled_job",
+        None,
+        Some(&format!(
+            "id={} {} '{}' cron='{}'",
+            id, job.kind, job.name, job.cron
+        )),
+    );
+    get_job(&conn, id)
+}
+
+#[command]
+pub fn admin_update_scheduled_job(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    id: i64,
+    job: ScheduledJobInput,
+) -> Result<ScheduledJob, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    get_job(&conn, id)?;
+
+    let (job, next) = validate_input(job)?;
+    conn.execute(
+        "UPDATE scheduled_jobs SET name = ?2, kind = ?3, cron = ?4, params = ?5, enabled = ?6,
+                catch_up = ?7, next_run_at = ?8, updated_at = ?9
+         WHERE id = ?1",
+        params![
+            id,
+            job.name,
+            job.kind,
+            job.cron,
+            job.params.to_string(),
+            job.enabled as i64,
+            job.catch_up as i64,
+            next,
+            Utc::now().timestamp()
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_update_scheduled_job",
+        None,
+        Some(&format!(
+            "id={} {} '{}' cron='{}' enabled={}",
+            id, job.kind, job.name, job.cron, job.enabled
+        )),
+    );
+    get_job(&conn, id)
+}
+
+#[command]
+pub fn admin_delete_scheduled_job(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    id: i64,
+) -> Result<Vec<ScheduledJob>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    let job = get_job(&conn, id)?;
+    if job.running {
+        return Err(format!("scheduled job {} is running", id));
+    }
+
+    // run history is kept; it still names the job kind
+    conn.execute("DELETE FROM scheduled_jobs WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_delete_scheduled_job",
+        None,
+        Some(&format!("id={} {} '{}'", id, job.kind, job.name)),
+    );
+    list_jobs(&conn)
+}
+
+/// Admin: run a job now, outside its schedule. Refused while a conflicting
+/// job is running. Returns the run id.
+#[command]
+pub fn admin_run_scheduled_job(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    id: i64,
+) -> Result<i64, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    let job = get_job(&conn, id)?;
+    let name = job.name.clone();
+
+    let run_id = start_run(&conn, job, "manual", None)?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_run_scheduled_job",
+        None,
+        Some(&format!("id={} '{}' run_id={}", id, name, run_id)),
+    );
+    Ok(run_id)
+}
+
+#[command]
+pub fn admin_list_scheduled_job_runs(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    job_id: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Vec<ScheduledJobRun>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    ensure_tables(&conn)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT r.id, r.job_id, j.name, r.kind, r.trigger, r.status, r.message,
+                    r.scheduled_for, r.started_at, r.finished_at
+             FROM scheduled_job_runs r
+             LEFT JOIN scheduled_jobs j ON j.id = r.job_id
+             WHERE ?1 IS NULL OR r.job_id = ?1
+             ORDER BY r.started_at DESC, r.id DESC
+             LIMIT ?2",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![job_id, limit.unwrap_or(100).clamp(1, 1000)], |r| {
+            Ok(ScheduledJobRun {
+                id: r.get(0)?,
+                job_id: r.get(1)?,
+                job_name: r.get(2)?,
+                kind: r.get(3)?,
+                trigger: r.get(4)?,
+                status: r.get(5)?,
+                message: r.get(6)?,
+                scheduled_for: r.get(7)?,
+                started_at: r.get(8)?,
+                finished_at: r.get(9)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> i64 {
+        Local
+            .with_ymd_and_hms(y, mo, d, h, mi, 0)
+            .single()
+            .unwrap()
+            .timestamp()
+    }
+
+    #[test]
+    fn next_run_is_strictly_after() {
+        let at = local(2026, 1, 15, 10, 30);
+        assert_eq!(next_run("@hourly", at).unwrap(), local(2026, 1, 15, 11, 0));
+        let on_the_hour = local(2026, 1, 15, 11, 0);
+        assert_eq!(next_run("@hourly", on_the_hour).unwrap(), local(2026, 1, 15, 12, 0));
+        assert_eq!(next_run("0 3 * * *", at).unwrap(), local(2026, 1, 16, 3, 0));
+        assert_eq!(next_run(" */15 * * * * ", at).unwrap(), local(2026, 1, 15, 10, 45));
+    }
+
+    #[test]
+    fn next_run_rolls_over_weeks_and_months() {
+        // 2026-01-15 is a Thursday
+        let at = local(2026, 1, 15, 10, 30);
+        assert_eq!(next_run("0 9 * * MON", at).unwrap(), local(2026, 1, 19, 9, 0));
+        assert_eq!(next_run("@monthly", at).unwrap(), local(2026, 2, 1, 0, 0));
+    }
+
+    #[test]
+    fn next_run_rejects_bad_expressions() {
+        let at = local(2026, 1, 15, 10, 30);
+        for bad in ["", "every day", "61 * * * *", "* * * *"] {
+            assert!(next_run(bad, at).is_err(), "{:?}", bad);
+        }
+    }
+}
@@ -1,7 +1,10 @@
 // src-tauri/src/security.rs
 use argon2::password_hash::SaltString;
 use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use rand_core::OsRng;
+use sha2::{Digest, Sha256};
+use std::io::Write;
 
 pub fn hash_password(password: &str) -> Result<String, String> {
     let salt = SaltString::generate(&mut OsRng);
@@ -18,3 +21,91 @@ pub fn verify_password(hash: &str, password: &str) -> Result<bool, String> {
         .verify_password(password.as_bytes(), &parsed)
         .is_ok())
 }
+
+// -----------------------------------------------------------------------------
+// Installation signing key (Ed25519)
+// -----------------------------------------------------------------------------
+
+const INSTALLATION_KEY_FILE: &str = "installation.key";
+
+/// Load the per-installation signing key from the data dir, generating it on first use.
+/// The file holds the hex-encoded 32-byte secret seed.
+pub fn installation_signing_key() -> Result<SigningKey, String> {
+    let mut p = crate::db::data_dir()?;
+    p.push(INSTALLATION_KEY_FILE);
+
+    if p.exists() {
+        let raw = std::fs::read_to_string(&p)
+            .map_err(|e| format!("read installation key {:?}: {}", p, e))?;
+        let bytes = hex::decode(raw.trim()).map_err(|e| format!("installation key: {}", e))?;
+        let seed: [u8; 32] = bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| "installation key has wrong length".to_string())?;
+        return Ok(SigningKey::from_bytes(&seed));
+    }
+
+    let key = SigningKey::generate(&mut OsRng);
+    // created owner-only, so the secret is never readable by others, not
+    // even between the write and a later chmod
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = match options.open(&p) {
+        Ok(f) => f,
+        // another thread created it first
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+            return installation_signing_key()
+        }
+        Err(e) => return Err(format!("create installation key {:?}: {}", p, e)),
+    };
+    file.write_all(hex::encode(key.to_bytes()).as_bytes())
+        .map_err(|e| format!("write installation key {:?}: {}", p, e))?;
+    Ok(key)
+}
+
+/// Hex-encoded public half of the installation key.
+pub fn installation_public_key_hex() -> Result<String, String> {
+    Ok(hex::encode(
+        installation_signing_key()?.verifying_key().to_bytes(),
+    ))
+}
+
+/// Sign `data` with the installation key. Returns (signature_hex, public_key_hex).
+pub fn sign_detached(data: &[u8]) -> Result<(String, String), String> {
+    let key = installation_signing_key()?;
+    let sig = key.sign(data);
+    Ok((
+        hex::encode(sig.to_bytes()),
+        hex::encode(key.verifying_key().to_bytes()),
+    ))
+}
+
+/// Verify a detached Ed25519 signature produced by `sign_detached`.
+/// Ok(false) means the signature does not match; Err is for malformed input.
+pub fn verify_detached(public_key_hex: &str, signature_hex: &str, data: &[u8]) -> Result<bool, String> {
+    let pk_bytes = hex::decode(public_key_hex.trim()).map_err(|e| format!("public key: {}", e))?;
+    let pk_arr: [u8; 32] = pk_bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| "public key has wrong length".to_string())?;
+    let vk = VerifyingKey::from_bytes(&pk_arr).map_err(|e| format!("public key: {}", e))?;
+
+    let sig_bytes = hex::decode(signature_hex.trim()).map_err(|e| format!("signature: {}", e))?;
+    let sig_arr: [u8; 64] = sig_bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| "signature has wrong length".to_string())?;
+    let sig = Signature::from_bytes(&sig_arr);
+
+    Ok(vk.verify(data, &sig).is_ok())
+}
+
+/// Hex SHA-256 of a byte slice.
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
@@ -1,5 +1,5 @@
 // src-tauri/src/session.rs
-use crate::db::{create_session_token, get_session, revoke_session};
+use crate::db::{create_session_token, delete_expired_sessions, get_session, revoke_session};
 use chrono::Utc;
 use rusqlite::Connection;
 
@@ -32,3 +32,8 @@ pub fn validate_session(conn: &Connection, token: &str) -> Result<Option<i64>, S
 pub fn revoke_session_token(conn: &Connection, token: &str) -> Result<(), String> {
     revoke_session(conn, token)
 }
+
+/// Delete sessions that have expired; returns how many were removed.
+pub fn purge_expired_sessions(conn: &Connection) -> Result<usize, String> {
+    delete_expired_sessions(conn, Utc::now().timestamp())
+}
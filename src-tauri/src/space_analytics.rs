@@ -0,0 +1,374 @@
+// src-tauri/src/space_analytics.rs
+//
+// Space usage reports computed from files_index (sizes as of the last walk)
+// instead of walking disks: largest files, old large files, bytes per drive
+// and category, and folder sizes rolled up from the files below them.
+//
+// Folder totals are summed in SQL: each file's size is added to every
+// indexed folder above it, following the `parent` column (for a listing of
+// one folder, to the child it sits under). Archive members
+// (archive_index.rs) are left out: the archive's own size covers them.
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, MAIN_SEPARATOR};
+use tauri::{command, AppHandle};
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 5_000;
+/// Defaults for "old large files": 100 MiB, untouched for a year.
+const DEFAULT_OLD_MIN_BYTES: i64 = 100 << 20;
+const DEFAULT_OLD_DAYS: i64 = 365;
+
+fn clamp_limit(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+}
+
+#[derive(Debug, Serialize)]
+pub struct SizedFile {
+    pub path: String,
+    pub name: String,
+    pub size: i64,
+    pub doc_type: Option<String>,
+    pub drive: Option<String>,
+    pub modified: Option<i64>,
+}
+
+const FILE_COLUMNS: &str = "path, name, COALESCE(size, 0), doc_type, drive, mtime";
+
+fn sized_file(r: &rusqlite::Row) -> rusqlite::Result<SizedFile> {
+    Ok(SizedFile {
+        path: r.get(0)?,
+        name: r.get(1)?,
+        size: r.get(2)?,
+        doc_type: r.get(3)?,
+        drive: r.get(4)?,
+        modified: r.get(5)?,
+    })
+}
+
+/// Files by size, largest first. `drive` and `under` narrow the scope;
+/// `min_bytes` and `modified_before` select old large files.
+fn largest_files(
+    conn: &Connection,
+    drive: Option<&str>,
+    under: Option<&str>,
+    min_bytes: i64,
+    modified_before: Option<i64>,
+    limit: i64,
+) -> Result<Vec<SizedFile>, String> {
+    let (lo, hi) = match under {
+        Some(p) => {
+            let (lo, hi) = crate::indexer::descendant_range(p);
+            (Some(lo), Some(hi))
+        }
+        None => (None, None),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM files_index
+             WHERE file_type NOT IN ('dir', 'member') AND size >= ?1
+               AND (?2 IS NULL OR drive = ?2)
+               AND (?3 IS NULL OR (path >= ?3 AND path < ?4))
+               AND (?5 IS NULL OR COALESCE(mtime, indexed_at) < ?5)
+             ORDER BY size DESC LIMIT ?6",
+            FILE_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(
+            params![min_bytes, drive, lo, hi, modified_before, limit],
+            sized_file,
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpaceBucket {
+    pub drive: String,
+    pub category: String,
+    pub files: i64,
+    pub bytes: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FolderSize {
+    pub path: String,
+    pub name: String,
+    pub is_dir: bool,
+    /// Bytes of all files at or below this entry.
+    pub bytes: i64,
+    pub files: i64,
+    /// Bytes of files directly inside the folder (0 for files).
+    pub own_bytes: i64,
+}
+
+fn name_of(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Size of each entry directly inside `dir`, from the files below it.
+fn child_sizes(conn: &Connection, dir: &str) -> Result<Vec<FolderSize>, String> {
+    let (lo, hi) = crate::indexer::descendant_range(dir);
+    let mut stmt = conn
+        .prepare(
+            "SELECT path, COALESCE(size, 0), file_type = 'dir' FROM files_index
+             WHERE path >= ?1 AND path < ?2 AND file_type IS NOT 'member'",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![lo, hi], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, i64>(1)?,
+                r.get::<_, bool>(2)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut children: HashMap<String, FolderSize> = HashMap::new();
+    for row in rows {
+        let (path, size, is_dir) = row.map_err(|e| e.to_string())?;
+        let rest = &path[lo.len()..];
+        let (first, nested) = match rest.split_once(MAIN_SEPARATOR) {
+            Some((first, _)) => (first, true),
+            None => (rest, false),
+        };
+        let child = children
+            .entry(first.to_string())
+            .or_insert_with(|| FolderSize {
+                path: format!("{}{}", lo, first),
+                name: first.to_string(),
+                is_dir: nested,
+                bytes: 0,
+                files: 0,
+                own_bytes: 0,
+            });
+        child.is_dir |= nested || is_dir;
+        if is_dir {
+            continue;
+        }
+        child.bytes += size;
+        child.files += 1;
+        if nested && !rest[first.len() + 1..].contains(MAIN_SEPARATOR) {
+            child.own_bytes += size;
+        }
+    }
+    Ok(children.into_values().collect())
+}
+
+/// Recursive size of every indexed folder (optionally only those on `drive`).
+/// Each file row is carried up its chain of indexed parent folders and
+/// summed per folder; depth 0 is the folder the file sits in.
+fn folder_totals(conn: &Connection, drive: Option<&str>) -> Result<Vec<FolderSize>, String> {
+    let mut stmt = conn
+        .prepare(
+            "WITH RECURSIVE up(dir, size, depth) AS (
+                 SELECT parent, COALESCE(size, 0), 0 FROM files_index
+                 WHERE file_type NOT IN ('dir', 'member') AND parent IS NOT NULL
+                   AND (?1 IS NULL OR drive = ?1)
+                 UNION ALL
+                 SELECT d.parent, up.size, up.depth + 1
+                 FROM up JOIN files_index d ON d.path = up.dir
+                 WHERE d.file_type = 'dir' AND d.parent IS NOT NULL AND d.parent != d.path
+             )
+             SELECT up.dir, SUM(up.size), COUNT(*),
+                    SUM(CASE WHEN up.depth = 0 THEN up.size ELSE 0 END)
+             FROM up JOIN files_index d ON d.path = up.dir
+             WHERE d.file_type = 'dir' AND (?1 IS NULL OR d.drive = ?1)
+             GROUP BY up.dir",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![drive], |r| {
+            let path: String = r.get(0)?;
+            Ok(FolderSize {
+                name: name_of(&path),
+                path,
+                is_dir: true,
+                bytes: r.get(1)?,
+                files: r.get(2)?,
+                own_bytes: r.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<rusqlite::Result<_>>()
+        .map_err(|e| e.to_string())
+}
+
+// -----------------------------------------------------------------------------
+// Commands
+// -----------------------------------------------------------------------------
+
+fn open_admin(
+    session_token: Option<String>,
+    sessionToken: Option<String>,
+) -> Result<Connection, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    crate::fs_ops::ensure_files_index_tables(&conn)?;
+    Ok(conn)
+}
+
+/// Admin: the largest indexed files, optionally on one drive or below a folder.
+#[command]
+pub fn admin_largest_files(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    drive: Option<String>,
+    under: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<SizedFile>, String> {
+    let conn = open_admin(session_token, sessionToken)?;
+    largest_files(
+        &conn,
+        drive.as_deref(),
+        under.as_deref(),
+        0,
+        None,
+        clamp_limit(limit),
+    )
+}
+
+/// Admin: files of at least `min_bytes` (default 100 MiB) not modified for
+/// `older_than_days` (default 365), largest first.
+#[command]
+pub fn admin_old_large_files(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    min_bytes: Option<i64>,
+    older_than_days: Option<i64>,
+    drive: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<SizedFile>, String> {
+    let conn = open_admin(session_token, sessionToken)?;
+    let days = older_than_days.unwrap_or(DEFAULT_OLD_DAYS).max(0);
+    let cutoff = Utc::now()
+        .timestamp()
+        .saturating_sub(days.saturating_mul(86_400));
+    largest_files(
+        &conn,
+        drive.as_deref(),
+        None,
+        min_bytes.unwrap_or(DEFAULT_OLD_MIN_BYTES).max(0),
+        Some(cutoff),
+        clamp_limit(limit),
+    )
+}
+
+/// Admin: file count and bytes per drive and category, largest first.
+#[command]
+pub fn admin_space_breakdown(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    drive: Option<String>,
+) -> Result<Vec<SpaceBucket>, String> {
+    let conn = open_admin(session_token, sessionToken)?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT COALESCE(drive, 'unknown'), {} AS category, COUNT(*), COALESCE(SUM(size), 0)
+             FROM files_index
+             WHERE file_type NOT IN ('dir', 'member') AND (?1 IS NULL OR drive = ?1)
+             GROUP BY 1, 2
+             ORDER BY 4 DESC",
+            crate::db::CATEGORY_SQL
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![drive], |r| {
+            Ok(SpaceBucket {
+                drive: r.get(0)?,
+                category: r.get(1)?,
+                files: r.get(2)?,
+                bytes: r.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Admin: folder sizes, largest first. With `path`, the entries directly
+/// inside that folder (like `du`); otherwise the largest indexed folders,
+/// optionally on one drive.
+#[command]
+pub fn admin_folder_sizes(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    path: Option<String>,
+    drive: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<FolderSize>, String> {
+    let conn = open_admin(session_token, sessionToken)?;
+    let mut out = match path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(p) => child_sizes(&conn, p)?,
+        None => folder_totals(&conn, drive.as_deref())?,
+    };
+    out.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    out.truncate(clamp_limit(limit) as usize);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(conn: &Connection, path: &str, file_type: &str, size: i64, drive: &str) {
+        conn.execute(
+            "INSERT INTO files_index (path, name, file_type, size, indexed_at, drive, parent)
+             VALUES (?1, ?2, ?3, ?4, 0, ?5, ?6)",
+            params![
+                path,
+                name_of(path),
+                file_type,
+                size,
+                drive,
+                crate::indexer::parent_of(path)
+            ],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn folder_totals_roll_up_through_indexed_parents() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::fs_ops::ensure_files_index_tables(&conn).unwrap();
+        let sep = MAIN_SEPARATOR;
+        let p = |rel: &str| format!("{sep}data{sep}{}", rel.replace('/', &sep.to_string()));
+        add(&conn, &p("a"), "dir", 0, "d1");
+        add(&conn, &p("a/deep"), "dir", 0, "d1");
+        add(&conn, &p("b"), "dir", 0, "d2");
+        add(&conn, &p("a/x.pdf"), "file", 3_000, "d1");
+        add(&conn, &p("a/deep/y.mp4"), "file", 9_000, "d1");
+        add(&conn, &p("b/z.txt"), "file", 500, "d2");
+        add(&conn, &p("top.log"), "file", 100, "d1");
+
+        let mut all = folder_totals(&conn, None).unwrap();
+        all.sort_by(|a, b| a.path.cmp(&b.path));
+        let got: Vec<(String, i64, i64, i64)> = all
+            .into_iter()
+            .map(|f| (f.path, f.bytes, f.files, f.own_bytes))
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                (p("a"), 12_000, 2, 3_000),
+                (p("a/deep"), 9_000, 1, 9_000),
+                (p("b"), 500, 1, 500),
+            ]
+        );
+
+        let d2 = folder_totals(&conn, Some("d2")).unwrap();
+        assert_eq!(d2.len(), 1);
+        assert_eq!((d2[0].path.as_str(), d2[0].bytes), (p("b").as_str(), 500));
+    }
+}
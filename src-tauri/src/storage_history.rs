@@ -0,0 +1,424 @@
+// src-tauri/src/storage_history.rs
+//
+// Disk usage history. Samples of total/used/free bytes per drive go into
+// `storage_scans`; a drive is recorded under its volume key (volumes.rs) when
+// it has one, so a USB disk keeps one history across mount points. Samples
+// are taken by the `storage_sample` scheduled job (hourly by default) or on
+// request by an admin; reading storage info for the dashboard records nothing.
+//
+// Trends fit a least-squares line through the used bytes of a window to get
+// a growth rate and a projected number of days until the drive is full. A
+// sample that takes a drive past its configured fullness (the `disk_full`
+// alert rule) is written to the audit log, where the rule raises an alert.
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use sysinfo::{DiskExt, System, SystemExt};
+use tauri::{command, AppHandle};
+
+pub const THRESHOLD_ACTION: &str = "storage_threshold_crossed";
+const STORAGE_ACTOR: &str = "storage";
+
+const HISTORY_DAYS_KEY: &str = "storage.history_days";
+const DEFAULT_HISTORY_DAYS: i64 = 365;
+const DEFAULT_TREND_DAYS: i64 = 30;
+
+pub(crate) fn ensure_tables(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS storage_scans (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            drive TEXT NOT NULL,
+            total_bytes INTEGER,
+            used_bytes INTEGER,
+            available_bytes INTEGER,
+            scanned_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_storage_scans_drive_scanned_at ON storage_scans(drive, scanned_at);",
+    )
+    .map_err(|e| format!("ensure storage_scans: {}", e))?;
+    crate::db::ensure_column(conn, "storage_scans", "mount_point", "TEXT")?;
+    crate::db::ensure_column(conn, "storage_scans", "label", "TEXT")?;
+    Ok(())
+}
+
+struct DiskReading {
+    drive: String,
+    mount_point: String,
+    label: Option<String>,
+    total: u64,
+    available: u64,
+}
+
+fn used_pct(used: i64, total: i64) -> f64 {
+    if total <= 0 {
+        0.0
+    } else {
+        used as f64 * 100.0 / total as f64
+    }
+}
+
+/// Current usage of every real disk, one reading per drive.
+fn read_disks(conn: &Connection) -> Vec<DiskReading> {
+    if let Err(e) = crate::volumes::refresh(conn) {
+        eprintln!("[storage] volume refresh failed: {}", e);
+    }
+    let names = crate::locations::names_by_volume(conn).unwrap_or_default();
+
+    let mut sys = System::new();
+    sys.refresh_disks_list();
+    sys.refresh_disks();
+
+    let mut out: Vec<DiskReading> = Vec::new();
+    for disk in sys.disks() {
+        let total = disk.total_space();
+        if total == 0 {
+            continue;
+        }
+        let mount = disk.mount_point().to_string_lossy().to_string();
+        let (drive, label) = match crate::volumes::volume_at(&mount) {
+            Some((key, label)) => {
+                let label = names.get(&key).cloned().or(label);
+                (key, label)
+            }
+            None => (mount.clone(), None),
+        };
+        // bind mounts show the same volume more than once
+        if out.iter().any(|r| r.drive == drive) {
+            continue;
+        }
+        out.push(DiskReading {
+            drive,
+            mount_point: mount,
+            label,
+            total,
+            available: disk.available_space(),
+        });
+    }
+    out
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SampleReport {
+    pub recorded: usize,
+    /// Drives that went past their alert threshold with this sample.
+    pub crossed: Vec<String>,
+}
+
+/// Record a sample for every drive, report threshold crossings and drop
+/// samples past the retention period.
+pub(crate) fn record_samples(conn: &Connection) -> Result<SampleReport, String> {
+    crate::db::init_db_schema(conn).map_err(|e| format!("init schema: {}", e))?;
+    ensure_tables(conn)?;
+    let now = Utc::now().timestamp();
+    let mut report = SampleReport::default();
+
+    for d in read_disks(conn) {
+        let last: Option<(i64, i64)> = conn
+            .query_row(
+                "SELECT used_bytes, total_bytes FROM storage_scans
+                 WHERE drive = ?1 ORDER BY scanned_at DESC LIMIT 1",
+                params![d.drive],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .ok();
+
+        let total = d.total as i64;
+        let available = d.available as i64;
+        let used = total.saturating_sub(available);
+        conn.execute(
+            "INSERT INTO storage_scans (drive, mount_point, label, total_bytes, used_bytes,
+                                        available_bytes, scanned_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![d.drive, d.mount_point, d.label, total, used, available, now],
+        )
+        .map_err(|e| e.to_string())?;
+        report.recorded += 1;
+
+        let threshold = match crate::alerts::disk_full_threshold(conn, &d.drive)? {
+            Some(t) => t,
+            None => continue,
+        };
+        let pct = used_pct(used, total);
+        let was_below = match last {
+            Some((u, t)) => used_pct(u, t) < threshold,
+            None => true,
+        };
+        if pct >= threshold && was_below {
+            let _ = crate::db::insert_audit_log(
+                conn,
+                None,
+                Some(STORAGE_ACTOR),
+                THRESHOLD_ACTION,
+                None,
+                Some(&format!(
+                    "drive {} ({}) is {:.1}% full, threshold {}%, {} bytes free",
+                    d.label.as_deref().unwrap_or(&d.drive),
+                    d.mount_point,
+                    pct,
+                    threshold,
+                    available
+                )),
+            );
+            report.crossed.push(d.drive);
+        }
+    }
+
+    let days = crate::db::get_setting(conn, HISTORY_DAYS_KEY)?
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|d| *d > 0)
+        .unwrap_or(DEFAULT_HISTORY_DAYS);
+    conn.execute(
+        "DELETE FROM storage_scans WHERE scanned_at < ?1",
+        params![now.saturating_sub(days.saturating_mul(86_400))],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(report)
+}
+
+// -----------------------------------------------------------------------------
+// Series and trends
+// -----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StoragePoint {
+    pub scanned_at: i64,
+    pub total_bytes: i64,
+    pub used_bytes: i64,
+    pub available_bytes: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StorageSeries {
+    pub drive: String,
+    pub label: Option<String>,
+    pub mount_point: Option<String>,
+    pub points: Vec<StoragePoint>,
+}
+
+/// Samples since `since`, grouped by drive (latest mount point and label).
+fn load_series(
+    conn: &Connection,
+    drive: Option<&str>,
+    since: i64,
+) -> Result<Vec<StorageSeries>, String> {
+    ensure_tables(conn)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT drive, label, mount_point, scanned_at, COALESCE(total_bytes, 0),
+                    COALESCE(used_bytes, 0), COALESCE(available_bytes, 0)
+             FROM storage_scans
+             WHERE scanned_at >= ?1 AND (?2 IS NULL OR drive = ?2)
+             ORDER BY drive, scanned_at",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![since, drive], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, Option<String>>(1)?,
+                r.get::<_, Option<String>>(2)?,
+                StoragePoint {
+                    scanned_at: r.get(3)?,
+                    total_bytes: r.get(4)?,
+                    used_bytes: r.get(5)?,
+                    available_bytes: r.get(6)?,
+                },
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut out: Vec<StorageSeries> = Vec::new();
+    for row in rows {
+        let (drive, label, mount_point, point) = row.map_err(|e| e.to_string())?;
+        match out.last_mut() {
+            Some(s) if s.drive == drive => {
+                if label.is_some() {
+                    s.label = label;
+                }
+                s.mount_point = mount_point;
+                s.points.push(point);
+            }
+            _ => out.push(StorageSeries {
+                drive,
+                label,
+                mount_point,
+                points: vec![point],
+            }),
+        }
+    }
+    Ok(out)
+}
+
+/// Least-squares slope of used bytes over time, in bytes per day.
+fn growth_per_day(points: &[StoragePoint]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let t0 = points[0].scanned_at;
+    let xs: Vec<f64> = points
+        .iter()
+        .map(|p| (p.scanned_at - t0) as f64 / 86_400.0)
+        .collect();
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.used_bytes as f64).sum::<f64>() / n;
+    let mut num = 0.0;
+    let mut den = 0.0;
+    for (x, p) in xs.iter().zip(points) {
+        num += (x - mean_x) * (p.used_bytes as f64 - mean_y);
+        den += (x - mean_x) * (x - mean_x);
+    }
+    if den <= 0.0 {
+        None
+    } else {
+        Some(num / den)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StorageTrend {
+    pub drive: String,
+    pub label: Option<String>,
+    pub mount_point: Option<String>,
+    pub total_bytes: i64,
+    pub used_bytes: i64,
+    pub available_bytes: i64,
+    pub used_pct: f64,
+    pub last_sample_at: i64,
+    pub samples: usize,
+    pub window_days: i64,
+    /// Change in used bytes over the window (last sample minus first).
+    pub growth_bytes: i64,
+    /// Fitted growth rate; None with fewer than two samples.
+    pub growth_bytes_per_day: Option<f64>,
+    /// Days until no space is left at the fitted rate; None unless growing.
+    pub days_until_full: Option<f64>,
+    pub alert_threshold_pct: Option<f64>,
+}
+
+fn trend_of(conn: &Connection, s: StorageSeries, window_days: i64) -> Result<StorageTrend, String> {
+    let first = s.points[0].clone();
+    let last = s.points[s.points.len() - 1].clone();
+    let rate = growth_per_day(&s.points);
+    let days_until_full = rate
+        .filter(|r| *r > 0.0)
+        .map(|r| last.available_bytes.max(0) as f64 / r);
+    Ok(StorageTrend {
+        alert_threshold_pct: crate::alerts::disk_full_threshold(conn, &s.drive)?,
+        drive: s.drive,
+        label: s.label,
+        mount_point: s.mount_point,
+        total_bytes: last.total_bytes,
+        used_bytes: last.used_bytes,
+        available_bytes: last.available_bytes,
+        used_pct: used_pct(last.used_bytes, last.total_bytes),
+        last_sample_at: last.scanned_at,
+        samples: s.points.len(),
+        window_days,
+        growth_bytes: last.used_bytes - first.used_bytes,
+        growth_bytes_per_day: rate,
+        days_until_full,
+    })
+}
+
+// -----------------------------------------------------------------------------
+// Commands
+// -----------------------------------------------------------------------------
+
+/// Admin: usage samples of the last `days` days (default 30), optionally for
+/// one drive.
+#[command]
+pub fn admin_storage_history(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    drive: Option<String>,
+    days: Option<i64>,
+) -> Result<Vec<StorageSeries>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    let days = days.unwrap_or(DEFAULT_TREND_DAYS).max(1);
+    let since = Utc::now()
+        .timestamp()
+        .saturating_sub(days.saturating_mul(86_400));
+    load_series(&conn, drive.as_deref(), since)
+}
+
+/// Admin: growth rate and projected days until full per drive, fitted over
+/// the last `days` days (default 30). Fullest drives first.
+#[command]
+pub fn admin_storage_trends(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    days: Option<i64>,
+) -> Result<Vec<StorageTrend>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    let days = days.unwrap_or(DEFAULT_TREND_DAYS).max(1);
+    let since = Utc::now()
+        .timestamp()
+        .saturating_sub(days.saturating_mul(86_400));
+
+    let mut out = Vec::new();
+    for s in load_series(&conn, None, since)? {
+        out.push(trend_of(&conn, s, days)?);
+    }
+    out.sort_by(|a, b| b.used_pct.total_cmp(&a.used_pct));
+    Ok(out)
+}
+
+/// Admin: take a sample of every drive now.
+#[command]
+pub fn admin_storage_sample_now(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<SampleReport, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    record_samples(&conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(day: i64, used: i64) -> StoragePoint {
+        StoragePoint {
+            scanned_at: 1_700_000_000 + day * 86_400,
+            total_bytes: 1_000_000,
+            used_bytes: used,
+            available_bytes: 1_000_000 - used,
+        }
+    }
+
+    #[test]
+    fn growth_is_the_fitted_slope_per_day() {
+        let steady = [
+            point(0, 1_000),
+            point(1, 1_500),
+            point(2, 2_000),
+            point(4, 3_000),
+        ];
+        assert_eq!(growth_per_day(&steady), Some(500.0));
+
+        let shrinking = [point(0, 9_000), point(2, 5_000)];
+        assert_eq!(growth_per_day(&shrinking), Some(-2_000.0));
+
+        // noise around a line still fits the line
+        let noisy = [point(0, 100), point(1, 300), point(2, 300), point(3, 500)];
+        let rate = growth_per_day(&noisy).unwrap();
+        assert!((rate - 120.0).abs() < 1e-9, "{}", rate);
+    }
+
+    #[test]
+    fn growth_needs_two_distinct_times() {
+        assert_eq!(growth_per_day(&[]), None);
+        assert_eq!(growth_per_day(&[point(0, 10)]), None);
+        assert_eq!(growth_per_day(&[point(3, 10), point(3, 20)]), None);
+    }
+}
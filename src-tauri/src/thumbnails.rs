@@ -0,0 +1,759 @@
+// src-tauri/src/thumbnails.rs
+//
+// Thumbnails of indexed images, PDF first pages and PPTX first slides, so
+// slide decks and scans can be browsed without opening each file in an
+// external app. Generated on request and kept as JPEGs in a size-bounded
+// cache under the data dir, keyed by the file's content hash (content_hash.rs)
+// and edge length, so copies and moved files share one entry. The least
+// recently used entries are evicted once the cache exceeds its limit.
+//
+// PDF pages are rendered with poppler's `pdftoppm` (the tool the OCR stage
+// uses); without it, the largest image on the first page stands in, which
+// covers scanned documents. PPTX files use the thumbnail PowerPoint saves in
+// the package, falling back to the first picture on slide 1.
+//
+// The webview loads cache files through the asset protocol, whose scope is
+// empty by default: each file is added to it only after the requesting
+// session has been validated and allowed to see the source file. Grants are
+// recorded per session and withdrawn again when the entry is evicted or the
+// session logs out. The scope never takes a withdrawn path back, so a cache
+// file gets a fresh name before it is handed out again.
+use crate::content_extract;
+use image::{DynamicImage, GrayImage, RgbImage};
+use once_cell::sync::{Lazy, OnceCell};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{command, AppHandle, Manager};
+
+const CONFIG_KEY: &str = "thumbnails.config";
+const MIN_EDGE: u32 = 32;
+const MAX_EDGE: u32 = 1024;
+const JPEG_QUALITY: u8 = 80;
+/// Largest source file a thumbnail is made from.
+const MAX_SOURCE_BYTES: u64 = 100 * 1024 * 1024;
+const PDFTOPPM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Set on the first grant; eviction and logout withdraw grants through it.
+static APP: OnceCell<AppHandle> = OnceCell::new();
+/// Cache files added to the asset protocol scope, by session token.
+static GRANTED: Lazy<Mutex<HashMap<String, HashSet<PathBuf>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+/// Cache files withdrawn from the scope; renamed before being handed out again.
+static REVOKED: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SourceKind {
+    Image,
+    Pdf,
+    Pptx,
+}
+
+impl SourceKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            SourceKind::Image => "image",
+            SourceKind::Pdf => "pdf",
+            SourceKind::Pptx => "pptx",
+        }
+    }
+}
+
+const SOURCE_EXTENSIONS: &[(&str, SourceKind)] = &[
+    ("png", SourceKind::Image),
+    ("jpg", SourceKind::Image),
+    ("jpeg", SourceKind::Image),
+    ("gif", SourceKind::Image),
+    ("bmp", SourceKind::Image),
+    ("tif", SourceKind::Image),
+    ("tiff", SourceKind::Image),
+    ("webp", SourceKind::Image),
+    ("pdf", SourceKind::Pdf),
+    ("pptx", SourceKind::Pptx),
+    ("pptm", SourceKind::Pptx),
+];
+
+fn kind_for_name(name: &str) -> Option<SourceKind> {
+    let ext = name.rsplit_once('.')?.1.to_ascii_lowercase();
+    SOURCE_EXTENSIONS
+        .iter()
+        .find(|(e, _)| *e == ext)
+        .map(|(_, k)| *k)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThumbnailConfig {
+    /// Upper bound on the total size of cached thumbnails, in bytes.
+    #[serde(default = "default_max_cache_bytes")]
+    pub max_cache_bytes: i64,
+    /// Longest edge, in pixels, when a request does not ask for one.
+    #[serde(default = "default_edge")]
+    pub default_edge: u32,
+}
+
+fn default_max_cache_bytes() -> i64 {
+    256 * 1024 * 1024
+}
+
+fn default_edge() -> u32 {
+    256
+}
+
+impl Default for ThumbnailConfig {
+    fn default() -> Self {
+        ThumbnailConfig {
+            max_cache_bytes: default_max_cache_bytes(),
+            default_edge: default_edge(),
+        }
+    }
+}
+
+pub fn load_config(conn: &Connection) -> Result<ThumbnailConfig, String> {
+    match crate::db::get_setting(conn, CONFIG_KEY)? {
+        Some(raw) => serde_json::from_str(&raw).map_err(|e| format!("thumbnail config: {}", e)),
+        None => Ok(ThumbnailConfig::default()),
+    }
+}
+
+fn validate_config(cfg: &ThumbnailConfig) -> Result<(), String> {
+    if cfg.max_cache_bytes < 1024 * 1024 {
+        return Err("max_cache_bytes must be at least 1 MiB".into());
+    }
+    if !(MIN_EDGE..=MAX_EDGE).contains(&cfg.default_edge) {
+        return Err(format!(
+            "default_edge must be between {} and {}",
+            MIN_EDGE, MAX_EDGE
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn ensure_tables(conn: &Connection) -> Result<(), String> {
+    crate::content_hash::ensure_tables(conn)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS thumbnail_cache (
+            cache_key TEXT PRIMARY KEY,
+            file_name TEXT NOT NULL,
+            source_kind TEXT NOT NULL,
+            bytes INTEGER NOT NULL,
+            width INTEGER NOT NULL,
+            height INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            last_access INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_thumbnail_cache_access ON thumbnail_cache(last_access);",
+    )
+    .map_err(|e| format!("ensure thumbnail_cache: {}", e))
+}
+
+fn cache_dir() -> Result<PathBuf, String> {
+    let mut p = crate::db::data_dir()?;
+    p.push("thumbnails");
+    std::fs::create_dir_all(&p).map_err(|e| format!("create {}: {}", p.display(), e))?;
+    Ok(p)
+}
+
+#[derive(Debug, Serialize)]
+pub struct Thumbnail {
+    /// Cached JPEG, to be loaded through the asset protocol.
+    pub asset_path: String,
+    pub width: u32,
+    pub height: u32,
+    pub content_hash: String,
+}
+
+/// The cache entry for `key`, if its file is still there.
+fn cached(
+    conn: &Connection,
+    dir: &Path,
+    key: &str,
+    hash: &str,
+) -> Result<Option<Thumbnail>, String> {
+    let row: Option<(String, u32, u32)> = conn
+        .query_row(
+            "SELECT file_name, width, height FROM thumbnail_cache WHERE cache_key = ?1",
+            params![key],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    let (file_name, width, height) = match row {
+        Some(r) => r,
+        None => return Ok(None),
+    };
+    let mut file = dir.join(&file_name);
+    if !file.is_file() {
+        conn.execute(
+            "DELETE FROM thumbnail_cache WHERE cache_key = ?1",
+            params![key],
+        )
+        .map_err(|e| e.to_string())?;
+        return Ok(None);
+    }
+    if is_revoked(&file) {
+        let renamed = cache_file_name(key);
+        let to = dir.join(&renamed);
+        std::fs::rename(&file, &to).map_err(|e| format!("rename {}: {}", file.display(), e))?;
+        conn.execute(
+            "UPDATE thumbnail_cache SET file_name = ?2 WHERE cache_key = ?1",
+            params![key, renamed],
+        )
+        .map_err(|e| e.to_string())?;
+        file = to;
+    }
+    conn.execute(
+        "UPDATE thumbnail_cache SET last_access = ?2 WHERE cache_key = ?1",
+        params![key, chrono::Utc::now().timestamp()],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(Some(Thumbnail {
+        asset_path: file.to_string_lossy().to_string(),
+        width,
+        height,
+        content_hash: hash.to_string(),
+    }))
+}
+
+/// A new cache file name for `key`; unique, so it has never been withdrawn
+/// from the asset protocol scope.
+fn cache_file_name(key: &str) -> String {
+    format!("{}-{}.jpg", key, uuid::Uuid::new_v4().simple())
+}
+
+/// Add `file` to the asset protocol scope on behalf of `session_token`.
+fn grant(app: &AppHandle, session_token: &str, file: &Path) -> Result<(), String> {
+    app.asset_protocol_scope()
+        .allow_file(file)
+        .map_err(|e| format!("asset scope: {}", e))?;
+    let _ = APP.set(app.clone());
+    if let Ok(mut granted) = GRANTED.lock() {
+        granted
+            .entry(session_token.to_string())
+            .or_default()
+            .insert(file.to_path_buf());
+    }
+    Ok(())
+}
+
+fn is_revoked(file: &Path) -> bool {
+    REVOKED.lock().map(|r| r.contains(file)).unwrap_or(false)
+}
+
+fn forbid(file: &Path) {
+    let app = match APP.get() {
+        Some(app) => app,
+        None => return,
+    };
+    if let Err(e) = app.asset_protocol_scope().forbid_file(file) {
+        eprintln!("[thumbnails] asset scope: {}", e);
+    }
+    if let Ok(mut revoked) = REVOKED.lock() {
+        revoked.insert(file.to_path_buf());
+    }
+}
+
+/// Withdraw `file` from every session it was granted to (it was evicted).
+fn revoke_file(file: &Path) {
+    let mut was_granted = false;
+    if let Ok(mut granted) = GRANTED.lock() {
+        for files in granted.values_mut() {
+            was_granted |= files.remove(file);
+        }
+        granted.retain(|_, files| !files.is_empty());
+    }
+    if was_granted {
+        forbid(file);
+    }
+}
+
+/// Withdraw the cache files granted to `session_token` that no other
+/// session still holds (logout).
+pub(crate) fn revoke_session(session_token: &str) {
+    let withdrawn: Vec<PathBuf> = match GRANTED.lock() {
+        Ok(mut granted) => match granted.remove(session_token) {
+            Some(files) => files
+                .into_iter()
+                .filter(|f| !granted.values().any(|other| other.contains(f)))
+                .collect(),
+            None => return,
+        },
+        Err(_) => return,
+    };
+    for file in withdrawn {
+        forbid(&file);
+    }
+}
+
+/// Drop least recently used entries (other than `keep`) until the cache
+/// fits in `max_bytes`. Returns the number removed.
+fn evict(
+    conn: &Connection,
+    dir: &Path,
+    max_bytes: i64,
+    keep: Option<&str>,
+) -> Result<usize, String> {
+    let mut total: i64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(bytes), 0) FROM thumbnail_cache",
+            [],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if total <= max_bytes {
+        return Ok(0);
+    }
+    let entries: Vec<(String, String, i64)> = {
+        let mut stmt = conn
+            .prepare("SELECT cache_key, file_name, bytes FROM thumbnail_cache ORDER BY last_access")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+            .map_err(|e| e.to_string())?;
+        rows.filter_map(|r| r.ok()).collect()
+    };
+    let mut removed = 0;
+    for (key, file_name, bytes) in entries {
+        if total <= max_bytes {
+            break;
+        }
+        if Some(key.as_str()) == keep {
+            continue;
+        }
+        let file = dir.join(&file_name);
+        let _ = std::fs::remove_file(&file);
+        revoke_file(&file);
+        conn.execute(
+            "DELETE FROM thumbnail_cache WHERE cache_key = ?1",
+            params![key],
+        )
+        .map_err(|e| e.to_string())?;
+        total -= bytes;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+// -----------------------------------------------------------------------------
+// Sources
+// -----------------------------------------------------------------------------
+
+fn decode(bytes: &[u8]) -> Result<DynamicImage, String> {
+    image::load_from_memory(bytes).map_err(|e| format!("decode image: {}", e))
+}
+
+/// First page rendered by pdftoppm, scaled so its longest edge is `edge`.
+fn render_pdf_page(pdftoppm: &str, pdf: &Path, edge: u32) -> Result<DynamicImage, String> {
+    let work = std::env::temp_dir().join(format!("vaultguard-thumb-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&work).map_err(|e| format!("thumbnail work dir: {}", e))?;
+    let prefix = work.join("page");
+    let mut cmd = crate::ocr::low_priority(pdftoppm);
+    cmd.arg("-f")
+        .arg("1")
+        .arg("-l")
+        .arg("1")
+        .arg("-singlefile")
+        .arg("-png")
+        .arg("-scale-to")
+        .arg(edge.to_string())
+        .arg(pdf)
+        .arg(&prefix);
+    let result = match crate::ocr::run_tool(cmd, PDFTOPPM_TIMEOUT) {
+        Ok(()) => std::fs::read(prefix.with_extension("png"))
+            .map_err(|e| format!("pdftoppm output: {}", e))
+            .and_then(|png| decode(&png)),
+        Err(crate::ocr::ToolError::Timeout) => Err("pdftoppm timed out".to_string()),
+        Err(crate::ocr::ToolError::Failed(m)) => Err(format!("pdftoppm: {}", m)),
+    };
+    let _ = std::fs::remove_dir_all(&work);
+    result
+}
+
+/// Largest image drawn on the first page (a scanned page is one image).
+fn pdf_page_image(bytes: &[u8]) -> Result<DynamicImage, String> {
+    let doc = lopdf::Document::load_mem(bytes).map_err(|e| format!("pdf parse: {}", e))?;
+    let page_id = *doc
+        .get_pages()
+        .values()
+        .next()
+        .ok_or_else(|| "pdf has no pages".to_string())?;
+    let (own, inherited) = doc.get_page_resources(page_id);
+    let resources = own.into_iter().chain(
+        inherited
+            .iter()
+            .filter_map(|id| doc.get_dictionary(*id).ok()),
+    );
+
+    let mut best: Option<(i64, &lopdf::Stream)> = None;
+    for res in resources {
+        let xobjects = match res.get_deref(b"XObject", &doc).and_then(|o| o.as_dict()) {
+            Ok(x) => x,
+            Err(_) => continue,
+        };
+        for (_, obj) in xobjects.iter() {
+            let stream = match doc.dereference(obj).and_then(|(_, o)| o.as_stream()) {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
+            let dict = &stream.dict;
+            if dict.get(b"Subtype").and_then(|o| o.as_name()).ok() != Some(&b"Image"[..]) {
+                continue;
+            }
+            let dim = |key: &[u8]| dict.get(key).and_then(|o| o.as_i64()).unwrap_or(0);
+            let area = dim(b"Width") * dim(b"Height");
+            if !matches!(best, Some((a, _)) if a >= area) {
+                best = Some((area, stream));
+            }
+        }
+    }
+    let stream = best
+        .ok_or_else(|| "no image on the first page".to_string())?
+        .1;
+    pdf_image(stream)
+}
+
+/// Decode an image XObject: JPEG data as is, otherwise 8-bit gray, RGB or
+/// CMYK samples (the component count follows from the data length).
+fn pdf_image(stream: &lopdf::Stream) -> Result<DynamicImage, String> {
+    let filters = stream.filters().unwrap_or_default();
+    if filters.iter().any(|f| f == "DCTDecode") {
+        return decode(&stream.content);
+    }
+    let dim = |key: &[u8]| {
+        stream
+            .dict
+            .get(key)
+            .and_then(|o| o.as_i64())
+            .ok()
+            .filter(|v| *v > 0)
+            .map(|v| v as u32)
+    };
+    let (w, h) = match (dim(b"Width"), dim(b"Height")) {
+        (Some(w), Some(h)) => (w, h),
+        _ => return Err("pdf image without dimensions".into()),
+    };
+    if dim(b"BitsPerComponent") != Some(8) {
+        return Err("unsupported pdf image depth".into());
+    }
+    let data = if filters.is_empty() {
+        stream.content.clone()
+    } else {
+        // lopdf declines to decompress image streams; a copy without the
+        // Subtype is decoded like any other stream
+        let mut plain = stream.clone();
+        plain.dict.remove(b"Subtype");
+        plain
+            .decompressed_content()
+            .map_err(|e| format!("pdf image: {}", e))?
+    };
+    let pixels = w as usize * h as usize;
+    let img = match data.len().checked_div(pixels) {
+        Some(1) => GrayImage::from_raw(w, h, data).map(DynamicImage::ImageLuma8),
+        Some(3) => RgbImage::from_raw(w, h, data).map(DynamicImage::ImageRgb8),
+        Some(4) => {
+            let rgb = data
+                .chunks_exact(4)
+                .flat_map(|p| {
+                    let k = 255 - p[3] as u16;
+                    [0, 1, 2].map(|i| ((255 - p[i] as u16) * k / 255) as u8)
+                })
+                .collect();
+            RgbImage::from_raw(w, h, rgb).map(DynamicImage::ImageRgb8)
+        }
+        _ => None,
+    };
+    img.ok_or_else(|| "unsupported pdf image format".to_string())
+}
+
+/// Targets of the image relationships in a part's .rels file.
+fn image_targets(rels: &[u8]) -> Vec<String> {
+    use quick_xml::events::Event;
+    let mut reader = quick_xml::Reader::from_reader(rels);
+    let mut buf = Vec::new();
+    let mut out = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e))
+                if e.local_name().as_ref() == b"Relationship" =>
+            {
+                let mut is_image = false;
+                let mut target = None;
+                for a in e.attributes().flatten() {
+                    let value = a
+                        .unescape_value()
+                        .map(|v| v.to_string())
+                        .unwrap_or_default();
+                    match a.key.local_name().as_ref() {
+                        b"Type" => is_image = value.ends_with("/image"),
+                        b"Target" => target = Some(value),
+                        _ => {}
+                    }
+                }
+                if let (true, Some(t)) = (is_image, target) {
+                    out.push(t);
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    out
+}
+
+/// Package part name of a relationship target relative to `base_dir`.
+fn resolve_part(base_dir: &str, target: &str) -> String {
+    let mut parts: Vec<&str> = if target.starts_with('/') {
+        Vec::new()
+    } else {
+        base_dir.split('/').filter(|s| !s.is_empty()).collect()
+    };
+    for seg in target.split('/') {
+        match seg {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            s => parts.push(s),
+        }
+    }
+    parts.join("/")
+}
+
+/// The package thumbnail PowerPoint saves, else the first picture on slide 1.
+fn pptx_preview(bytes: &[u8]) -> Result<DynamicImage, String> {
+    let mut pkg = content_extract::open_package(bytes)?;
+    for part in [
+        "docProps/thumbnail.jpeg",
+        "docProps/thumbnail.jpg",
+        "docProps/thumbnail.png",
+    ] {
+        if let Some(img) = content_extract::read_part(&mut pkg, part)?.and_then(|b| decode(&b).ok())
+        {
+            return Ok(img);
+        }
+    }
+    let rels = content_extract::read_part(&mut pkg, "ppt/slides/_rels/slide1.xml.rels")?
+        .ok_or_else(|| "presentation has no preview".to_string())?;
+    for target in image_targets(&rels) {
+        let part = resolve_part("ppt/slides", &target);
+        if let Ok(Some(img)) = content_extract::read_part(&mut pkg, &part) {
+            if let Ok(img) = decode(&img) {
+                return Ok(img);
+            }
+        }
+    }
+    Err("presentation has no preview".into())
+}
+
+fn source_image(
+    conn: &Connection,
+    kind: SourceKind,
+    path: &Path,
+    bytes: &[u8],
+    edge: u32,
+) -> Result<DynamicImage, String> {
+    match kind {
+        SourceKind::Image => decode(bytes),
+        SourceKind::Pdf => {
+            let cfg = crate::ocr::load_config(conn)?;
+            let pdftoppm = cfg.pdftoppm_path.unwrap_or_else(|| "pdftoppm".into());
+            let rendered = match crate::ocr::tool_probe(&pdftoppm, "-v") {
+                Some(_) => render_pdf_page(&pdftoppm, path, edge),
+                None => Err("pdftoppm not found".into()),
+            };
+            rendered.or_else(|e| pdf_page_image(bytes).map_err(|e2| format!("{}; {}", e, e2)))
+        }
+        SourceKind::Pptx => pptx_preview(bytes),
+    }
+}
+
+/// Thumbnail of the indexed file `file_id` at `path`, from the cache or
+/// generated now.
+pub(crate) fn thumbnail_for(
+    conn: &Connection,
+    cfg: &ThumbnailConfig,
+    file_id: i64,
+    path: &str,
+    edge: u32,
+) -> Result<Thumbnail, String> {
+    let kind = kind_for_name(path).ok_or_else(|| "no preview for this file type".to_string())?;
+    let meta = std::fs::metadata(path).map_err(|e| format!("stat {}: {}", path, e))?;
+    if !meta.is_file() {
+        return Err("path is not a file".into());
+    }
+    if meta.len() > MAX_SOURCE_BYTES {
+        return Err(format!("{} bytes is too large for a preview", meta.len()));
+    }
+
+    let algorithm = crate::content_hash::load_config(conn)?.algorithm;
+    let hash = crate::content_hash::current_hash(conn, file_id, path, algorithm)?
+        .ok_or_else(|| "file changed while it was read".to_string())?;
+    let key = format!("{}-{}", hash, edge);
+    let dir = cache_dir()?;
+    if let Some(t) = cached(conn, &dir, &key, &hash)? {
+        return Ok(t);
+    }
+
+    let bytes = std::fs::read(path).map_err(|e| format!("read {}: {}", path, e))?;
+    let thumb = source_image(conn, kind, Path::new(path), &bytes, edge)?
+        .thumbnail(edge, edge)
+        .to_rgb8();
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+        .encode_image(&thumb)
+        .map_err(|e| format!("encode thumbnail: {}", e))?;
+    let file_name = cache_file_name(&key);
+    let file = dir.join(&file_name);
+    std::fs::write(&file, &jpeg).map_err(|e| format!("write {}: {}", file.display(), e))?;
+
+    let now = chrono::Utc::now().timestamp();
+    conn.execute(
+        "INSERT OR REPLACE INTO thumbnail_cache
+           (cache_key, file_name, source_kind, bytes, width, height, created_at, last_access)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+        params![
+            key,
+            file_name,
+            kind.as_str(),
+            jpeg.len() as i64,
+            thumb.width(),
+            thumb.height(),
+            now
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    evict(conn, &dir, cfg.max_cache_bytes, Some(&key))?;
+
+    Ok(Thumbnail {
+        asset_path: file.to_string_lossy().to_string(),
+        width: thumb.width(),
+        height: thumb.height(),
+        content_hash: hash,
+    })
+}
+
+// -----------------------------------------------------------------------------
+// Commands
+// -----------------------------------------------------------------------------
+
+/// Thumbnail of an indexed image, PDF or presentation, longest edge `edge`
+/// pixels (default from the settings). The returned `asset_path` is added
+/// to the asset protocol scope until it is evicted or the session logs out.
+#[command]
+pub fn get_thumbnail(
+    app: AppHandle,
+    session_token: String,
+    path: String,
+    edge: Option<u32>,
+) -> Result<Thumbnail, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("db open: {}", e))?;
+    let uid = crate::session::validate_session(&conn, &session_token)
+        .map_err(|e| format!("session validation: {}", e))?
+        .ok_or_else(|| "invalid session".to_string())?;
+    ensure_tables(&conn)?;
+
+    let file_id = crate::fs_ops::visible_file_id(&conn, uid, &path)?
+        .ok_or_else(|| "file is not indexed or not accessible".to_string())?;
+    let cfg = load_config(&conn)?;
+    let edge = edge.unwrap_or(cfg.default_edge).clamp(MIN_EDGE, MAX_EDGE);
+    let thumb = thumbnail_for(&conn, &cfg, file_id, &path, edge)?;
+    grant(&app, &session_token, Path::new(&thumb.asset_path))?;
+    Ok(thumb)
+}
+
+#[derive(Debug, Serialize)]
+pub struct ThumbnailCacheStatus {
+    pub config: ThumbnailConfig,
+    pub entries: i64,
+    pub bytes: i64,
+}
+
+fn status(conn: &Connection) -> Result<ThumbnailCacheStatus, String> {
+    let (entries, bytes) = conn
+        .query_row(
+            "SELECT COUNT(*), COALESCE(SUM(bytes), 0) FROM thumbnail_cache",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(ThumbnailCacheStatus {
+        config: load_config(conn)?,
+        entries,
+        bytes,
+    })
+}
+
+#[command]
+pub fn admin_get_thumbnail_settings(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<ThumbnailCacheStatus, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    ensure_tables(&conn)?;
+    status(&conn)
+}
+
+/// Save the cache settings; a lower limit takes effect immediately.
+#[command]
+pub fn admin_set_thumbnail_settings(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+    config: ThumbnailConfig,
+) -> Result<ThumbnailCacheStatus, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    ensure_tables(&conn)?;
+
+    validate_config(&config)?;
+    let raw = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    crate::db::set_setting(&conn, CONFIG_KEY, &raw)?;
+    let evicted = evict(&conn, &cache_dir()?, config.max_cache_bytes, None)?;
+
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_set_thumbnail_settings",
+        None,
+        Some(&format!(
+            "max_cache_bytes={} default_edge={} evicted={}",
+            config.max_cache_bytes, config.default_edge, evicted
+        )),
+    );
+
+    status(&conn)
+}
+
+/// Delete every cached thumbnail. Returns the number removed.
+#[command]
+pub fn admin_clear_thumbnail_cache(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<usize, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    let (admin_id, admin_username) =
+        crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    ensure_tables(&conn)?;
+
+    let removed = evict(&conn, &cache_dir()?, 0, None)?;
+    let _ = crate::db::insert_audit_log(
+        &conn,
+        Some(admin_id),
+        Some(&admin_username),
+        "admin_clear_thumbnail_cache",
+        None,
+        Some(&format!("removed:{}", removed)),
+    );
+    Ok(removed)
+}
@@ -0,0 +1,671 @@
+// src-tauri/src/volumes.rs
+//
+// Stable identity for mounted volumes. A drive is known by its filesystem
+// UUID (volume serial number on Windows), or failing that its label or
+// network share, instead of the path it happens to be mounted at, so a USB
+// stick plugged in under a different mount point (or drive letter) keeps its
+// index, tags and file history.
+//
+// Every files_index row records the volume it lives on and its path relative
+// to the volume root. `refresh` reads the current mount table; a known volume
+// that shows up at a new mount point has its rows (and tags and file
+// identities) re-based onto the new root. Volumes that are not mounted are
+// offline: their rows are left alone by walks and by `reconcile_missing_files`
+// until the volume returns. If another volume takes an offline volume's
+// mount point, the offline rows are parked under a detached root
+// (`[<key>]/...`) so the two indexes cannot collide.
+use once_cell::sync::Lazy;
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use serde::Serialize;
+use std::path::{Path, MAIN_SEPARATOR};
+use std::sync::RwLock;
+use tauri::{command, AppHandle};
+
+/// A mounted filesystem as read from the OS.
+#[derive(Debug, Clone)]
+pub(crate) struct MountedVolume {
+    /// Stable identity: "uuid:…", "label:…", "net:…" or "dev:…".
+    pub key: String,
+    pub uuid: Option<String>,
+    pub label: Option<String>,
+    pub fs_type: String,
+    pub device: Option<String>,
+    /// Mount point in the form index paths use (canonicalized).
+    pub root: String,
+}
+
+/// Where an indexed path lives.
+#[derive(Debug, Clone)]
+pub(crate) struct Located {
+    pub volume_id: i64,
+    pub key: String,
+    /// Path below the volume root, without a leading separator.
+    pub rel_path: String,
+}
+
+/// Online volumes (with their volumes.id) as of the last refresh, longest
+/// root first.
+static MOUNTS: Lazy<RwLock<Vec<(i64, MountedVolume)>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+pub(crate) fn ensure_tables(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS volumes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            key TEXT NOT NULL UNIQUE,
+            uuid TEXT,
+            label TEXT,
+            fs_type TEXT,
+            device TEXT,
+            mount_point TEXT,
+            online INTEGER NOT NULL DEFAULT 0,
+            first_seen_at INTEGER NOT NULL,
+            last_seen_at INTEGER NOT NULL,
+            last_mounted_at INTEGER
+        );",
+    )
+    .map_err(|e| format!("ensure volumes: {}", e))
+}
+
+fn same_path(a: &str, b: &str) -> bool {
+    if cfg!(target_os = "windows") {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
+}
+
+/// Path of `path` below `root`, if it is inside it.
+fn relative_to<'a>(path: &'a str, root: &str) -> Option<&'a str> {
+    let head = path.get(..root.len())?;
+    if !same_path(head, root) {
+        return None;
+    }
+    let rest = &path[root.len()..];
+    if rest.is_empty() {
+        return Some("");
+    }
+    if root.ends_with(['/', '\\']) {
+        return Some(rest);
+    }
+    rest.strip_prefix(['/', '\\'])
+}
+
+fn join_root(root: &str, rel: &str) -> String {
+    if rel.is_empty() {
+        root.to_string()
+    } else if root.ends_with(['/', '\\']) {
+        format!("{}{}", root, rel)
+    } else {
+        format!("{}{}{}", root, MAIN_SEPARATOR, rel)
+    }
+}
+
+/// Online volume holding `path`, from the mount table of the last refresh.
+/// A mount point itself is a directory of the enclosing volume.
+pub(crate) fn locate(path: &str) -> Option<Located> {
+    let mounts = MOUNTS.read().ok()?;
+    mounts.iter().find_map(|(id, v)| {
+        let rel = relative_to(path, &v.root).filter(|rel| !rel.is_empty())?;
+        Some(Located {
+            volume_id: *id,
+            key: v.key.clone(),
+            rel_path: rel.to_string(),
+        })
+    })
+}
+
+/// (key, label) of the online volume mounted exactly at `mount`.
+pub(crate) fn volume_at(mount: &str) -> Option<(String, Option<String>)> {
+    let root = canonical_root(mount);
+    let mounts = MOUNTS.read().ok()?;
+    mounts
+        .iter()
+        .find(|(_, v)| same_path(&v.root, &root))
+        .map(|(_, v)| (v.key.clone(), v.label.clone()))
+}
+
+/// Detached root for the rows of an offline volume whose mount point has
+/// been taken by another volume.
+fn detached_root(key: &str) -> String {
+    format!("[{}]", key)
+}
+
+// -----------------------------------------------------------------------------
+// Mount table
+// -----------------------------------------------------------------------------
+
+/// Canonical form of a mount point, without the `\\?\` verbatim prefix
+/// Windows puts on canonical paths (stored roots must match walked paths).
+fn canonical_root(mount: &str) -> String {
+    dunce::canonicalize(mount)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| mount.to_string())
+}
+
+/// Decode `\` escapes: octal `\ooo` (mountinfo) or hex `\xHH` (udev links).
+#[cfg(target_os = "linux")]
+fn unescape(s: &str, hex: bool) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' {
+            let decoded = if hex {
+                s.get(i + 1..i + 4)
+                    .and_then(|t| t.strip_prefix('x'))
+                    .and_then(|t| u8::from_str_radix(t, 16).ok())
+            } else {
+                s.get(i + 1..i + 4)
+                    .and_then(|t| u8::from_str_radix(t, 8).ok())
+            };
+            if let Some(v) = decoded {
+                out.push(v);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// device path -> name of its link in /dev/disk/by-<kind>
+#[cfg(target_os = "linux")]
+fn disk_links(kind: &str) -> std::collections::HashMap<String, String> {
+    let mut out = std::collections::HashMap::new();
+    if let Ok(rd) = std::fs::read_dir(format!("/dev/disk/by-{}", kind)) {
+        for e in rd.flatten() {
+            if let Ok(target) = std::fs::canonicalize(e.path()) {
+                out.insert(
+                    target.to_string_lossy().to_string(),
+                    unescape(&e.file_name().to_string_lossy(), true),
+                );
+            }
+        }
+    }
+    out
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn scan_mounts() -> Vec<MountedVolume> {
+    const NETWORK_FS: &[&str] = &["nfs", "nfs4", "cifs", "smb3", "smbfs", "fuse.sshfs", "9p"];
+    let raw = match std::fs::read_to_string("/proc/self/mountinfo") {
+        Ok(s) => s,
+        Err(_) => return Vec::new(),
+    };
+    let uuids = disk_links("uuid");
+    let labels = disk_links("label");
+    let partuuids = disk_links("partuuid");
+    let mut out: Vec<MountedVolume> = Vec::new();
+    for line in raw.lines() {
+        // id parent maj:min root mount_point options [optional...] - fs_type source super
+        let (pre, post) = match line.split_once(" - ") {
+            Some(v) => v,
+            None => continue,
+        };
+        let pre: Vec<&str> = pre.split(' ').collect();
+        let post: Vec<&str> = post.split(' ').collect();
+        if pre.len() < 5 || post.len() < 2 {
+            continue;
+        }
+        // bind mounts of a subdirectory are not volume roots
+        if pre[3] != "/" {
+            continue;
+        }
+        let mount = unescape(pre[4], false);
+        let fs_type = post[0].to_string();
+        let source = unescape(post[1], false);
+        let (key, uuid, label, device) = if source.starts_with("/dev/") {
+            let dev = std::fs::canonicalize(&source)
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or(source.clone());
+            let uuid = uuids.get(&dev).cloned();
+            let label = labels.get(&dev).cloned();
+            let key = match (&uuid, &label, partuuids.get(&dev)) {
+                (Some(u), _, _) => format!("uuid:{}", u),
+                (None, Some(l), _) => format!("label:{}", l),
+                (None, None, Some(p)) => format!("partuuid:{}", p),
+                (None, None, None) => format!("dev:{}", dev),
+            };
+            (key, uuid, label, Some(dev))
+        } else if NETWORK_FS.contains(&fs_type.as_str()) {
+            (format!("net:{}", source), None, None, Some(source))
+        } else {
+            continue;
+        };
+        let root = canonical_root(&mount);
+        // a filesystem mounted twice keeps its first (usually primary) mount
+        if out.iter().any(|v| v.key == key) {
+            continue;
+        }
+        out.push(MountedVolume {
+            key,
+            uuid,
+            label,
+            fs_type,
+            device,
+            root,
+        });
+    }
+    out
+}
+
+#[cfg(not(target_os = "linux"))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let out = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&out.stdout).to_string())
+}
+
+/// (serial, label) from `vol X:`; the serial is the xxxx-xxxx token.
+#[cfg(target_os = "windows")]
+fn volume_info(mount: &str) -> (Option<String>, Option<String>) {
+    let drive = mount.trim_end_matches('\\');
+    let text = match command_output("cmd", &["/C", "vol", drive]) {
+        Some(t) => t,
+        None => return (None, None),
+    };
+    let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+    let label = lines
+        .next()
+        .and_then(|l| l.rsplit_once(" is "))
+        .map(|(_, l)| l.trim().to_string())
+        .filter(|l| !l.is_empty());
+    let serial = lines.next().and_then(|l| {
+        l.split_whitespace()
+            .last()
+            .filter(|t| t.len() == 9 && t.as_bytes()[4] == b'-')
+            .map(|t| t.to_string())
+    });
+    (serial, label)
+}
+
+/// (volume UUID, volume name) from `diskutil info`.
+#[cfg(target_os = "macos")]
+fn volume_info(mount: &str) -> (Option<String>, Option<String>) {
+    let text = match command_output("diskutil", &["info", mount]) {
+        Some(t) => t,
+        None => return (None, None),
+    };
+    let field = |name: &str| {
+        text.lines()
+            .find_map(|l| l.trim().strip_prefix(name))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    (field("Volume UUID:"), field("Volume Name:"))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn volume_info(_mount: &str) -> (Option<String>, Option<String>) {
+    (None, None)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn scan_mounts() -> Vec<MountedVolume> {
+    use sysinfo::{DiskExt, System, SystemExt};
+    let mut sys = System::new();
+    sys.refresh_disks_list();
+    let mut out: Vec<MountedVolume> = Vec::new();
+    for disk in sys.disks() {
+        let mount = disk.mount_point().to_string_lossy().to_string();
+        let (uuid, label) = volume_info(&mount);
+        let key = match (&uuid, &label) {
+            (Some(u), _) => format!("uuid:{}", u),
+            (None, Some(l)) => format!("label:{}", l),
+            (None, None) => format!("dev:{}", disk.name().to_string_lossy()),
+        };
+        if out.iter().any(|v| v.key == key) {
+            continue;
+        }
+        out.push(MountedVolume {
+            key,
+            uuid,
+            label,
+            fs_type: String::from_utf8_lossy(disk.file_system()).to_string(),
+            device: Some(disk.name().to_string_lossy().to_string()),
+            root: canonical_root(&mount),
+        });
+    }
+    out
+}
+
+// -----------------------------------------------------------------------------
+// Refresh / re-base
+// -----------------------------------------------------------------------------
+
+#[derive(Debug, Default, Serialize)]
+pub struct RefreshReport {
+    pub online: usize,
+    pub went_offline: Vec<String>,
+    /// (volume key, old root, new root, rows moved)
+    pub rebased: Vec<(String, String, String, usize)>,
+    /// Rows attributed to a volume for the first time.
+    pub attributed: usize,
+}
+
+/// Rows credited to an online volume mounted above `?3` (a root).
+const ENCLOSING: &str = "volume_id IN (SELECT id FROM volumes
+     WHERE online = 1 AND length(mount_point) < length(?3))";
+
+/// Move the rows of volume `id` from `old_root` to `new_root`. Returns the
+/// number of files_index rows moved.
+fn rebase(conn: &Connection, id: i64, old_root: &str, new_root: &str) -> Result<usize, String> {
+    let (lo, hi) = crate::indexer::descendant_range(new_root);
+    // rows already written at the new location (e.g. a walk that ran before
+    // this refresh) would collide with the moved ones; the moved rows carry
+    // the history, so they win
+    conn.execute(
+        &format!(
+            "DELETE FROM files_index
+             WHERE path >= ?1 AND path < ?2
+               AND (volume_id IS NULL OR volume_id = ?4 OR {})",
+            ENCLOSING
+        ),
+        params![lo, hi, new_root, id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let rows: Vec<(i64, String)> = {
+        let mut stmt = conn
+            .prepare("SELECT id, rel_path FROM files_index WHERE volume_id = ?1")
+            .map_err(|e| e.to_string())?;
+        let it = stmt
+            .query_map(params![id], |r| {
+                Ok((
+                    r.get(0)?,
+                    r.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                ))
+            })
+            .map_err(|e| e.to_string())?;
+        it.filter_map(|r| r.ok()).collect()
+    };
+    {
+        let mut stmt = conn
+            .prepare_cached("UPDATE files_index SET path = ?2, parent = ?3 WHERE id = ?1")
+            .map_err(|e| e.to_string())?;
+        for (row_id, rel) in &rows {
+            let path = join_root(new_root, rel);
+            let parent = crate::indexer::parent_of(&path);
+            stmt.execute(params![row_id, path, parent])
+                .map_err(|e| format!("rebase {}: {}", path, e))?;
+        }
+    }
+
+    // tags are keyed by path
+    let (old_lo, old_hi) = crate::indexer::descendant_range(old_root);
+    let tags: Vec<(i64, String)> = {
+        let mut stmt = conn
+            .prepare("SELECT id, path FROM file_tags WHERE path = ?1 OR (path >= ?2 AND path < ?3)")
+            .map_err(|e| e.to_string())?;
+        let it = stmt
+            .query_map(params![old_root, old_lo, old_hi], |r| {
+                Ok((r.get(0)?, r.get(1)?))
+            })
+            .map_err(|e| e.to_string())?;
+        it.filter_map(|r| r.ok()).collect()
+    };
+    for (tag_id, path) in tags {
+        if let Some(rel) = relative_to(&path, old_root) {
+            conn.execute(
+                "UPDATE file_tags SET path = ?2 WHERE id = ?1",
+                params![tag_id, join_root(new_root, rel)],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    crate::file_history::volume_rebased(conn, old_root, new_root)?;
+    Ok(rows.len())
+}
+
+/// Attribute rows written before volumes were tracked, or while their volume
+/// was not identified yet (then credited to the enclosing volume), to the
+/// volume they are on now.
+fn attribute_rows(conn: &Connection, mounts: &[(i64, MountedVolume)]) -> Result<usize, String> {
+    let mut n = 0;
+    let mut stmt = conn
+        .prepare_cached(
+            "UPDATE files_index SET volume_id = ?1, rel_path = ?2, drive = ?3 WHERE id = ?4",
+        )
+        .map_err(|e| e.to_string())?;
+    for (id, v) in mounts {
+        let (key, root) = (&v.key, &v.root);
+        let (lo, hi) = crate::indexer::descendant_range(root);
+        let rows: Vec<(i64, String)> = {
+            let mut sel = conn
+                .prepare(&format!(
+                    "SELECT id, path FROM files_index
+                         WHERE path >= ?1 AND path < ?2
+                           AND (volume_id IS NULL OR {})",
+                    ENCLOSING
+                ))
+                .map_err(|e| e.to_string())?;
+            let it = sel
+                .query_map(params![lo, hi, root], |r| Ok((r.get(0)?, r.get(1)?)))
+                .map_err(|e| e.to_string())?;
+            it.filter_map(|r| r.ok()).collect()
+        };
+        for (row_id, path) in rows {
+            // a nested mount claims its own subtree
+            if let Some(loc) = locate(&path).filter(|loc| loc.volume_id == *id) {
+                stmt.execute(params![id, loc.rel_path, key, row_id])
+                    .map_err(|e| e.to_string())?;
+                n += 1;
+            }
+        }
+    }
+    Ok(n)
+}
+
+/// Reconcile the volumes table with `mounted`: re-base volumes that moved,
+/// mark missing ones offline and attribute unassigned rows.
+pub(crate) fn refresh_with(
+    conn: &Connection,
+    mounted: &[MountedVolume],
+) -> Result<RefreshReport, String> {
+    // file_tags and file_identities are re-based along with files_index
+    crate::db::init_db_schema(conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::fs_ops::ensure_files_index_tables(conn)?;
+    let now = chrono::Utc::now().timestamp();
+    let mut report = RefreshReport::default();
+
+    // take the write lock up front: a deferred transaction that has read
+    // cannot wait for a writer and fails with "database is locked" at once
+    let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)
+        .map_err(|e| e.to_string())?;
+    let mut online_ids = Vec::new();
+    for v in mounted {
+        // an offline volume last mounted here would collide with this one
+        let displaced: Vec<(i64, String, String)> = {
+            let mut stmt = tx
+                .prepare(
+                    "SELECT id, key, mount_point FROM volumes
+                     WHERE online = 0 AND key != ?1 AND mount_point = ?2",
+                )
+                .map_err(|e| e.to_string())?;
+            let it = stmt
+                .query_map(params![v.key, v.root], |r| {
+                    Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+                })
+                .map_err(|e| e.to_string())?;
+            it.filter_map(|r| r.ok()).collect()
+        };
+        for (id, key, old_root) in displaced {
+            let parked = detached_root(&key);
+            let moved = rebase(&tx, id, &old_root, &parked)?;
+            tx.execute(
+                "UPDATE volumes SET mount_point = ?2 WHERE id = ?1",
+                params![id, parked],
+            )
+            .map_err(|e| e.to_string())?;
+            report.rebased.push((key, old_root, parked, moved));
+        }
+
+        let known: Option<(i64, Option<String>)> = tx
+            .query_row(
+                "SELECT id, mount_point FROM volumes WHERE key = ?1",
+                params![v.key],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+        let id = match known {
+            Some((id, old_root)) => {
+                if let Some(old_root) = old_root.filter(|r| !same_path(r, &v.root)) {
+                    let moved = rebase(&tx, id, &old_root, &v.root)?;
+                    report
+                        .rebased
+                        .push((v.key.clone(), old_root, v.root.clone(), moved));
+                }
+                tx.execute(
+                    "UPDATE volumes SET uuid = ?2, label = ?3, fs_type = ?4, device = ?5,
+                        mount_point = ?6, last_seen_at = ?7,
+                        last_mounted_at = CASE WHEN online = 0 THEN ?7 ELSE last_mounted_at END,
+                        online = 1
+                     WHERE id = ?1",
+                    params![id, v.uuid, v.label, v.fs_type, v.device, v.root, now],
+                )
+                .map_err(|e| e.to_string())?;
+                id
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO volumes (key, uuid, label, fs_type, device, mount_point, online,
+                                          first_seen_at, last_seen_at, last_mounted_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, 1, ?7, ?7, ?7)",
+                    params![v.key, v.uuid, v.label, v.fs_type, v.device, v.root, now],
+                )
+                .map_err(|e| e.to_string())?;
+                tx.last_insert_rowid()
+            }
+        };
+        online_ids.push(id);
+    }
+
+    let gone: Vec<(i64, String)> = {
+        let mut stmt = tx
+            .prepare("SELECT id, key FROM volumes WHERE online = 1")
+            .map_err(|e| e.to_string())?;
+        let it = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .map_err(|e| e.to_string())?;
+        it.filter_map(|r| r.ok())
+            .filter(|(id, _)| !online_ids.contains(id))
+            .collect()
+    };
+    for (id, key) in gone {
+        tx.execute("UPDATE volumes SET online = 0 WHERE id = ?1", params![id])
+            .map_err(|e| e.to_string())?;
+        report.went_offline.push(key);
+    }
+
+    let mut table: Vec<(i64, MountedVolume)> = online_ids
+        .iter()
+        .copied()
+        .zip(mounted.iter().cloned())
+        .collect();
+    table.sort_by_key(|(_, v)| std::cmp::Reverse(v.root.len()));
+    if let Ok(mut m) = MOUNTS.write() {
+        *m = table.clone();
+    }
+    report.attributed = attribute_rows(&tx, &table)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    report.online = table.len();
+    Ok(report)
+}
+
+/// Read the OS mount table and reconcile volumes with it.
+pub fn refresh(conn: &Connection) -> Result<RefreshReport, String> {
+    let mounted = scan_mounts();
+    // an unreadable mount table comes back empty: that says nothing about the
+    // volumes, so leave their state alone rather than taking them all offline
+    if mounted.is_empty() {
+        return Ok(RefreshReport::default());
+    }
+    refresh_with(conn, &mounted)
+}
+
+/// `refresh` on its own connection, logging the outcome (startup, walks).
+pub fn refresh_logged() {
+    let res = crate::db::open_connection().and_then(|c| refresh(&c));
+    match res {
+        Ok(r) => {
+            for (key, old, new, rows) in &r.rebased {
+                eprintln!("[volumes] {} moved {} -> {} ({} rows)", key, old, new, rows);
+            }
+        }
+        Err(e) => eprintln!("[volumes] refresh failed: {}", e),
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Commands
+// -----------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+pub struct VolumeRecord {
+    pub id: i64,
+    pub key: String,
+    pub uuid: Option<String>,
+    pub label: Option<String>,
+    pub fs_type: Option<String>,
+    pub device: Option<String>,
+    pub mount_point: Option<String>,
+    pub online: bool,
+    pub first_seen_at: i64,
+    pub last_seen_at: i64,
+    pub last_mounted_at: Option<i64>,
+    pub indexed_entries: i64,
+}
+
+pub fn list_volumes(conn: &Connection) -> Result<Vec<VolumeRecord>, String> {
+    ensure_tables(conn)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT v.id, v.key, v.uuid, v.label, v.fs_type, v.device, v.mount_point, v.online,
+                    v.first_seen_at, v.last_seen_at, v.last_mounted_at,
+                    (SELECT COUNT(*) FROM files_index fi WHERE fi.volume_id = v.id)
+             FROM volumes v ORDER BY v.online DESC, v.last_seen_at DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |r| {
+            Ok(VolumeRecord {
+                id: r.get(0)?,
+                key: r.get(1)?,
+                uuid: r.get(2)?,
+                label: r.get(3)?,
+                fs_type: r.get(4)?,
+                device: r.get(5)?,
+                mount_point: r.get(6)?,
+                online: r.get::<_, i64>(7)? != 0,
+                first_seen_at: r.get(8)?,
+                last_seen_at: r.get(9)?,
+                last_mounted_at: r.get(10)?,
+                indexed_entries: r.get(11)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Admin: volumes seen so far, refreshed against the current mount table.
+#[command]
+pub fn admin_list_volumes(
+    _app: AppHandle,
+    sessionToken: Option<String>,
+    session_token: Option<String>,
+) -> Result<Vec<VolumeRecord>, String> {
+    let conn = crate::db::open_connection().map_err(|e| format!("open db: {}", e))?;
+    crate::db::init_db_schema(&conn).map_err(|e| format!("init schema: {}", e))?;
+    crate::audit::ensure_admin_session(&conn, session_token, sessionToken)?;
+    refresh(&conn)?;
+    list_volumes(&conn)
+}